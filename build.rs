@@ -0,0 +1,36 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+/// Names the config file the `embedded_config` feature embeds
+const EMBED_CONFIG_VAR: &str = "JUMPSTART_EMBED_CONFIG";
+
+/// Writes `embedded_config.rs` to `OUT_DIR` for `get_default_config_content`
+/// to include: an `include_str!` of `$JUMPSTART_EMBED_CONFIG`, relative to
+/// the crate root, or of `config.yml` in the crate root when it is unset.
+fn main() {
+    println!("cargo:rerun-if-env-changed={}", EMBED_CONFIG_VAR);
+    if env::var_os("CARGO_FEATURE_EMBEDDED_CONFIG").is_none() {
+        return;
+    }
+
+    let crate_root = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let config = crate_root.join(env::var_os(EMBED_CONFIG_VAR).unwrap_or("config.yml".into()));
+    if !config.is_file() {
+        eprintln!(
+            "Config file to embed '{}' not found; set {} to the config to embed",
+            config.display(),
+            EMBED_CONFIG_VAR
+        );
+        process::exit(1);
+    }
+    println!("cargo:rerun-if-changed={}", config.display());
+
+    let generated = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("embedded_config.rs");
+    let include = format!("include_str!({:?})\n", config.to_string_lossy());
+    if let Err(e) = fs::write(&generated, include) {
+        eprintln!("Failed to write '{}': {}", generated.display(), e);
+        process::exit(1);
+    }
+}
@@ -1,347 +1,3626 @@
-use crate::config::Config;
-use std::process::Command;
+use crate::browser::resolve_browser_executable;
+use crate::conditions::{MachineIdentity, SessionKind};
+use crate::config::{
+    self, Application, Config, Console, Enforce, GroupFailurePolicy, LaunchMethod,
+};
+use crate::desktop::Desktop;
+use crate::display::{
+    Gaps, Monitor, Rect, ShowState, placement_monitor, plan_layout, relative_target_rect,
+};
+use crate::gpu;
+use crate::identity::IdentityStore;
+use crate::matching::{
+    FoundWindow, IgnoreList, MatchCriteria, WindowCandidate, find_candidate, find_ready_candidate,
+    format_suggestions, suggest_titles,
+};
+use crate::placement_script::{self, PLACEMENT_SCRIPT_TIMEOUT, PlacementRequest};
+use crate::priority;
+use crate::readiness::{OnTimeout, wait_for_tcp};
+use crate::record::{self, PositionRecord};
+use crate::replay::{self, SessionAction};
+use crate::session::launch_in_user_session;
+use crate::undo::UndoSnapshot;
+use crate::watch::TrackedWindow;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
 #[cfg(windows)]
-use std::collections::HashMap;
+use crate::config::DisplaySelector;
+#[cfg(windows)]
+use crate::desktop::WindowsDesktop;
+#[cfg(windows)]
+use crate::identity;
+#[cfg(windows)]
+use crate::monitor::{get_monitors, monitor_of_window};
+#[cfg(windows)]
+use crate::undo;
+#[cfg(windows)]
+use crate::window::find_window;
+
+#[cfg(test)]
+use crate::mock::{MockDesktop, MockWindowsApi, WindowsApiTrait, create_mock_monitors};
+
+/// How long to wait for an application's window when no `launch_timeout_ms` is configured
+pub const DEFAULT_WINDOW_WAIT: Duration = Duration::from_secs(5);
+
+/// Interval between window searches while waiting for an application to appear
+pub const WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Pause after each launched application before starting the next one
+pub const LAUNCH_SPACING: Duration = Duration::from_secs(2);
+
+/// The timing of a launch run and how it looks for windows, so that
+/// `--safe-mode` can swap in a gentler preset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pacing {
+    /// Pause after each launched application before starting the next one
+    pub launch_spacing: Duration,
+    /// Interval between window searches while waiting for an application
+    pub poll_interval: Duration,
+    /// How long to wait for a window when the app has no `launch_timeout_ms`
+    pub window_wait: Duration,
+    /// Enumerate windows on a background thread while each app starts up.
+    /// Without it windows are enumerated on the launching thread, and the
+    /// newest enumeration is reused to find later apps' open windows.
+    pub prefetch: bool,
+    /// Honour apps' `smooth_move` and `enforce`
+    pub follow_up_moves: bool,
+}
+
+impl Pacing {
+    /// `--safe-mode`, for machines that a normal run slows to a crawl
+    pub const SAFE_MODE: Pacing = Pacing {
+        launch_spacing: Duration::from_secs(5),
+        poll_interval: Duration::from_secs(1),
+        window_wait: Duration::from_secs(15),
+        prefetch: false,
+        follow_up_moves: false,
+    };
+
+    /// How long to wait for `app`'s window to appear
+    pub fn window_wait_for(&self, app: &Application) -> Duration {
+        app.launch_timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(self.window_wait)
+    }
+}
+
+impl Default for Pacing {
+    fn default() -> Self {
+        Pacing {
+            launch_spacing: LAUNCH_SPACING,
+            poll_interval: WINDOW_POLL_INTERVAL,
+            window_wait: DEFAULT_WINDOW_WAIT,
+            prefetch: true,
+            follow_up_moves: true,
+        }
+    }
+}
+
+/// Delay before the first `--retry-run` retry; each further retry doubles it
+pub const RETRY_BASE_DELAY: Duration = Duration::from_secs(5);
+
+/// How long the shell gets to report a failed launch; an app still running
+/// after that is taken as launched
+const SHELL_LAUNCH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How much of a failed launch's stderr goes into the error message
+const STDERR_EXCERPT_BYTES: usize = 500;
+
+/// Number of intermediate moves for apps with `smooth_move`
+const SMOOTH_MOVE_STEPS: u32 = 8;
+
+/// Total duration of a smooth move
+pub const SMOOTH_MOVE_MS: u64 = 200;
+
+/// Logged when signed in over Remote Desktop, where placement follows the client's monitors
+const REMOTE_SESSION_WARNING: &str = "Running in a Remote Desktop session; the monitors are the RDP client's, so windows may not land where they do at the console";
+
+/// Outcome of launching and positioning a single application
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppOutcome {
+    Positioned,
+    /// Started without looking for its window, as asked
+    Launched,
+    LaunchFailed(String),
+    MonitorNotFound,
+    WindowNotFound,
+    /// The window did not appear within the app's `launch_timeout_ms`
+    TimedOut(Duration),
+    PositionFailed(String),
+    /// The application was not launched, e.g. because it is disabled
+    Skipped(String),
+    /// Not launched because `app` in the same abort-on-failure group failed
+    GroupFailed {
+        group: String,
+        app: String,
+    },
+    /// Not launched because `anchor`, the app it is placed relative to, was
+    /// not positioned
+    AnchorFailed(String),
+}
+
+impl AppOutcome {
+    pub fn is_failure(&self) -> bool {
+        !matches!(
+            self,
+            AppOutcome::Positioned | AppOutcome::Launched | AppOutcome::Skipped(_)
+        )
+    }
+}
+
+impl std::fmt::Display for AppOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppOutcome::Positioned => write!(f, "positioned"),
+            AppOutcome::Launched => write!(f, "launched"),
+            AppOutcome::LaunchFailed(e) => write!(f, "launch failed: {}", e),
+            AppOutcome::MonitorNotFound => write!(f, "monitor not found"),
+            AppOutcome::WindowNotFound => write!(f, "window not found"),
+            AppOutcome::TimedOut(after) => write!(f, "timed out after {} ms", after.as_millis()),
+            AppOutcome::PositionFailed(e) => write!(f, "positioning failed: {}", e),
+            AppOutcome::Skipped(reason) => write!(f, "skipped ({})", reason),
+            AppOutcome::GroupFailed { group, app } => {
+                write!(
+                    f,
+                    "not launched because {} in group '{}' failed",
+                    app, group
+                )
+            }
+            AppOutcome::AnchorFailed(anchor) => {
+                write!(
+                    f,
+                    "not launched because {}, which it is placed relative to, was not positioned",
+                    anchor
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AppResult {
+    pub name: String,
+    pub outcome: AppOutcome,
+    /// The app's window and how it was matched, when one was found
+    pub found: Option<FoundWindow>,
+}
+
+/// Per-application results of a launch run, in config order
+#[derive(Debug, Clone, Default)]
+pub struct LaunchReport {
+    pub results: Vec<AppResult>,
+    /// Windows the run put in place, for `--watch`
+    pub windows: Vec<TrackedWindow>,
+    /// The run was cancelled before every app was processed
+    pub cancelled: bool,
+    /// The config's `total_timeout_secs` ran out before every app was processed
+    pub timed_out: bool,
+}
+
+impl LaunchReport {
+    pub fn record(&mut self, name: &str, outcome: AppOutcome) {
+        self.results.push(AppResult {
+            name: name.to_string(),
+            outcome,
+            found: None,
+        });
+    }
+
+    pub fn positioned_count(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|r| r.outcome == AppOutcome::Positioned)
+            .count()
+    }
+
+    pub fn is_success(&self) -> bool {
+        !self.timed_out && self.results.iter().all(|r| !r.outcome.is_failure())
+    }
+
+    pub fn first_failure(&self) -> Option<&AppResult> {
+        self.results.iter().find(|r| r.outcome.is_failure())
+    }
+
+    /// The run's error when it must fail on the first app that wasn't
+    /// positioned
+    fn fail_fast_error(&self, options: &LaunchOptions) -> Option<String> {
+        let failed = self.first_failure().filter(|_| options.fail_fast)?;
+        Some(format!("{}: {}", failed.name, failed.outcome))
+    }
+}
+
+/// The outcome of an app whose group gives up after a failure, once another
+/// of its apps has failed earlier in the run
+fn group_failure(config: &Config, app: &Application, report: &LaunchReport) -> Option<AppOutcome> {
+    let (name, group) = config.group_of(&app.name)?;
+    if group.on_failure != GroupFailurePolicy::AbortGroup {
+        return None;
+    }
+    let failed = report
+        .results
+        .iter()
+        .find(|result| result.outcome.is_failure() && group.apps.contains(&result.name))?;
+    Some(AppOutcome::GroupFailed {
+        group: name.to_string(),
+        app: failed.name.clone(),
+    })
+}
+
+/// The window of the app that `app` is placed `relative_to`, or the outcome
+/// for `app` when that app was not positioned earlier in the run
+fn relative_anchor(app: &Application, report: &LaunchReport) -> Result<Option<usize>, AppOutcome> {
+    let Some(ref relative) = app.relative_to else {
+        return Ok(None);
+    };
+    report
+        .results
+        .iter()
+        .find(|result| result.name == relative.app && result.outcome == AppOutcome::Positioned)
+        .and_then(|result| result.found.as_ref())
+        .map(|found| Some(found.hwnd))
+        .ok_or_else(|| AppOutcome::AnchorFailed(relative.app.clone()))
+}
+
+/// Live progress events emitted while a launch run is in progress.
+/// `index` refers to the application's position in `Config::applications`.
+#[derive(Debug, Clone)]
+pub enum LaunchProgress {
+    Started { index: usize, name: String },
+    Finished { index: usize, result: AppResult },
+    Completed(Result<LaunchReport, String>),
+}
+
+/// Per-application state shown by the interactive front-ends during a run
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum AppStatus {
+    #[default]
+    Idle,
+    Launching,
+    Done(AppOutcome),
+}
+
+impl AppStatus {
+    /// Updates `statuses` (indexed like `Config::applications`) from a progress event
+    pub fn apply(statuses: &mut [AppStatus], event: &LaunchProgress) {
+        match event {
+            LaunchProgress::Started { index, .. } => {
+                if let Some(status) = statuses.get_mut(*index) {
+                    *status = AppStatus::Launching;
+                }
+            }
+            LaunchProgress::Finished { index, result } => {
+                if let Some(status) = statuses.get_mut(*index) {
+                    *status = AppStatus::Done(result.outcome.clone());
+                }
+            }
+            LaunchProgress::Completed(_) => {}
+        }
+    }
+}
+
+/// Options controlling a launch run
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    /// Receives a `LaunchProgress` event as each application starts and finishes
+    pub progress: Option<Sender<LaunchProgress>>,
+    /// Position an app's existing window instead of launching it again
+    pub reposition_existing: bool,
+    /// Only position windows that are already open, never launching anything.
+    /// When several windows match, the largest is positioned.
+    pub snap_only: bool,
+    /// JSONL file every window move is appended to, e.g. for checking a
+    /// layout on a CI runner
+    pub record: Option<PathBuf>,
+    /// JSONL file every launch and move is appended to with its time, for
+    /// `jumpstart replay`
+    pub record_session: Option<PathBuf>,
+    /// Stop at the first app that isn't positioned and fail the run
+    pub fail_fast: bool,
+    /// Run even when the config's `expected_monitors` doesn't match
+    pub ignore_monitor_mismatch: bool,
+    /// Timing and window enumeration, e.g. `Pacing::SAFE_MODE`
+    pub pacing: Pacing,
+    /// Set from another thread, e.g. on Ctrl+C, to stop before the next app
+    /// and move the windows the run already moved back where they were
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl LaunchOptions {
+    fn cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::SeqCst))
+    }
+
+    fn emit(&self, event: LaunchProgress) {
+        if let Some(ref progress) = self.progress {
+            // The receiver going away (e.g. the UI closed) must not abort the run
+            let _ = progress.send(event);
+        }
+    }
+
+    fn record_position(&self, name: &str, hwnd: usize, rect: Rect) {
+        let Some(ref path) = self.record else {
+            return;
+        };
+        let record = PositionRecord {
+            app: name.to_string(),
+            hwnd,
+            rect,
+        };
+        // Recording is a side channel; failing to write must not stop the move
+        if let Err(e) = record::append(path, &record) {
+            warn!("{}", e);
+        }
+    }
+
+    fn record_step(&self, action: SessionAction) {
+        let Some(ref path) = self.record_session else {
+            return;
+        };
+        if let Err(e) = replay::record_session(path, action) {
+            warn!("{}", e);
+        }
+    }
+
+    /// Fails unless the connected monitors are the ones the config expects
+    fn check_monitors(&self, config: &Config, monitors: &[Monitor]) -> Result<(), String> {
+        match config.check_monitor_count(monitors) {
+            Err(e) if !self.ignore_monitor_mismatch => Err(format!(
+                "{}; run with --ignore-monitor-mismatch to continue anyway",
+                e
+            )),
+            Err(e) => {
+                warn!("{}", e);
+                Ok(())
+            }
+            Ok(()) => Ok(()),
+        }
+    }
+
+    fn finish(&self, index: usize, report: &mut LaunchReport, name: &str, outcome: AppOutcome) {
+        self.finish_found(index, report, name, outcome, None);
+    }
+
+    /// Like `finish`, also recording the window the app was matched to
+    fn finish_found(
+        &self,
+        index: usize,
+        report: &mut LaunchReport,
+        name: &str,
+        outcome: AppOutcome,
+        found: Option<FoundWindow>,
+    ) {
+        report.record(name, outcome);
+        if let Some(result) = report.results.last_mut() {
+            result.found = found;
+            self.emit(LaunchProgress::Finished {
+                index,
+                result: result.clone(),
+            });
+        }
+    }
+}
+
+/// A started application, as far as its launcher knows
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LaunchHandle {
+    /// ID of the app's own process, when the launcher started it directly
+    pub process_id: Option<u32>,
+}
+
+/// How applications are started. Finding and positioning their windows is
+/// the same whichever launcher started them.
+pub trait Launcher {
+    fn launch(&self, app: &Application) -> Result<LaunchHandle, String>;
+}
+
+/// The default launcher: executables directly, anything else through the
+/// shell, and `user_session` apps on the desktop of the console user
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShellLauncher {
+    /// Start apps minimized where the launch method allows, so they don't
+    /// take focus
+    pub minimized: bool,
+}
+
+impl ShellLauncher {
+    pub fn for_config(config: &Config) -> Self {
+        ShellLauncher {
+            minimized: config.no_activate,
+        }
+    }
+}
+
+impl Launcher for ShellLauncher {
+    fn launch(&self, app: &Application) -> Result<LaunchHandle, String> {
+        let mut command = LaunchCommand {
+            minimized: self.minimized,
+            ..resolve_launch_command(app)?
+        };
+        if let Some(preference) = app.prefer_gpu {
+            command.env = gpu::prepare(&command.program, preference);
+        }
+        let process_id = if app.user_session {
+            let pid = launch_in_user_session(&command)?;
+            info!(
+                "Launched {} in the user session as process {}",
+                app.name, pid
+            );
+            Some(pid)
+        } else {
+            launch_application(&command)?
+        };
+        if let Some(priority) = app.priority {
+            match process_id {
+                Some(pid) => {
+                    if let Err(e) = priority::set_priority(pid, priority) {
+                        warn!("Could not set {}'s priority: {}", app.name, e);
+                    }
+                }
+                None => warn!(
+                    "Could not set {}'s priority: it was started through the shell, so its process isn't known",
+                    app.name
+                ),
+            }
+        }
+        Ok(LaunchHandle { process_id })
+    }
+}
+
+/// Runs `launch_and_position_applications` on a background thread, reporting
+/// progress (and finally `LaunchProgress::Completed`) over `progress`.
+pub fn spawn_launch(config: Config, progress: Sender<LaunchProgress>) {
+    thread::spawn(move || {
+        let options = LaunchOptions {
+            progress: Some(progress),
+            ..Default::default()
+        };
+        let launcher = ShellLauncher::for_config(&config);
+        let result = launch_and_position_applications(&config, &launcher, &options);
+        options.emit(LaunchProgress::Completed(result));
+    });
+}
+
+/// Starts every enabled application without waiting for or moving any
+/// windows, for when the user will arrange them by hand
+pub fn launch_only(
+    config: &Config,
+    launcher: &impl Launcher,
+    options: &LaunchOptions,
+) -> LaunchReport {
+    let machine = MachineIdentity::current();
+    let mut report = LaunchReport::default();
+    for (index, app) in config.applications.iter().enumerate() {
+        if let Some(reason) = app.skip_reason(&machine) {
+            info!("Skipping {}: {}", app.name, reason);
+            options.finish(index, &mut report, &app.name, AppOutcome::Skipped(reason));
+            continue;
+        }
+        info!("Launching {} without positioning it...", app.name);
+        options.emit(LaunchProgress::Started {
+            index,
+            name: app.name.clone(),
+        });
+        let outcome = match launcher.launch(app) {
+            Ok(_) => AppOutcome::Launched,
+            Err(e) => {
+                error!("Failed to launch {}: {}", app.name, e);
+                AppOutcome::LaunchFailed(e)
+            }
+        };
+        options.finish(index, &mut report, &app.name, outcome);
+    }
+    report
+}
+
+/// Runs `launch_only` on a background thread, reporting progress like
+/// `spawn_launch`
+pub fn spawn_launch_only(config: Config, progress: Sender<LaunchProgress>) {
+    thread::spawn(move || {
+        let options = LaunchOptions {
+            progress: Some(progress),
+            ..Default::default()
+        };
+        let report = launch_only(&config, &ShellLauncher::for_config(&config), &options);
+        options.emit(LaunchProgress::Completed(Ok(report)));
+    });
+}
+
+/// Returns the window title fragment to search for: the app's `window_title`
+/// when set. Otherwise well-known apps whose window titles differ from their
+/// display names are mapped, and others use their name.
+pub fn search_title_for(app: &Application) -> String {
+    if let Some(ref title) = app.window_title {
+        return title.clone();
+    }
+    match app.name.as_str() {
+        "Teams" => "teams",
+        "Outlook" => "outlook",
+        "Slack" => "slack",
+        "Notion" => "notion",
+        name => name,
+    }
+    .to_string()
+}
+
+/// What the app's window must match: its `match` criteria, or else a
+/// title containing `search_title_for`
+pub fn criteria_for(app: &Application) -> MatchCriteria {
+    app.match_criteria
+        .clone()
+        .unwrap_or_else(|| MatchCriteria::title(&search_title_for(app)))
+}
+
+/// The app's `placement_script`, or else the config's. A `center_fixed`
+/// app is placed by its window's own size instead.
+fn placement_script_for<'a>(config: &'a Config, app: &'a Application) -> Option<&'a str> {
+    app.placement_script
+        .as_deref()
+        .or(config.placement_script.as_deref())
+        .filter(|_| !app.center_fixed)
+}
+
+/// Where the app's placement script puts its window, keeping the `planned`
+/// rectangle when there is none or it fails. The script may not run past
+/// the run's `deadline`.
+fn scripted_rect(
+    config: &Config,
+    app: &Application,
+    monitor: &Monitor,
+    monitors: &[Monitor],
+    planned: Rect,
+    deadline: Option<Instant>,
+) -> Rect {
+    let Some(script) = placement_script_for(config, app) else {
+        return planned;
+    };
+    let request = PlacementRequest::new(app, monitor, monitors, planned);
+    let timeout = within_deadline(PLACEMENT_SCRIPT_TIMEOUT, deadline);
+    match placement_script::run(script, &request, monitors, timeout) {
+        Ok(rect) => {
+            info!(
+                "Placement script put {} at ({}, {}) with size {}x{}",
+                app.name, rect.x, rect.y, rect.width, rect.height
+            );
+            rect
+        }
+        Err(e) => {
+            warn!("{}: {}; keeping its planned position", app.name, e);
+            planned
+        }
+    }
+}
+
+/// The title to suggest similar windows for when `search` matched nothing
+fn search_hint<'a>(app: &'a Application, search: &'a MatchCriteria) -> &'a str {
+    search.title_hint().unwrap_or(&app.name)
+}
+
+/// When a run of `config` must stop, per its `total_timeout_secs`
+fn run_deadline(config: &Config) -> Option<Instant> {
+    config
+        .total_timeout_secs
+        .map(|secs| Instant::now() + Duration::from_secs(secs))
+}
+
+/// `timeout`, cut short so that waiting can't outlast the run's `deadline`
+fn within_deadline(timeout: Duration, deadline: Option<Instant>) -> Duration {
+    deadline.map_or(timeout, |deadline| {
+        timeout.min(deadline.saturating_duration_since(Instant::now()))
+    })
+}
+
+/// Repeatedly calls `probe` until it yields a value or `timeout` elapses.
+/// The probe always runs at least once, even with a zero timeout.
+pub fn poll_until<T>(
+    timeout: Duration,
+    interval: Duration,
+    mut probe: impl FnMut() -> Option<T>,
+) -> Option<T> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(value) = probe() {
+            return Some(value);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return None;
+        }
+        thread::sleep(interval.min(deadline - now));
+    }
+}
+
+/// Checks `enforce.retries` times, `enforce.interval_ms` apart, that the window
+/// still has the `target` rectangle and show state, putting it back with
+/// `apply` whenever `current` finds it moved, maximized or minimized. Stops
+/// early once the run's `deadline` has passed. Returns how many times it had
+/// to be put back.
+pub fn enforce_position(
+    target: (Rect, ShowState),
+    enforce: &Enforce,
+    deadline: Option<Instant>,
+    mut current: impl FnMut() -> Option<(Rect, ShowState)>,
+    mut apply: impl FnMut((Rect, ShowState)) -> Result<(), String>,
+    mut sleep: impl FnMut(Duration),
+) -> Result<u32, String> {
+    let mut corrections = 0;
+    for _ in 0..enforce.retries {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            debug!("total_timeout_secs has passed; no longer holding the window in place");
+            break;
+        }
+        sleep(within_deadline(
+            Duration::from_millis(enforce.interval_ms),
+            deadline,
+        ));
+        match current() {
+            Some((rect, state)) if (rect, state) != target => {
+                debug!(
+                    "Window moved to ({}, {}) with size {}x{} ({:?}); moving it back",
+                    rect.x, rect.y, rect.width, rect.height, state
+                );
+                apply(target)?;
+                corrections += 1;
+            }
+            Some(_) => {}
+            // The window is gone; nothing left to enforce
+            None => break,
+        }
+    }
+    Ok(corrections)
+}
+
+/// Whether an already open window can be left where it is, because it is on
+/// its `target` monitor and the config doesn't ask to reposition it anyway
+pub fn leave_in_place(
+    config: &Config,
+    already_open: bool,
+    current: Option<&Monitor>,
+    target: &Monitor,
+) -> bool {
+    already_open
+        && !config.reposition_if_same_monitor
+        && current.is_some_and(|monitor| monitor.index == target.index)
+}
+
+/// Orders windows by their `z_order`, top first. Windows with the same value
+/// keep their config order.
+pub fn stacking_order<T: Copy>(layered: &[(i32, T)]) -> Vec<T> {
+    let mut sorted = layered.to_vec();
+    sorted.sort_by_key(|&(z_order, _)| std::cmp::Reverse(z_order));
+    sorted.into_iter().map(|(_, window)| window).collect()
+}
+
+/// Runs a launch until every app is positioned or skipped, retrying the whole
+/// run up to `retries` more times with exponential backoff. Each run uses
+/// `options`, but retries reposition windows that are already open instead
+/// of launching the apps again.
+pub fn launch_with_retries(
+    retries: u32,
+    base_delay: Duration,
+    options: &LaunchOptions,
+    mut run: impl FnMut(&LaunchOptions) -> Result<LaunchReport, String>,
+    mut sleep: impl FnMut(Duration),
+) -> Result<LaunchReport, String> {
+    let mut attempt = 0;
+    loop {
+        let options = LaunchOptions {
+            reposition_existing: options.reposition_existing || attempt > 0,
+            ..options.clone()
+        };
+        let result = run(&options);
+        match result {
+            Ok(ref report) => info!(
+                "Attempt {} of {}: {} of {} applications positioned",
+                attempt + 1,
+                retries + 1,
+                report.positioned_count(),
+                report.results.len()
+            ),
+            Err(ref e) => warn!("Attempt {} of {} failed: {}", attempt + 1, retries + 1, e),
+        }
+        // Another attempt would outlast the time budget or the user's patience
+        if matches!(result, Ok(ref report) if report.is_success() || report.cancelled || report.timed_out)
+            || attempt == retries
+        {
+            return result;
+        }
+
+        let delay = base_delay.saturating_mul(2u32.saturating_pow(attempt));
+        info!("Retrying in {} s", delay.as_secs_f32());
+        sleep(delay);
+        attempt += 1;
+    }
+}
+
+/// The connected monitors in display-number order
+#[cfg(windows)]
+pub fn monitors() -> Vec<Monitor> {
+    get_monitors()
+}
+
+/// Monitors can only be enumerated on Windows
+#[cfg(not(windows))]
+pub fn monitors() -> Vec<Monitor> {
+    Vec::new()
+}
+
+/// Blocks until the app's `wait_for` service is reachable, but not past the
+/// run's `deadline`. Returns the outcome to record instead of launching when
+/// it never became ready.
+fn wait_for_dependency(app: &Application, deadline: Option<Instant>) -> Option<AppOutcome> {
+    let wait_for = app.wait_for.as_ref()?;
+    info!("Waiting for {} before launching {}", wait_for.tcp, app.name);
+    let timeout = within_deadline(wait_for.timeout(), deadline);
+    let Err(e) = wait_for_tcp(&wait_for.tcp, timeout) else {
+        return None;
+    };
+
+    match wait_for.on_timeout {
+        OnTimeout::Fail => {
+            error!("Not launching {}: {}", app.name, e);
+            Some(AppOutcome::LaunchFailed(e))
+        }
+        OnTimeout::Skip => {
+            warn!("Skipping {}: {}", app.name, e);
+            Some(AppOutcome::Skipped(e))
+        }
+        OnTimeout::Launch => {
+            warn!("Launching {} anyway: {}", app.name, e);
+            None
+        }
+    }
+}
+
+/// Everything needed to start an application's process
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub working_dir: Option<String>,
+    pub console: Console,
+    /// Ask for the app's window to start minimized and inactive. Only shell
+    /// and user session launches can; executables started directly can't.
+    pub minimized: bool,
+    pub method: LaunchMethod,
+    /// Extra environment variables, e.g. from `prefer_gpu`. Launches through
+    /// `ShellExecuteEx` or in the user session don't pass them on.
+    pub env: Vec<(String, String)>,
+}
+
+impl LaunchCommand {
+    /// Whether `program` is an `.exe` that can be started directly, which
+    /// gives us its process ID. Anything else (shortcuts, documents, URIs,
+    /// store apps) needs the shell.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn is_executable(&self) -> bool {
+        Path::new(&self.program)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"))
+    }
+}
+
+impl std::fmt::Display for LaunchCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        if let Some(ref dir) = self.working_dir {
+            write!(f, " (in {})", dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the program and arguments to run for an application. Browser
+/// apps use `executable` when given, otherwise the installed browser is located.
+pub fn resolve_launch_command(app: &Application) -> Result<LaunchCommand, String> {
+    let (program, mut args) = match app.browser {
+        None => (app.executable.clone(), Vec::new()),
+        Some(ref browser) => {
+            let program = if app.executable.is_empty() {
+                resolve_browser_executable(browser.kind)
+                    .ok_or_else(|| format!("Could not find {:?} for {}", browser.kind, app.name))?
+                    .to_string_lossy()
+                    .into_owned()
+            } else {
+                app.executable.clone()
+            };
+            (program, browser.args())
+        }
+    };
+    args.extend(app.args.iter().cloned());
+    args.extend(app.open_file.iter().cloned());
+
+    Ok(LaunchCommand {
+        program,
+        args,
+        working_dir: app.working_dir.clone(),
+        console: app.console,
+        minimized: false,
+        method: app.launch_method,
+        env: Vec::new(),
+    })
+}
+
+/// The command line an application would be launched with, for dry runs
+fn describe_launch(app: &Application) -> String {
+    match resolve_launch_command(app) {
+        Ok(command) => command.to_string(),
+        Err(e) => format!("<{}>", e),
+    }
+}
+
+/// Runs `cmd` for up to `timeout`, capturing its stderr unless it should
+/// write to jumpstart's console. Returns `None` if it is still running by
+/// then, otherwise its exit status and the start of its stderr.
+fn run_with_timeout(
+    cmd: &mut Command,
+    timeout: Duration,
+    capture: bool,
+) -> io::Result<Option<(ExitStatus, String)>> {
+    if capture {
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+    }
+    let mut child = cmd.spawn()?;
+
+    // Read on a thread so neither a chatty child nor one that keeps the pipe
+    // open can block the launcher; the rest is drained so the child never stalls
+    let (sender, receiver) = mpsc::channel();
+    if let Some(mut stderr) = child.stderr.take() {
+        thread::spawn(move || {
+            let mut excerpt = Vec::new();
+            let _ = (&mut stderr)
+                .take(STDERR_EXCERPT_BYTES as u64)
+                .read_to_end(&mut excerpt);
+            let _ = sender.send(excerpt);
+            let _ = io::copy(&mut stderr, &mut io::sink());
+        });
+    }
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if start.elapsed() >= timeout {
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    // Apps started by the shell may inherit the pipe, so don't wait for EOF long
+    let excerpt = receiver
+        .recv_timeout(Duration::from_millis(200))
+        .unwrap_or_default();
+    Ok(Some((
+        status,
+        String::from_utf8_lossy(&excerpt).trim().to_string(),
+    )))
+}
+
+/// The error for a launch command that exited with `status`, quoting its stderr
+fn launch_failure(status: ExitStatus, stderr: &str) -> String {
+    if stderr.is_empty() {
+        format!("Application failed to start with status: {}", status)
+    } else {
+        format!(
+            "Application failed to start with status: {}: {}",
+            status, stderr
+        )
+    }
+}
+
+/// The `cmd` arguments that hand a program to `start`. Without `/B`, `start`
+/// opens console programs in a new window; with it they share cmd's console.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn shell_start_args(console: Console) -> &'static [&'static str] {
+    match console {
+        Console::New => &["/C", "start", ""],
+        Console::Inherit | Console::None => &["/C", "start", "", "/B"],
+    }
+}
 
+/// Process creation flags giving a program the requested console
 #[cfg(windows)]
-use std::thread;
+fn console_creation_flags(console: Console) -> u32 {
+    use winapi::um::winbase::{CREATE_NEW_CONSOLE, DETACHED_PROCESS};
+    match console {
+        Console::New => CREATE_NEW_CONSOLE,
+        Console::Inherit => 0,
+        Console::None => DETACHED_PROCESS,
+    }
+}
 
+/// Launches the application and returns its process ID when it is known.
+/// By default executables are started directly; other targets go through
+/// `start`, whose child is `cmd` rather than the app, so no ID is returned
+/// for them. The app's `launch_method` overrides this choice.
 #[cfg(windows)]
-use std::time::Duration;
+pub fn launch_application(command: &LaunchCommand) -> Result<Option<u32>, String> {
+    info!("Attempting to launch: {}", command);
+    let executable = &command.program;
+    let failed = |e: std::io::Error| {
+        error!("Failed to launch application '{}': {}", executable, e);
+        format!("Failed to launch application: {}", e)
+    };
+
+    match command.method {
+        LaunchMethod::Direct => return launch_direct(command).map(Some).map_err(failed),
+        LaunchMethod::ShellExecute => return launch_shell_execute(command).map_err(failed),
+        LaunchMethod::Auto if command.is_executable() => match launch_direct(command) {
+            Ok(pid) => return Ok(Some(pid)),
+            // Not on PATH, but `start` also finds apps registered under App Paths
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                debug!("{} not found directly, trying the shell", executable);
+            }
+            Err(e) => return Err(failed(e)),
+        },
+        LaunchMethod::Auto | LaunchMethod::Shell => {}
+    }
+    launch_with_start(command)
+}
 
+/// Starts an executable as a child process, which gives its process ID
 #[cfg(windows)]
-use crate::monitor::{calculate_window_position, get_monitor_by_number, get_monitors};
+fn launch_direct(command: &LaunchCommand) -> std::io::Result<u32> {
+    use std::os::windows::process::CommandExt;
 
+    let mut cmd = Command::new(&command.program);
+    cmd.args(&command.args)
+        .envs(command.env.clone())
+        .creation_flags(console_creation_flags(command.console));
+    if let Some(ref dir) = command.working_dir {
+        cmd.current_dir(dir);
+    }
+    let child = cmd.spawn()?;
+    info!(
+        "Successfully launched: {} (process {})",
+        command.program,
+        child.id()
+    );
+    Ok(child.id())
+}
+
+/// Opens the target with `ShellExecuteEx`, which handles file associations
+/// and URIs like `start` does but reports the process it started, if any
 #[cfg(windows)]
-use crate::window::{find_window_by_title, position_window};
+fn launch_shell_execute(command: &LaunchCommand) -> std::io::Result<Option<u32>> {
+    use crate::session::quote_arg;
+    use std::io;
+    use std::ptr::null;
+    use widestring::U16CString;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::GetProcessId;
+    use winapi::um::shellapi::{SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW, ShellExecuteExW};
+    use winapi::um::winuser::{SW_SHOWMINNOACTIVE, SW_SHOWNORMAL};
 
-#[cfg(test)]
-use crate::config::Application;
-#[cfg(test)]
-use crate::mock::{MockWindowsApi, WindowsApiTrait, create_mock_monitors, create_mock_window_map};
+    let wide = |text: &str| {
+        U16CString::from_str(text).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    };
+    let file = wide(&command.program)?;
+    let arguments: Vec<String> = command.args.iter().map(|arg| quote_arg(arg)).collect();
+    let parameters = wide(&arguments.join(" "))?;
+    let directory = command.working_dir.as_deref().map(wide).transpose()?;
+
+    let mut info: SHELLEXECUTEINFOW = unsafe { std::mem::zeroed() };
+    info.cbSize = std::mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+    info.fMask = SEE_MASK_NOCLOSEPROCESS;
+    info.lpFile = file.as_ptr();
+    info.lpParameters = parameters.as_ptr();
+    info.lpDirectory = directory.as_ref().map_or(null(), |dir| dir.as_ptr());
+    info.nShow = if command.minimized {
+        SW_SHOWMINNOACTIVE
+    } else {
+        SW_SHOWNORMAL
+    };
+    if unsafe { ShellExecuteExW(&mut info) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // Handing a document or URI to an app that is already running starts no process
+    if info.hProcess.is_null() {
+        info!("Successfully launched: {}", command.program);
+        return Ok(None);
+    }
+    let pid = unsafe { GetProcessId(info.hProcess) };
+    unsafe { CloseHandle(info.hProcess) };
+    info!(
+        "Successfully launched: {} (process {})",
+        command.program, pid
+    );
+    Ok((pid != 0).then_some(pid))
+}
 
+/// Starts the target through `cmd /C start`, which resolves documents,
+/// shortcuts, URIs and App Paths entries
 #[cfg(windows)]
-pub fn launch_application(executable: &str) -> Result<(), String> {
-    info!("Attempting to launch: {}", executable);
-
-    // Launch the application using shell execute with DETACHED_PROCESS flag
-    // Using cmd /C start with /B flag to run without creating a new window
-    let status = Command::new("cmd")
-        .args(["/C", "start", "", "/B", executable])
-        .status()
-        .map_err(|e| {
-            error!("Failed to launch application '{}': {}", executable, e);
-            format!("Failed to launch application: {}", e)
-        })?;
-
-    if !status.success() {
-        let error_msg = format!("Application failed to start with status: {}", status);
+fn launch_with_start(command: &LaunchCommand) -> Result<Option<u32>, String> {
+    use std::os::windows::process::CommandExt;
+    use winapi::um::winbase::DETACHED_PROCESS;
+
+    let executable = &command.program;
+    // A program started with `/B` shares cmd's console, so a detached cmd
+    // leaves it without one
+    let mut cmd = Command::new("cmd");
+    cmd.args(shell_start_args(command.console));
+    if command.minimized {
+        cmd.arg("/MIN");
+    }
+    cmd.arg(executable)
+        .args(&command.args)
+        .envs(command.env.clone());
+    if command.console == Console::None {
+        cmd.creation_flags(DETACHED_PROCESS);
+    }
+    if let Some(ref dir) = command.working_dir {
+        cmd.current_dir(dir);
+    }
+    let capture = command.console != Console::Inherit;
+    let exit = run_with_timeout(&mut cmd, SHELL_LAUNCH_TIMEOUT, capture).map_err(|e| {
+        error!("Failed to launch application '{}': {}", executable, e);
+        format!("Failed to launch application: {}", e)
+    })?;
+
+    if let Some((status, stderr)) = exit
+        && !status.success()
+    {
+        let error_msg = launch_failure(status, &stderr);
         error!("Failed to launch '{}': {}", executable, error_msg);
         return Err(error_msg);
     }
 
     info!("Successfully launched: {}", executable);
 
-    Ok(())
+    Ok(None)
 }
 
-#[cfg(not(windows))]
-#[allow(dead_code)]
-pub fn launch_application(executable: &str) -> Result<(), String> {
-    // Try to launch the application using standard shell commands
-    let output = Command::new("sh")
-        .args(["-c", executable])
-        .output()
-        .map_err(|e| format!("Failed to launch application: {}", e))?;
+#[cfg(not(windows))]
+pub fn launch_application(command: &LaunchCommand) -> Result<Option<u32>, String> {
+    match command.method {
+        LaunchMethod::Direct => {
+            let mut cmd = Command::new(&command.program);
+            cmd.args(&command.args).envs(command.env.clone());
+            if let Some(ref dir) = command.working_dir {
+                cmd.current_dir(dir);
+            }
+            let child = cmd
+                .spawn()
+                .map_err(|e| format!("Failed to launch application: {}", e))?;
+            return Ok(Some(child.id()));
+        }
+        LaunchMethod::ShellExecute => {
+            return Err("The shellexecute launch method is only supported on Windows".to_string());
+        }
+        LaunchMethod::Auto | LaunchMethod::Shell => {}
+    }
+
+    // Try to launch the application using standard shell commands; extra
+    // arguments are passed positionally so they need no quoting
+    let script = if command.args.is_empty() {
+        command.program.clone()
+    } else {
+        format!("{} \"$@\"", command.program)
+    };
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", &script, "sh"])
+        .args(&command.args)
+        .envs(command.env.clone());
+    if let Some(ref dir) = command.working_dir {
+        cmd.current_dir(dir);
+    }
+    let capture = command.console != Console::Inherit;
+    let exit = run_with_timeout(&mut cmd, SHELL_LAUNCH_TIMEOUT, capture)
+        .map_err(|e| format!("Failed to launch application: {}", e))?;
+
+    if let Some((status, stderr)) = exit
+        && !status.success()
+    {
+        return Err(launch_failure(status, &stderr));
+    }
+
+    // The shell's process is not the app's, so there is no process to report
+    Ok(None)
+}
+
+/// Launches and positions every application of `config` on the real desktop,
+/// remembering the positioned windows for the next run and where they were
+/// for `--undo`
+#[cfg(windows)]
+pub fn launch_and_position_applications(
+    config: &Config,
+    launcher: &dyn Launcher,
+    options: &LaunchOptions,
+) -> Result<LaunchReport, String> {
+    let identity_path = identity::store_path();
+    let mut identities = IdentityStore::load_from(&identity_path);
+    let remembered_before = identities.clone();
+    let mut snapshot = UndoSnapshot::default();
+
+    let result = launch_and_position_on(
+        config,
+        launcher,
+        &WindowsDesktop,
+        options,
+        &mut identities,
+        &mut snapshot,
+    );
+
+    if identities != remembered_before
+        && let Err(e) = identities.save_to(&identity_path)
+    {
+        warn!("Failed to remember the positioned windows: {}", e);
+    }
+    // A cancelled run moved its windows back, so the previous snapshot still
+    // describes the desktop; keep it too if this run didn't move anything
+    let cancelled = matches!(result, Ok(ref report) if report.cancelled);
+    if !cancelled
+        && !snapshot.windows.is_empty()
+        && let Err(e) = snapshot.save_to(&undo::snapshot_path())
+    {
+        warn!("Failed to save undo snapshot: {}", e);
+    }
+    result
+}
+
+/// The launch run itself, starting apps with `launcher` and finding and
+/// moving their windows on `desktop`. Windows are preferred when they look
+/// like the ones in `identities`, which is updated with the windows
+/// positioned now, and where each window was before it moved is added to
+/// `snapshot`.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn launch_and_position_on(
+    config: &Config,
+    launcher: &(impl Launcher + ?Sized),
+    desktop: &impl Desktop,
+    options: &LaunchOptions,
+    identities: &mut IdentityStore,
+    snapshot: &mut UndoSnapshot,
+) -> Result<LaunchReport, String> {
+    // Get available monitors
+    let monitors = desktop.monitors();
+    info!("Found {} monitors", monitors.len());
+    options.check_monitors(config, &monitors)?;
+    let plan = plan_layout(config, &monitors);
+
+    let mut report = LaunchReport::default();
+    // Positioned windows with a `z_order`, restacked once all are in place
+    let mut layered = Vec::new();
+    // Handed focus back at the end, in case a launched app took it anyway
+    let focused = config
+        .no_activate
+        .then(|| desktop.foreground_window())
+        .flatten();
+    let ignore = config.ignore_list();
+
+    let machine = MachineIdentity::current();
+    if machine.session == SessionKind::Remote {
+        warn!("{}", REMOTE_SESSION_WARNING);
+    }
+
+    // Launch and position each application
+    let deadline = run_deadline(config);
+    let pacing = options.pacing;
+    // Without prefetching, the newest enumeration (cloaked windows included),
+    // which later apps reuse to find their open windows
+    let mut enumerated = None;
+    for (index, app) in config.applications.iter().enumerate() {
+        if options.cancelled() {
+            warn!("Cancelled; not launching the remaining applications");
+            report.cancelled = true;
+            break;
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            warn!("total_timeout_secs has passed; not launching the remaining applications");
+            report.timed_out = true;
+            break;
+        }
+        if options.fail_fast && report.first_failure().is_some() {
+            warn!("Not launching the remaining applications after a failure");
+            break;
+        }
+        if let Some(reason) = app.skip_reason(&machine) {
+            info!("Skipping {}: {}", app.name, reason);
+            options.finish(index, &mut report, &app.name, AppOutcome::Skipped(reason));
+            continue;
+        }
+        if let Some(outcome) = group_failure(config, app, &report) {
+            warn!("{}: {}", app.name, outcome);
+            options.finish(index, &mut report, &app.name, outcome);
+            continue;
+        }
+        let anchor = match relative_anchor(app, &report) {
+            Ok(anchor) => anchor,
+            Err(outcome) => {
+                error!("{}: {}", app.name, outcome);
+                options.finish(index, &mut report, &app.name, outcome);
+                continue;
+            }
+        };
+
+        if options.snap_only {
+            info!("Snapping {}...", app.name);
+        } else {
+            info!("Launching {}...", app.name);
+        }
+        options.emit(LaunchProgress::Started {
+            index,
+            name: app.name.clone(),
+        });
+
+        // Try to find the window by title
+        let search = criteria_for(app);
+
+        let mut existing = None;
+        let mut launched_process = None;
+        let already_open = options.snap_only
+            || (options.reposition_existing
+                && if pacing.prefetch {
+                    let windows = desktop.enumerate_windows(false);
+                    desktop
+                        .find_in_windows(&windows, &search, None, &ignore, None)
+                        .is_some()
+                } else {
+                    let windows = cached_windows(desktop, &mut enumerated, app);
+                    desktop
+                        .find_in_windows(&windows, &search, None, &ignore, None)
+                        .is_some()
+                });
+        if options.snap_only {
+            let windows = if pacing.prefetch {
+                desktop.enumerate_windows(app.include_cloaked)
+            } else {
+                cached_windows(desktop, &mut enumerated, app)
+            };
+            existing = desktop.find_largest_in_windows(&windows, &search, &ignore);
+            if existing.is_none() {
+                warn!("{} is not running; not launching it", app.name);
+                options.finish(index, &mut report, &app.name, AppOutcome::WindowNotFound);
+                continue;
+            }
+        } else if already_open {
+            info!("{} is already running; repositioning its window", app.name);
+        } else {
+            if let Some(outcome) = wait_for_dependency(app, deadline) {
+                options.finish(index, &mut report, &app.name, outcome);
+                continue;
+            }
+
+            if let Some(file) = app.missing_open_file() {
+                warn!("File to open for {} does not exist: {}", app.name, file);
+            }
+
+            // Launch the application
+            match launcher.launch(app) {
+                Ok(handle) => launched_process = handle.process_id,
+                Err(e) => {
+                    error!("Failed to launch {}: {}", app.name, e);
+                    options.finish(index, &mut report, &app.name, AppOutcome::LaunchFailed(e));
+                    continue;
+                }
+            }
+            options.record_step(SessionAction::Launch {
+                app: Box::new(app.clone()),
+            });
+        }
+
+        // Enumerate windows in the background while the app starts up, so a
+        // fresh list is ready by the time we search it
+        let prefetch = (existing.is_none() && pacing.prefetch)
+            .then(|| desktop.prefetch_windows(pacing.poll_interval, app.include_cloaked))
+            .flatten();
+
+        // Get the target monitor
+        let planned = &plan[index];
+        let assigned = &planned.display;
+        if app.display.is_none() && config.auto_distribute {
+            info!("Assigned {} to display {}", app.name, assigned);
+        }
+        let (Some(monitor), Some(rect)) =
+            (placement_monitor(app, assigned, &monitors), planned.rect)
+        else {
+            error!("Monitor {} not found for {}", assigned, app.name);
+            options.finish(index, &mut report, &app.name, AppOutcome::MonitorNotFound);
+            if !options.snap_only {
+                thread::sleep(within_deadline(pacing.launch_spacing, deadline));
+            }
+            continue;
+        };
+        // Beside where the anchor actually landed rather than where it was planned
+        let rect = match (
+            anchor.and_then(|hwnd| desktop.window_rect(hwnd)),
+            &app.relative_to,
+        ) {
+            (Some(anchor), Some(relative)) => {
+                match relative_target_rect(
+                    monitor,
+                    app,
+                    relative,
+                    anchor,
+                    Gaps::from(config).for_app(app),
+                ) {
+                    (rect, Ok(_)) => rect,
+                    (_, Err(e)) => {
+                        warn!("{}: {}; keeping its planned position", app.name, e);
+                        rect
+                    }
+                }
+            }
+            _ => rect,
+        };
+        let rect = scripted_rect(config, app, monitor, &monitors, rect, deadline);
+
+        let timeout = within_deadline(pacing.window_wait_for(app), deadline);
+        let mut last_seen = Vec::new();
+        let window = match existing {
+            Some(found) => Some(found),
+            None => {
+                // Poll for the window until it appears or the deadline passes
+                info!(
+                    "Waiting up to {} ms for {} to start...",
+                    timeout.as_millis(),
+                    app.name
+                );
+                debug!("Searching for a window matching '{}'", search);
+                let ready_title = app.ready_when_title.as_deref();
+                let remembered = identities.apps.get(&app.name);
+                match prefetch {
+                    // Each probe waits for the next enumeration, which paces the polling
+                    Some(prefetch) => poll_until(timeout, Duration::ZERO, || {
+                        let windows = prefetch.next_within(pacing.poll_interval)?;
+                        let found = desktop.find_in_windows(
+                            &windows,
+                            &search,
+                            ready_title,
+                            &ignore,
+                            remembered,
+                        );
+                        last_seen = windows;
+                        found
+                    }),
+                    None => poll_until(timeout, pacing.poll_interval, || {
+                        let windows =
+                            visible_to(app, enumerated.insert(desktop.enumerate_windows(true)));
+                        let found = desktop.find_in_windows(
+                            &windows,
+                            &search,
+                            ready_title,
+                            &ignore,
+                            remembered,
+                        );
+                        last_seen = windows;
+                        found
+                    }),
+                }
+            }
+        };
+        if window.is_none()
+            && let Some(ref ready_title) = app.ready_when_title
+            && let Some(loading) = find_candidate(&last_seen, &search, &ignore)
+        {
+            warn!(
+                "{}'s window '{}' never got a title containing '{}'",
+                app.name, loading.title, ready_title
+            );
+        }
+        // Launchers and updaters often hand off to a helper process whose
+        // window has a title nobody would configure
+        let window = match (window, launched_process) {
+            (None, Some(process_id)) if app.ready_when_title.is_none() => {
+                desktop.find_by_process_tree(process_id, &last_seen, &ignore)
+            }
+            (window, _) => window,
+        };
+
+        if let Some(ref found) = window {
+            info!(
+                app = %app.name,
+                matched_by = %found.strategy,
+                title = %found.title,
+                "Found {} via {}",
+                app.name,
+                found.strategy
+            );
+            // Renamed before the next app's search, so it can't match this window
+            if let Some(ref title) = app.set_title
+                && let Err(e) = desktop.set_title(found.hwnd, title)
+            {
+                warn!(
+                    "Could not rename {}'s window to '{}': {}",
+                    app.name, title, e
+                );
+            }
+        }
+        let outcome = match window.as_ref().map(|found| found.hwnd) {
+            Some(hwnd)
+                if leave_in_place(
+                    config,
+                    already_open,
+                    desktop.monitor_of_window(hwnd, &monitors),
+                    monitor,
+                ) =>
+            {
+                info!(
+                    "{} is already on display {}; leaving it in place",
+                    app.name, assigned
+                );
+                AppOutcome::Positioned
+            }
+            Some(hwnd) => {
+                info!(
+                    "Positioning {} on display {} ({})",
+                    app.name,
+                    assigned,
+                    monitor.label()
+                );
+
+                if let Some(ref warning) = planned.warning {
+                    warn!("{}: {}", app.name, warning);
+                }
+                // The window's own size, which only it knows, centered on its slot
+                let rect = match desktop.normal_size(hwnd) {
+                    Some((width, height)) if app.center_fixed => rect.center_in(width, height),
+                    _ => rect,
+                };
+                let Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                } = rect;
+                options.record_position(&app.name, hwnd, rect);
+                options.record_step(SessionAction::Move {
+                    app: app.name.clone(),
+                    search: search.clone(),
+                    rect,
+                });
+
+                // Remember where the window was so the launch can be undone
+                if let Some(rect) = desktop.window_rect(hwnd) {
+                    snapshot.record(&app.name, hwnd, rect);
+                }
+
+                // Apps launched minimized are restored in place before moving.
+                // Activating a window on another virtual desktop would switch to it.
+                let activate = !config.no_activate && !desktop.is_on_other_desktop(hwnd);
+                if !activate && !already_open {
+                    desktop.restore_without_activating(hwnd);
+                }
+
+                // Position the window
+                let moved = if app.smooth_move && pacing.follow_up_moves {
+                    desktop.position_window_smooth(
+                        hwnd,
+                        rect,
+                        SMOOTH_MOVE_STEPS,
+                        SMOOTH_MOVE_MS,
+                        activate,
+                    )
+                } else {
+                    desktop.position_window(hwnd, rect, activate)
+                };
+                match moved {
+                    Ok(()) => {
+                        info!(
+                            "Successfully positioned {} at ({}, {}) with size {}x{}",
+                            app.name, x, y, width, height
+                        );
+                        // Where the window itself goes, borders included
+                        let outer = if app.align_visible_frame {
+                            match desktop.align_visible_frame(hwnd, rect) {
+                                Ok(outer) => {
+                                    debug!(
+                                        "Grew {} to ({}, {}) {}x{} so its visible frame fills the slot",
+                                        app.name, outer.x, outer.y, outer.width, outer.height
+                                    );
+                                    outer
+                                }
+                                Err(e) => {
+                                    warn!("Could not align {}'s visible frame: {}", app.name, e);
+                                    rect
+                                }
+                            }
+                        } else {
+                            rect
+                        };
+                        if pacing.follow_up_moves
+                            && let Some(ref enforce) = app.enforce
+                        {
+                            enforce_app_position(
+                                desktop, app, hwnd, outer, enforce, activate, deadline,
+                            );
+                        }
+                        if let Some(z_order) = app.z_order {
+                            layered.push((z_order, hwnd));
+                        }
+                        report.windows.push(TrackedWindow {
+                            app: app.name.clone(),
+                            hwnd,
+                            search: search.clone(),
+                            rect,
+                        });
+                        AppOutcome::Positioned
+                    }
+                    Err(e) => {
+                        error!("Failed to position window for {}: {}", app.name, e);
+                        AppOutcome::PositionFailed(e)
+                    }
+                }
+            }
+            None if app.launch_timeout_ms.is_some() => {
+                let suggestions = suggest_titles(search_hint(app, &search), &last_seen, 3);
+                error!(
+                    app = %app.name,
+                    search = %search,
+                    suggestions = ?suggestions,
+                    "Timed out after {} ms waiting for {} window (searched for: {}); {}",
+                    timeout.as_millis(),
+                    app.name,
+                    search,
+                    format_suggestions(&suggestions)
+                );
+                AppOutcome::TimedOut(timeout)
+            }
+            None => {
+                let suggestions = suggest_titles(search_hint(app, &search), &last_seen, 3);
+                warn!(
+                    app = %app.name,
+                    search = %search,
+                    suggestions = ?suggestions,
+                    "Could not find window for {} (searched for: {}); {}",
+                    app.name,
+                    search,
+                    format_suggestions(&suggestions)
+                );
+                AppOutcome::WindowNotFound
+            }
+        };
+        if outcome == AppOutcome::Positioned
+            && let Some(ref found) = window
+        {
+            identities.apps.insert(
+                app.name.clone(),
+                desktop.window_identity(found.hwnd, &found.title),
+            );
+        }
+        options.finish_found(index, &mut report, &app.name, outcome, window);
+
+        // Wait a bit before launching the next application
+        if !options.snap_only {
+            thread::sleep(within_deadline(pacing.launch_spacing, deadline));
+        }
+    }
+
+    if !layered.is_empty() {
+        info!("Stacking {} windows by z_order", layered.len());
+        if let Err(e) = desktop.set_zorder(&stacking_order(&layered)) {
+            warn!("{}", e);
+        }
+    }
+
+    if let Some(hwnd) = focused
+        && desktop.foreground_window() != Some(hwnd)
+        && !desktop.set_foreground_window(hwnd)
+    {
+        warn!("Could not give focus back to the window that had it");
+    }
+
+    if report.cancelled {
+        // The previous snapshot still describes the desktop as it is again
+        let restored = desktop.restore(snapshot);
+        info!("Moved {} windows back to where they were", restored);
+        return Ok(report);
+    }
+
+    if let Some(e) = report.fail_fast_error(options) {
+        return Err(e);
+    }
+    info!(
+        "All applications processed: {} of {} positioned",
+        report.positioned_count(),
+        report.results.len()
+    );
+    Ok(report)
+}
+
+/// The windows of an enumeration that `app` may match: cloaked ones only
+/// when it includes them
+fn visible_to(app: &Application, windows: &[WindowCandidate]) -> Vec<WindowCandidate> {
+    windows
+        .iter()
+        .filter(|window| app.include_cloaked || !window.cloaked)
+        .cloned()
+        .collect()
+}
+
+/// The newest enumeration as `app` sees it, enumerating only when there is
+/// none yet
+fn cached_windows(
+    desktop: &impl Desktop,
+    enumerated: &mut Option<Vec<WindowCandidate>>,
+    app: &Application,
+) -> Vec<WindowCandidate> {
+    visible_to(
+        app,
+        enumerated.get_or_insert_with(|| desktop.enumerate_windows(true)),
+    )
+}
+
+/// Holds an app's window in place for apps with `enforce`, logging how often
+/// it had to be moved back. Without `activate`, the window is put back
+/// without taking focus, as on its first move.
+fn enforce_app_position(
+    desktop: &impl Desktop,
+    app: &Application,
+    hwnd: usize,
+    target: Rect,
+    enforce: &Enforce,
+    activate: bool,
+    deadline: Option<Instant>,
+) {
+    // Windows are positioned in their normal state; an app that maximizes
+    // itself afterwards would otherwise keep undoing the move
+    let result = enforce_position(
+        (target, ShowState::Normal),
+        enforce,
+        deadline,
+        || {
+            desktop
+                .window_rect(hwnd)
+                .map(|rect| (rect, desktop.show_state(hwnd)))
+        },
+        |(rect, state)| {
+            if activate {
+                desktop.set_show_state(hwnd, state);
+            } else if desktop.show_state(hwnd) != state {
+                // Restoring through the show state would activate the window
+                desktop.restore_without_activating(hwnd);
+            }
+            desktop.position_window(hwnd, rect, activate)
+        },
+        thread::sleep,
+    );
+    match result {
+        Ok(0) => debug!("{} stayed in place", app.name),
+        Ok(corrections) if corrections == enforce.retries => warn!(
+            "{} kept moving its window; it was moved back {} times and may not stay in place",
+            app.name, corrections
+        ),
+        Ok(corrections) => info!(
+            "{} moved its window {} times; moved it back each time",
+            app.name, corrections
+        ),
+        Err(e) => warn!("Failed to keep {} in place: {}", app.name, e),
+    }
+}
+
+/// Describes what a launch run would do without launching or moving anything.
+/// `simulated` monitors replace the connected ones, e.g. to check a config
+/// for another desk. Otherwise, apps that already have a window report which
+/// monitor it is on now. With `windows` from a `--dump-windows` snapshot,
+/// each app reports which of them it would match instead.
+pub fn dry_run(
+    config: &Config,
+    simulated: Option<Vec<Monitor>>,
+    windows: Option<&[WindowCandidate]>,
+) -> Vec<String> {
+    let simulating = simulated.is_some();
+    let monitors = simulated.unwrap_or_else(monitors);
+    // Without simulated monitors there is nothing to place windows on here
+    let positioning = cfg!(windows) || simulating;
+
+    let mut lines = Vec::new();
+    if positioning {
+        let verb = if simulating { "Simulating" } else { "Found" };
+        lines.push(format!("{} {} monitors", verb, monitors.len()));
+        for monitor in &monitors {
+            lines.push(format!("  {}: {}", monitor.index + 1, monitor.label()));
+        }
+        if let Err(e) = config.check_monitor_count(&monitors) {
+            lines.push(format!("{}; a launch would stop here", e));
+        }
+    }
+    let machine = MachineIdentity::current();
+    if machine.session == SessionKind::Remote {
+        lines.push(REMOTE_SESSION_WARNING.to_string());
+    }
+    let ignore = config.ignore_list();
+
+    for (app, planned) in config
+        .applications
+        .iter()
+        .zip(plan_layout(config, &monitors))
+    {
+        let assigned = &planned.display;
+        if let Some(reason) = app.skip_reason(&machine) {
+            lines.push(format!("{}: skipped ({})", app.name, reason));
+            continue;
+        }
+        if let Some(file) = app.missing_open_file() {
+            lines.push(format!(
+                "{}: file to open '{}' does not exist",
+                app.name, file
+            ));
+        }
+        if let Some(issue) = config::check_executable(&app.executable) {
+            lines.push(format!("{}: {}", app.name, issue.message));
+        }
+        let match_line = windows.map(|windows| window_match_line(app, windows, &ignore));
+        if !positioning {
+            lines.push(format!(
+                "{}: would launch '{}'",
+                app.name,
+                describe_launch(app)
+            ));
+            lines.extend(match_line);
+            continue;
+        }
+
+        let (Some(monitor), Some(rect)) =
+            (placement_monitor(app, assigned, &monitors), planned.rect)
+        else {
+            lines.push(format!(
+                "{}: would launch '{}', but {}",
+                app.name,
+                describe_launch(app),
+                planned.reason
+            ));
+            lines.extend(match_line);
+            continue;
+        };
+
+        let Rect {
+            x,
+            y,
+            width,
+            height,
+        } = rect;
+        lines.push(format!(
+            "{}: would launch '{}' and position it on display {} ({}) at ({}, {}) with size {}x{} ({})",
+            app.name,
+            describe_launch(app),
+            assigned,
+            monitor.label(),
+            x,
+            y,
+            width,
+            height,
+            planned.reason
+        ));
+        if let Some(ref warning) = planned.warning {
+            lines.push(format!("{}: {}", app.name, warning));
+        }
+        if app.center_fixed {
+            lines.push(format!(
+                "{}: would keep its window's size, centered on that area",
+                app.name
+            ));
+        }
+        // Scripts may have side effects, so a dry run doesn't run them
+        if let Some(script) = placement_script_for(config, app) {
+            lines.push(format!(
+                "{}: placement script '{}' would choose the final position",
+                app.name, script
+            ));
+        }
+        lines.extend(match_line);
+
+        // A snapshot's window handles are stale, so only live windows are looked up
+        #[cfg(windows)]
+        if !simulating
+            && windows.is_none()
+            && let Some(line) = current_monitor_line(app, assigned, monitor, &monitors, &ignore)
+        {
+            lines.push(line);
+        }
+    }
+
+    if !positioning {
+        lines.push("Window positioning is only supported on Windows.".to_string());
+    }
+    lines
+}
+
+/// Which of the `windows` an app would be matched to, the way the launcher
+/// searches for it
+fn window_match_line(
+    app: &Application,
+    windows: &[WindowCandidate],
+    ignore: &IgnoreList,
+) -> String {
+    let search = criteria_for(app);
+    let windows: Vec<WindowCandidate> = windows
+        .iter()
+        .filter(|window| (app.include_cloaked || !window.cloaked) && !ignore.ignores(window))
+        .cloned()
+        .collect();
+    match find_ready_candidate(&windows, &search, app.ready_when_title.as_deref(), ignore) {
+        Some(window) => format!(
+            "{}: would match window '{}' (class '{}', process {})",
+            app.name, window.title, window.class, window.process_id
+        ),
+        None => format!(
+            "{}: no window matches '{}'; {}",
+            app.name,
+            search,
+            format_suggestions(&suggest_titles(search_hint(app, &search), &windows, 3))
+        ),
+    }
+}
+
+/// Where an app's existing window is now compared to where it should go
+#[cfg(windows)]
+fn current_monitor_line(
+    app: &Application,
+    assigned: &DisplaySelector,
+    target: &Monitor,
+    monitors: &[Monitor],
+    ignore: &IgnoreList,
+) -> Option<String> {
+    let hwnd = find_window(&criteria_for(app), ignore)?;
+    Some(match monitor_of_window(hwnd, monitors) {
+        Some(current) if current.index == target.index => {
+            format!("{} is already on monitor {}", app.name, current.index + 1)
+        }
+        Some(current) => format!(
+            "{} is currently on monitor {} ({}), config wants monitor {}",
+            app.name,
+            current.index + 1,
+            current.label(),
+            assigned
+        ),
+        None => format!(
+            "{} has a window but it is not on any monitor, config wants monitor {}",
+            app.name, assigned
+        ),
+    })
+}
+
+#[cfg(not(windows))]
+pub fn launch_and_position_applications(
+    _config: &Config,
+    _launcher: &dyn Launcher,
+    _options: &LaunchOptions,
+) -> Result<LaunchReport, String> {
+    warn!("Window positioning is only supported on Windows.");
+    Ok(LaunchReport::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::{BrowserKind, BrowserLaunch};
+    use crate::config::{AppGroup, DisplaySelector, RelativePlacement, RelativeTo, Side};
+    use crate::display::{parse_monitor_spec, test_monitor};
+    use crate::matching::MatchStrategy;
+    use mockall::predicate::*;
+
+    #[test]
+    fn test_launch_application_invalid_executable() {
+        let mut mock_api = MockWindowsApi::new();
+
+        // Mock the launch_application function to return an error for invalid executables
+        mock_api
+            .expect_launch_application()
+            .with(eq("nonexistent_executable.exe"))
+            .times(1)
+            .returning(|_| Err("Failed to launch application".to_string()));
+
+        let result = mock_api.launch_application("nonexistent_executable.exe");
+        // This should fail since the executable doesn't exist
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_launch_and_position_applications_empty_config() {
+        let empty_config = Config {
+            applications: vec![],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+
+        // Mock get_monitors to return some monitors
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+
+        let result = launch_and_position_applications_mock(&empty_config, &mock_api);
+        // This should succeed since there are no applications to launch
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_launch_and_position_applications_invalid_display() {
+        // Create a test config with an invalid display number
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Test App".to_string(),
+                display: Some(999.into()), // Invalid display number
+                side: Some(Side::Left),
+                executable: "cmd.exe".to_string(), // Use a valid executable to avoid launch failure
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+
+        // Mock get_monitors to return some monitors
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+
+        // Mock successful launch
+        mock_api
+            .expect_launch_application()
+            .with(eq("cmd.exe"))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let result = launch_and_position_applications_mock(&test_config, &mock_api);
+        // The function should succeed even with invalid display number
+        // It just logs an error and continues
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_launch_and_position_applications_success() {
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                display: Some(1.into()),
+                side: Some(Side::Left),
+                executable: "teams.exe".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+
+        // Mock get_monitors to return some monitors
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+
+        // Mock successful launch
+        mock_api
+            .expect_launch_application()
+            .with(eq("teams.exe"))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        // Mock window finding
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("teams"))
+            .times(1)
+            .returning(|_| Some(1001));
+
+        // Mock window positioning
+        mock_api
+            .expect_position_window()
+            .with(eq(1001), eq(0), eq(0), eq(960), eq(1040))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let result = launch_and_position_applications_mock(&test_config, &mock_api);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_launch_and_position_applications_off_screen_geometry() {
+        // Saved for a third monitor that is no longer connected
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                executable: "teams.exe".to_string(),
+                geometry: Some(Rect::new(5000, 100, 800, 600)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+        mock_api.expect_launch_application().returning(|_| Ok(()));
+        mock_api
+            .expect_find_window_by_title()
+            .returning(|_| Some(1001));
+
+        // Moved to the right edge of the nearest monitor instead
+        mock_api
+            .expect_position_window()
+            .with(eq(1001), eq(3840 - 800), eq(100), eq(800), eq(600))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let result = launch_and_position_applications_mock(&test_config, &mock_api);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_launch_and_position_applications_geometry() {
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                executable: "teams.exe".to_string(),
+                geometry: Some(Rect::new(100, 50, 1280, 720)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+        mock_api.expect_launch_application().returning(|_| Ok(()));
+        mock_api
+            .expect_find_window_by_title()
+            .returning(|_| Some(1001));
+
+        // Exactly the configured rectangle, whatever the side
+        mock_api
+            .expect_position_window()
+            .with(eq(1001), eq(100), eq(50), eq(1280), eq(720))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let result = launch_and_position_applications_mock(&test_config, &mock_api);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_launch_and_position_applications_geometry_without_display() {
+        // Desktop coordinates are used even though display 3 isn't connected
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                executable: "teams.exe".to_string(),
+                display: Some(3.into()),
+                geometry: Some(Rect::new(2000, 50, 1280, 720)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+        mock_api.expect_launch_application().returning(|_| Ok(()));
+        mock_api
+            .expect_find_window_by_title()
+            .returning(|_| Some(1001));
+        mock_api
+            .expect_position_window()
+            .with(eq(1001), eq(2000), eq(50), eq(1280), eq(720))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let report = launch_and_position_applications_mock(&test_config, &mock_api).unwrap();
+        assert_eq!(report.results[0].outcome, AppOutcome::Positioned);
+    }
+
+    #[test]
+    fn test_launch_and_position_applications_window_title() {
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                executable: "teams.exe".to_string(),
+                window_title: Some("Microsoft Teams (work or school)".to_string()),
+                side: Some(Side::Left),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+        mock_api.expect_launch_application().returning(|_| Ok(()));
+
+        // Searched for by its window_title, not the built-in "teams"
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("Microsoft Teams (work or school)"))
+            .returning(|_| Some(1001));
+        mock_api
+            .expect_position_window()
+            .with(eq(1001), eq(0), eq(0), eq(960), eq(1040))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let result = launch_and_position_applications_mock(&test_config, &mock_api);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_window_title_when_retrying_and_enforcing() {
+        let title = "Microsoft Teams (work or school)";
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                executable: "teams.exe".to_string(),
+                window_title: Some(title.to_string()),
+                enforce: Some(Enforce {
+                    retries: 2,
+                    interval_ms: 1,
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .returning(create_mock_monitors);
+        mock_api
+            .expect_launch_application()
+            .times(1)
+            .returning(|_| Ok(()));
+        // Only ever searched for by its window_title: the first run's search,
+        // then the retry's check for an open window and its search
+        let mut searches = 0;
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq(title))
+            .times(3)
+            .returning(move |_| {
+                searches += 1;
+                (searches > 1).then_some(1001)
+            });
+        mock_api
+            .expect_position_window()
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let report = launch_with_retries(
+            1,
+            Duration::ZERO,
+            &LaunchOptions::default(),
+            |options| launch_and_position_applications_mock_with(&test_config, &mock_api, options),
+            |_| {},
+        )
+        .unwrap();
+        assert!(report.is_success());
+        // What --watch looks for when the app restarts
+        assert_eq!(report.windows[0].search, MatchCriteria::title(title));
+    }
+
+    #[test]
+    fn test_no_activate_when_enforcing() {
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                executable: "teams.exe".to_string(),
+                enforce: Some(Enforce {
+                    retries: 3,
+                    interval_ms: 1,
+                }),
+                ..Default::default()
+            }],
+            no_activate: true,
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .returning(create_mock_monitors);
+        mock_api.expect_launch_application().returning(|_| Ok(()));
+        mock_api
+            .expect_find_window_by_title()
+            .returning(|_| Some(1001));
+        // The first move, then one each time Teams maximizes itself again
+        mock_api
+            .expect_position_window()
+            .times(4)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let desktop = MockDesktop::new(&mock_api).maximizing_itself();
+        let options = LaunchOptions {
+            pacing: TEST_PACING,
+            ..Default::default()
+        };
+        let report = launch_and_position_on(
+            &test_config,
+            &desktop,
+            &desktop,
+            &options,
+            &mut IdentityStore::default(),
+            &mut UndoSnapshot::default(),
+        )
+        .unwrap();
+        assert!(report.is_success());
+        assert_eq!(desktop.moves(), vec![false; 4]);
+        assert_eq!(desktop.restores(), vec![false; 3]);
+    }
+
+    #[test]
+    fn test_launch_records_session() {
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                executable: "teams.exe".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+        mock_api.expect_launch_application().returning(|_| Ok(()));
+        mock_api
+            .expect_find_window_by_title()
+            .returning(|_| Some(1001));
+        mock_api
+            .expect_position_window()
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let session_path = std::env::temp_dir().join("jumpstart_launch_session_test.jsonl");
+        replay::start(&session_path).unwrap();
+        let options = LaunchOptions {
+            record_session: Some(session_path.clone()),
+            ..Default::default()
+        };
+        launch_and_position_applications_mock_with(&test_config, &mock_api, &options).unwrap();
+
+        // The launch comes first, then the move with what it searched for
+        let steps = replay::load(&session_path).unwrap();
+        assert_eq!(steps.len(), 2);
+        assert!(matches!(
+            &steps[0].action,
+            SessionAction::Launch { app } if app.executable == "teams.exe"
+        ));
+        assert!(matches!(
+            &steps[1].action,
+            SessionAction::Move { app, search, rect }
+                if app == "Teams" && *search == MatchCriteria::title("teams") && rect.width == 960
+        ));
+        std::fs::remove_file(&session_path).unwrap();
+    }
+
+    #[test]
+    fn test_launch_and_position_applications_split_ratio() {
+        let test_config = Config {
+            applications: vec![
+                Application {
+                    name: "Teams".to_string(),
+                    side: Some(Side::Left),
+                    executable: "teams.exe".to_string(),
+                    split_ratio: 0.6,
+                    ..Default::default()
+                },
+                Application {
+                    name: "Slack".to_string(),
+                    side: Some(Side::Right),
+                    executable: "slack.exe".to_string(),
+                    split_ratio: 0.4,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+
+        mock_api
+            .expect_launch_application()
+            .times(2)
+            .returning(|_| Ok(()));
+
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("teams"))
+            .returning(|_| Some(1001));
+
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("slack"))
+            .returning(|_| Some(1002));
+
+        // 60/40 split of a 1920-wide work area
+        mock_api
+            .expect_position_window()
+            .with(eq(1001), eq(0), eq(0), eq(1152), eq(1040))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        mock_api
+            .expect_position_window()
+            .with(eq(1002), eq(1152), eq(0), eq(768), eq(1040))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let report = launch_and_position_applications_mock(&test_config, &mock_api).unwrap();
+        assert!(report.is_success());
+    }
+
+    #[test]
+    fn test_launch_and_position_applications_timeout() {
+        let test_config = Config {
+            applications: vec![
+                Application {
+                    name: "Hung App".to_string(),
+                    display: Some(1.into()),
+                    side: Some(Side::Left),
+                    executable: "hung.exe".to_string(),
+                    launch_timeout_ms: Some(50),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Teams".to_string(),
+                    display: Some(2.into()),
+                    side: Some(Side::Right),
+                    executable: "teams.exe".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+
+        mock_api
+            .expect_launch_application()
+            .times(2)
+            .returning(|_| Ok(()));
+
+        // The hung app never shows a window
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("Hung App"))
+            .returning(|_| None);
+
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("teams"))
+            .times(1)
+            .returning(|_| Some(1002));
+
+        // The rest of the sequence still runs after the timeout
+        mock_api
+            .expect_position_window()
+            .with(eq(1002), eq(2880), eq(0), eq(960), eq(1040))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let report = launch_and_position_applications_mock(&test_config, &mock_api).unwrap();
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(
+            report.results[0].outcome,
+            AppOutcome::TimedOut(Duration::from_millis(50))
+        );
+        assert_eq!(report.results[1].outcome, AppOutcome::Positioned);
+        assert_eq!(report.positioned_count(), 1);
+        assert!(!report.is_success());
+    }
+
+    #[test]
+    fn test_launch_progress_and_disabled_apps() {
+        let test_config = Config {
+            applications: vec![
+                Application {
+                    name: "Disabled App".to_string(),
+                    executable: "disabled.exe".to_string(),
+                    enabled: false,
+                    ..Default::default()
+                },
+                Application {
+                    name: "Teams".to_string(),
+                    executable: "teams.exe".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut mock_api = MockWindowsApi::new();
+
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+
+        // Only the enabled application is launched
+        mock_api
+            .expect_launch_application()
+            .with(eq("teams.exe"))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("teams"))
+            .times(1)
+            .returning(|_| Some(1001));
+
+        mock_api
+            .expect_position_window()
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let options = LaunchOptions {
+            progress: Some(tx),
+            ..Default::default()
+        };
+        let report =
+            launch_and_position_applications_mock_with(&test_config, &mock_api, &options).unwrap();
+        drop(options);
+
+        assert_eq!(
+            report.results[0].outcome,
+            AppOutcome::Skipped("disabled".to_string())
+        );
+        assert!(report.is_success());
+
+        let events: Vec<LaunchProgress> = rx.iter().collect();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            &events[0],
+            LaunchProgress::Finished { index: 0, result } if result.name == "Disabled App"
+        ));
+        assert!(matches!(
+            &events[1],
+            LaunchProgress::Started { index: 1, .. }
+        ));
+        assert!(matches!(
+            &events[2],
+            LaunchProgress::Finished { index: 1, result } if result.outcome == AppOutcome::Positioned
+        ));
+    }
+
+    #[test]
+    fn test_launch_only() {
+        /// Starts nothing, failing for `failing`
+        struct FakeLauncher {
+            failing: &'static str,
+        }
+        impl Launcher for FakeLauncher {
+            fn launch(&self, app: &Application) -> Result<LaunchHandle, String> {
+                if app.name == self.failing {
+                    Err("not found".to_string())
+                } else {
+                    Ok(LaunchHandle::default())
+                }
+            }
+        }
+
+        let app = |name: &str, enabled: bool| Application {
+            name: name.to_string(),
+            executable: format!("{}.exe", name),
+            enabled,
+            ..Default::default()
+        };
+        let config = Config {
+            applications: vec![app("Editor", true), app("Music", false), app("Chat", true)],
+            ..Default::default()
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let options = LaunchOptions {
+            progress: Some(tx),
+            ..Default::default()
+        };
+        let report = launch_only(&config, &FakeLauncher { failing: "Chat" }, &options);
+
+        let outcomes: Vec<&AppOutcome> = report.results.iter().map(|r| &r.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [
+                &AppOutcome::Launched,
+                &AppOutcome::Skipped("disabled".to_string()),
+                &AppOutcome::LaunchFailed("not found".to_string()),
+            ]
+        );
+        assert_eq!(report.positioned_count(), 0);
+        assert_eq!(report.first_failure().unwrap().name, "Chat");
+
+        let mut statuses = vec![AppStatus::Idle; 3];
+        drop(options);
+        for event in rx {
+            AppStatus::apply(&mut statuses, &event);
+        }
+        assert_eq!(statuses[0], AppStatus::Done(AppOutcome::Launched));
+    }
+
+    #[test]
+    fn test_search_title_for() {
+        let app = |name: &str| Application {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(search_title_for(&app("Teams")), "teams");
+        assert_eq!(
+            search_title_for(&app("Visual Studio Code")),
+            "Visual Studio Code"
+        );
+
+        // window_title wins over the built-in map and the name
+        let titled = |name: &str, title: &str| Application {
+            window_title: Some(title.to_string()),
+            ..app(name)
+        };
+        assert_eq!(
+            search_title_for(&titled("Teams", "Microsoft Teams")),
+            "Microsoft Teams"
+        );
+        assert_eq!(
+            search_title_for(&titled("JetBrains Rider", "Rider")),
+            "Rider"
+        );
+        assert_eq!(
+            criteria_for(&titled("JetBrains Rider", "Rider")),
+            MatchCriteria::title("Rider")
+        );
+    }
+
+    #[test]
+    fn test_resolve_launch_command_for_browser_app() {
+        let app = Application {
+            name: "Calendar".to_string(),
+            executable: "/opt/chrome/chrome".to_string(),
+            args: vec!["--new-window".to_string()],
+            browser: Some(BrowserLaunch {
+                kind: BrowserKind::Chrome,
+                url: "https://calendar.example.com".to_string(),
+                profile: Some("Work".to_string()),
+                app_mode: true,
+            }),
+            ..Default::default()
+        };
+        let command = resolve_launch_command(&app).unwrap();
+        assert_eq!(command.program, "/opt/chrome/chrome");
+        assert_eq!(
+            command.args,
+            vec![
+                "--profile-directory=Work",
+                "--app=https://calendar.example.com",
+                "--new-window"
+            ]
+        );
+
+        let plain = Application {
+            executable: "editor.exe".to_string(),
+            args: vec!["--reuse".to_string()],
+            working_dir: Some("C:/projects".to_string()),
+            open_file: Some("C:/projects/app.sln".to_string()),
+            ..Default::default()
+        };
+        let command = resolve_launch_command(&plain).unwrap();
+        assert_eq!(
+            command.to_string(),
+            "editor.exe --reuse C:/projects/app.sln (in C:/projects)"
+        );
+    }
+
+    #[test]
+    fn test_console() {
+        let yaml = r#"
+name: "Log tail"
+side: "left"
+executable: "tail.exe"
+console: "none"
+"#;
+        let app: Application = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(resolve_launch_command(&app).unwrap().console, Console::None);
+        assert_eq!(Application::default().console, Console::New);
+
+        // Only a new console lets `start` open a window for the program
+        assert_eq!(shell_start_args(Console::New), ["/C", "start", ""]);
+        assert_eq!(
+            shell_start_args(Console::Inherit),
+            ["/C", "start", "", "/B"]
+        );
+        assert_eq!(shell_start_args(Console::None), ["/C", "start", "", "/B"]);
+    }
+
+    #[test]
+    fn test_shell_launcher_for_config() {
+        let yaml = "applications: []\nno_activate: true\n";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(ShellLauncher::for_config(&config).minimized);
+        assert!(!ShellLauncher::for_config(&Config::default()).minimized);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_launcher() {
+        let app = |executable: &str| Application {
+            name: "Test".to_string(),
+            executable: executable.to_string(),
+            ..Default::default()
+        };
+        // Commands without an .exe go through the shell, which reports no PID
+        let launcher = ShellLauncher::default();
+        assert_eq!(
+            launcher.launch(&app("true")),
+            Ok(LaunchHandle { process_id: None })
+        );
+        assert!(launcher.launch(&app("exit 3")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_launch_method() {
+        let command = |program: &str, method| LaunchCommand {
+            program: program.to_string(),
+            args: Vec::new(),
+            working_dir: None,
+            console: Console::default(),
+            minimized: false,
+            method,
+            env: Vec::new(),
+        };
+        // Only a direct launch knows the app's own process
+        assert!(matches!(
+            launch_application(&command("true", LaunchMethod::Direct)),
+            Ok(Some(_))
+        ));
+        assert_eq!(
+            launch_application(&command("true", LaunchMethod::Shell)),
+            Ok(None)
+        );
+        // There is no shell to resolve a command line for a direct launch
+        assert!(launch_application(&command("exit 0", LaunchMethod::Direct)).is_err());
+
+        let yaml = "name: Notes\nside: left\nexecutable: notes.exe\nlaunch_method: shellexecute\n";
+        let app: Application = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            resolve_launch_command(&app).unwrap().method,
+            LaunchMethod::ShellExecute
+        );
+    }
+
+    #[test]
+    fn test_launch_command_is_executable() {
+        let command = |program: &str| LaunchCommand {
+            program: program.to_string(),
+            args: Vec::new(),
+            working_dir: None,
+            console: Console::default(),
+            minimized: false,
+            method: LaunchMethod::default(),
+            env: Vec::new(),
+        };
+        assert!(command("C:\\Program Files\\Slack\\slack.exe").is_executable());
+        assert!(command("NOTEPAD.EXE").is_executable());
+        assert!(!command("C:\\Users\\me\\Desktop\\Notes.lnk").is_executable());
+        assert!(!command("scripts\\start-dev.bat").is_executable());
+        assert!(!command("ms-teams:").is_executable());
+        assert!(!command("https://calendar.example.com").is_executable());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_with_timeout_captures_stderr() {
+        let shell = |script: &str| {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", script]);
+            cmd
+        };
+
+        let (status, stderr) = run_with_timeout(
+            &mut shell("echo 'libfoo.so: cannot open shared object' >&2; exit 3"),
+            SHELL_LAUNCH_TIMEOUT,
+            true,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(status.code(), Some(3));
+        assert_eq!(
+            launch_failure(status, &stderr),
+            "Application failed to start with status: exit status: 3: \
+             libfoo.so: cannot open shared object"
+        );
+
+        // Only the start of a long error is kept
+        let (_, stderr) = run_with_timeout(
+            &mut shell("yes error | head -c 100000 >&2; exit 1"),
+            SHELL_LAUNCH_TIMEOUT,
+            true,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(stderr.len() <= STDERR_EXCERPT_BYTES);
+
+        // An app that keeps running is taken as launched
+        let started = Instant::now();
+        let exit =
+            run_with_timeout(&mut shell("sleep 5"), Duration::from_millis(100), true).unwrap();
+        assert!(exit.is_none());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_leave_in_place() {
+        let left = test_monitor(0, "\\\\.\\DISPLAY1", Rect::new(0, 0, 1920, 1080));
+        let right = test_monitor(1, "\\\\.\\DISPLAY2", Rect::new(1920, 0, 1920, 1080));
+        let mut config = Config::default();
+
+        // Windows are always repositioned by default
+        assert!(!leave_in_place(&config, true, Some(&left), &left));
+
+        config.reposition_if_same_monitor = false;
+        assert!(leave_in_place(&config, true, Some(&left), &left));
+        assert!(!leave_in_place(&config, true, Some(&right), &left));
+        assert!(!leave_in_place(&config, true, None, &left));
+        // A window the launch just opened is always positioned
+        assert!(!leave_in_place(&config, false, Some(&left), &left));
+
+        let yaml = "reposition_if_same_monitor: false\napplications: []";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(!config.reposition_if_same_monitor);
+        let config: Config = serde_yaml::from_str("applications: []").unwrap();
+        assert!(config.reposition_if_same_monitor);
+    }
+
+    #[test]
+    fn test_stacking_order() {
+        let layered = [
+            (0, "Browser"),
+            (10, "Editor"),
+            (5, "Docs"),
+            (10, "Terminal"),
+        ];
+        assert_eq!(
+            stacking_order(&layered),
+            vec!["Editor", "Terminal", "Docs", "Browser"]
+        );
+        assert!(stacking_order::<&str>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_enforce_position() {
+        let target = Rect::new(0, 0, 960, 1040);
+        let restored = Rect::new(200, 100, 800, 600);
+        let enforce = Enforce {
+            retries: 4,
+            interval_ms: 500,
+        };
+
+        let normal = |rect| (rect, ShowState::Normal);
+
+        // The app restores its saved position once, shortly after starting
+        let mut positions = vec![
+            normal(target),
+            normal(target),
+            normal(restored),
+            normal(target),
+        ]
+        .into_iter();
+        let mut applied = Vec::new();
+        let mut slept = Duration::ZERO;
+        let corrections = enforce_position(
+            normal(target),
+            &enforce,
+            None,
+            || positions.next(),
+            |placement| {
+                applied.push(placement);
+                Ok(())
+            },
+            |delay| slept += delay,
+        )
+        .unwrap();
+        assert_eq!(corrections, 1);
+        assert_eq!(applied, vec![normal(target)]);
+        assert_eq!(slept, Duration::from_secs(2));
+
+        // An app that maximizes itself is restored even though its restore
+        // rectangle is still the target
+        let maximized = (target, ShowState::Maximized);
+        let mut placements =
+            vec![maximized, normal(target), normal(target), normal(target)].into_iter();
+        let mut applied = Vec::new();
+        let corrections = enforce_position(
+            normal(target),
+            &enforce,
+            None,
+            || placements.next(),
+            |placement| {
+                applied.push(placement);
+                Ok(())
+            },
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(corrections, 1);
+        assert_eq!(applied, vec![normal(target)]);
+
+        // A window that closes ends the enforcement early
+        let mut checks = 0;
+        let corrections = enforce_position(
+            normal(target),
+            &enforce,
+            None,
+            || {
+                checks += 1;
+                None
+            },
+            |_| panic!("nothing to move back"),
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!((corrections, checks), (0, 1));
+
+        // Nothing is held in place once the run's time budget is spent
+        let corrections = enforce_position(
+            normal(target),
+            &enforce,
+            Some(Instant::now()),
+            || panic!("not checked after the deadline"),
+            |_| panic!("nothing to move back"),
+            |_| panic!("no waiting after the deadline"),
+        )
+        .unwrap();
+        assert_eq!(corrections, 0);
+    }
+
+    #[test]
+    fn test_safe_mode_pacing() {
+        let mut app = Application {
+            name: "Slow".to_string(),
+            executable: "slow.exe".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(Pacing::default().window_wait_for(&app), DEFAULT_WINDOW_WAIT);
+        assert!(Pacing::SAFE_MODE.window_wait_for(&app) > DEFAULT_WINDOW_WAIT);
+        assert!(Pacing::SAFE_MODE.launch_spacing > LAUNCH_SPACING);
+
+        // An app's own timeout wins over either preset
+        app.launch_timeout_ms = Some(1500);
+        assert_eq!(
+            Pacing::SAFE_MODE.window_wait_for(&app),
+            Duration::from_millis(1500)
+        );
+    }
+
+    #[test]
+    fn test_poll_until() {
+        let mut attempts = 0;
+        let found = poll_until(Duration::from_secs(1), Duration::from_millis(1), || {
+            attempts += 1;
+            (attempts == 3).then_some(attempts)
+        });
+        assert_eq!(found, Some(3));
+
+        // A probe that never succeeds gives up at the deadline
+        let start = Instant::now();
+        let found: Option<()> =
+            poll_until(Duration::from_millis(30), Duration::from_millis(5), || None);
+        assert!(found.is_none());
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn test_dry_run_with_simulated_monitors() {
+        let config = Config {
+            applications: vec![
+                Application {
+                    name: "Editor".to_string(),
+                    display: Some(DisplaySelector::Name("dell".to_string())),
+                    side: Some(Side::Right),
+                    executable: "editor.exe".to_string(),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Chat".to_string(),
+                    display: Some(3.into()),
+                    executable: "chat.exe".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let monitors = parse_monitor_spec("1920x1080@0,0;2560x1440@1920,0=DELL U2720Q").unwrap();
 
-    if !output.status.success() {
-        return Err(format!(
-            "Application failed to start: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+        let lines = dry_run(&config, Some(monitors), None);
+        assert_eq!(
+            lines,
+            vec![
+                "Simulating 2 monitors",
+                "  1: SIMULATED1",
+                "  2: DELL U2720Q (SIMULATED2)",
+                "Editor: would launch 'editor.exe' and position it on display 'dell' \
+                 (DELL U2720Q (SIMULATED2)) at (3200, 0) with size 1280x1440 (right side)",
+                "Chat: would launch 'chat.exe', but display 3 was not found",
+            ]
+        );
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_dry_run_with_window_snapshot() {
+        let config = Config {
+            applications: vec![
+                Application {
+                    name: "Slack".to_string(),
+                    executable: "slack.exe".to_string(),
+                    ready_when_title: Some("| general".to_string()),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Team Chat".to_string(),
+                    executable: "chat.exe".to_string(),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Calculator".to_string(),
+                    executable: "calc.exe".to_string(),
+                    include_cloaked: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let cloaked = |hwnd, title: &str| WindowCandidate {
+            hwnd,
+            title: title.to_string(),
+            class: "ApplicationFrameWindow".to_string(),
+            cloaked: true,
+            process_id: 4242,
+        };
+        let window = |hwnd, title: &str, class: &str| WindowCandidate {
+            hwnd,
+            title: title.to_string(),
+            class: class.to_string(),
+            cloaked: false,
+            process_id: 4242,
+        };
+        let windows = vec![
+            // A suspended window is passed over unless the app asks for it
+            cloaked(0, "Slack | General | Suspended"),
+            cloaked(4, "Calculator"),
+            window(1, "Slack - Loading...", "Chrome_WidgetWin_1"),
+            window(2, "Slack | General | Acme", "Chrome_WidgetWin_1"),
+            window(3, "Chat | Teams", "TeamsWebView"),
+        ];
+        let monitors = parse_monitor_spec("1920x1080@0,0").unwrap();
 
-#[cfg(windows)]
-pub fn launch_and_position_applications(config: &Config) -> Result<(), String> {
-    // Get available monitors
-    let monitors = get_monitors();
-    info!("Found {} monitors", monitors.len());
+        let lines = dry_run(&config, Some(monitors), Some(&windows));
+        assert_eq!(
+            lines[3],
+            "Slack: would match window 'Slack | General | Acme' \
+             (class 'Chrome_WidgetWin_1', process 4242)"
+        );
+        assert_eq!(
+            lines[5],
+            "Team Chat: no window matches 'Team Chat'; similar windows: 'Chat | Teams'"
+        );
+        assert_eq!(
+            lines[7],
+            "Calculator: would match window 'Calculator' (class 'ApplicationFrameWindow', process 4242)"
+        );
+    }
 
-    // Create a mapping of application names to their window titles
-    let mut app_window_titles = HashMap::new();
-    app_window_titles.insert("Teams", "teams");
-    app_window_titles.insert("Outlook", "outlook");
-    app_window_titles.insert("Slack", "slack");
-    app_window_titles.insert("Notion", "notion");
+    #[test]
+    fn test_snap_positions_existing_windows_without_launching() {
+        let test_config = Config {
+            applications: vec![
+                Application {
+                    name: "Teams".to_string(),
+                    executable: "teams.exe".to_string(),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Slack".to_string(),
+                    executable: "slack.exe".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
 
-    // Launch and position each application
-    for app in &config.applications {
-        info!("Launching {}...", app.name);
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .returning(create_mock_monitors);
+        mock_api.expect_launch_application().never();
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("teams"))
+            .times(1)
+            .returning(|_| Some(1001));
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("slack"))
+            .times(1)
+            .returning(|_| None);
+        mock_api
+            .expect_position_window()
+            .with(eq(1001), always(), always(), always(), always())
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
 
-        // Launch the application
-        if let Err(e) = launch_application(&app.executable) {
-            error!("Failed to launch {}: {}", app.name, e);
-            continue;
-        }
+        let record_path = std::env::temp_dir().join("jumpstart_snap_record_test.jsonl");
+        record::start(&record_path).unwrap();
+        let options = LaunchOptions {
+            snap_only: true,
+            record: Some(record_path.clone()),
+            ..Default::default()
+        };
+        let report =
+            launch_and_position_applications_mock_with(&test_config, &mock_api, &options).unwrap();
+        assert_eq!(report.results[0].outcome, AppOutcome::Positioned);
+        let found = report.results[0].found.as_ref().unwrap();
+        assert_eq!(
+            (found.hwnd, &found.strategy),
+            (1001, &MatchStrategy::Title("teams".to_string()))
+        );
+        // Apps that aren't running are reported, not launched
+        assert_eq!(report.results[1].outcome, AppOutcome::WindowNotFound);
+        assert!(report.results[1].found.is_none());
 
-        // Wait for the application to start and create its window
-        info!("Waiting 5 seconds for {} to start...", app.name);
-        thread::sleep(Duration::from_secs(5));
-        debug!("Finished waiting, now searching for {} window...", app.name);
+        // Only the move that happened is recorded
+        assert_eq!(
+            std::fs::read_to_string(&record_path).unwrap(),
+            "{\"app\":\"Teams\",\"hwnd\":1001,\"x\":0,\"y\":0,\"width\":960,\"height\":1040}\n"
+        );
+        std::fs::remove_file(&record_path).unwrap();
+    }
 
-        // Get the target monitor
-        if let Some(monitor) = get_monitor_by_number(&monitors, app.display) {
-            info!(
-                "Positioning {} on display {} ({})",
-                app.name, app.display, monitor.device_name
-            );
+    #[test]
+    fn test_launch_with_retries_repositions_instead_of_relaunching() {
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                executable: "teams.exe".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
 
-            // Calculate window position
-            let (x, y, width, height) = calculate_window_position(monitor, &app.side);
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .times(2)
+            .returning(create_mock_monitors);
 
-            // Try to find the window by title
-            let search_title = app_window_titles
-                .get(app.name.as_str())
-                .unwrap_or(&app.name.as_str())
-                .to_string();
+        // The retry finds the window opened by the first attempt, so the app
+        // is launched only once
+        mock_api
+            .expect_launch_application()
+            .with(eq("teams.exe"))
+            .times(1)
+            .returning(|_| Ok(()));
 
-            debug!(
-                "Searching for window with title containing: '{}'",
-                search_title
-            );
+        let mut searches = 0;
+        mock_api
+            .expect_find_window_by_title()
+            .with(eq("teams"))
+            .times(3)
+            .returning(move |_| {
+                searches += 1;
+                (searches > 1).then_some(1001)
+            });
 
-            if let Some(hwnd) = find_window_by_title(&search_title) {
-                // Position the window
-                if let Err(e) = position_window(hwnd, x, y, width, height) {
-                    error!("Failed to position window for {}: {}", app.name, e);
-                } else {
-                    info!(
-                        "Successfully positioned {} at ({}, {}) with size {}x{}",
-                        app.name, x, y, width, height
-                    );
-                }
-            } else {
-                warn!(
-                    "Could not find window for {} (searched for: {})",
-                    app.name, search_title
-                );
-            }
-        } else {
-            error!("Monitor {} not found for {}", app.display, app.name);
-        }
+        mock_api
+            .expect_position_window()
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
 
-        // Wait a bit before launching the next application
-        thread::sleep(Duration::from_secs(2));
+        let mut delays = Vec::new();
+        let report = launch_with_retries(
+            3,
+            Duration::from_secs(5),
+            &LaunchOptions::default(),
+            |options| launch_and_position_applications_mock_with(&test_config, &mock_api, options),
+            |delay| delays.push(delay),
+        )
+        .unwrap();
+        assert!(report.is_success());
+        assert_eq!(delays, vec![Duration::from_secs(5)]);
+
+        // Without success every retry is used, doubling the delay each time
+        let mut runs = 0;
+        let mut delays = Vec::new();
+        let report = launch_with_retries(
+            3,
+            Duration::from_secs(5),
+            &LaunchOptions::default(),
+            |options| {
+                assert_eq!(options.reposition_existing, runs > 0);
+                runs += 1;
+                let mut report = LaunchReport::default();
+                report.record("Teams", AppOutcome::WindowNotFound);
+                Ok(report)
+            },
+            |delay| delays.push(delay),
+        )
+        .unwrap();
+        assert!(!report.is_success());
+        assert_eq!(runs, 4);
+        assert_eq!(delays, [5, 10, 20].map(Duration::from_secs).to_vec());
     }
 
-    info!("All applications launched and positioned!");
-    Ok(())
-}
+    #[test]
+    fn test_expected_monitors() {
+        let test_config = Config {
+            applications: vec![Application {
+                name: "Teams".to_string(),
+                display: Some(1.into()),
+                side: Some(Side::Left),
+                executable: "teams.exe".to_string(),
+                ..Default::default()
+            }],
+            expected_monitors: Some(3),
+            ..Default::default()
+        };
 
-#[cfg(not(windows))]
-pub fn launch_and_position_applications(_config: &Config) -> Result<(), String> {
-    warn!("Window positioning is only supported on Windows.");
-    Ok(())
-}
+        // Nothing is launched when the wrong monitors are connected
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+        mock_api.expect_launch_application().times(0);
+        let error = launch_and_position_applications_mock(&test_config, &mock_api).unwrap_err();
+        assert!(
+            error.starts_with("The config expects 3 monitors but 2 are connected"),
+            "{}",
+            error
+        );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::Side;
-    use mockall::predicate::*;
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+        mock_api
+            .expect_launch_application()
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_api
+            .expect_find_window_by_title()
+            .returning(|_| Some(1001));
+        mock_api
+            .expect_position_window()
+            .returning(|_, _, _, _, _| Ok(()));
+        let options = LaunchOptions {
+            ignore_monitor_mismatch: true,
+            ..Default::default()
+        };
+        let report =
+            launch_and_position_applications_mock_with(&test_config, &mock_api, &options).unwrap();
+        assert!(report.is_success());
+    }
 
     #[test]
-    fn test_launch_application_invalid_executable() {
-        let mut mock_api = MockWindowsApi::new();
+    fn test_group_aborts_after_failure() {
+        let app = |name: &str, side| Application {
+            name: name.to_string(),
+            display: Some(1.into()),
+            side: Some(side),
+            executable: format!("{}.exe", name.to_lowercase()),
+            ..Default::default()
+        };
+        let test_config = Config {
+            applications: vec![
+                app("Teams", Side::Left),
+                app("Outlook", Side::Right),
+                app("Slack", Side::Left),
+            ],
+            groups: [(
+                "work".to_string(),
+                AppGroup {
+                    apps: vec!["Teams".to_string(), "Outlook".to_string()],
+                    on_failure: GroupFailurePolicy::AbortGroup,
+                },
+            )]
+            .into(),
+            ..Default::default()
+        };
 
-        // Mock the launch_application function to return an error for invalid executables
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .times(1)
+            .returning(create_mock_monitors);
+        // Outlook is skipped once Teams has failed; Slack isn't in the group
         mock_api
             .expect_launch_application()
-            .with(eq("nonexistent_executable.exe"))
+            .with(eq("outlook.exe"))
+            .times(0);
+        mock_api.expect_launch_application().returning(|_| Ok(()));
+        mock_api
+            .expect_find_window_by_title()
+            .returning(|title| (title == "slack").then_some(1001));
+        mock_api
+            .expect_position_window()
             .times(1)
-            .returning(|_| Err("Failed to launch application".to_string()));
+            .returning(|_, _, _, _, _| Ok(()));
 
-        let result = mock_api.launch_application("nonexistent_executable.exe");
-        // This should fail since the executable doesn't exist
-        assert!(result.is_err());
+        let report = launch_and_position_applications_mock(&test_config, &mock_api).unwrap();
+        assert_eq!(report.results[0].outcome, AppOutcome::WindowNotFound);
+        assert_eq!(
+            report.results[1].outcome,
+            AppOutcome::GroupFailed {
+                group: "work".to_string(),
+                app: "Teams".to_string()
+            }
+        );
+        assert_eq!(
+            report.results[1].outcome.to_string(),
+            "not launched because Teams in group 'work' failed"
+        );
+        assert_eq!(report.results[2].outcome, AppOutcome::Positioned);
     }
 
     #[test]
-    fn test_launch_and_position_applications_empty_config() {
-        let empty_config = Config {
-            applications: vec![],
+    fn test_relative_to_needs_a_positioned_anchor() {
+        let app = |name: &str, relative_to: Option<&str>| Application {
+            name: name.to_string(),
+            display: Some(1.into()),
+            executable: format!("{}.exe", name.to_lowercase()),
+            relative_to: relative_to.map(|anchor| RelativeTo {
+                app: anchor.to_string(),
+                placement: RelativePlacement::RightOf,
+            }),
+            ..Default::default()
+        };
+        let test_config = Config {
+            applications: vec![
+                app("Teams", None),
+                app("Notes", Some("Teams")),
+                app("Outlook", None),
+                app("Chat", Some("Outlook")),
+            ],
+            ..Default::default()
         };
 
         let mut mock_api = MockWindowsApi::new();
-
-        // Mock get_monitors to return some monitors
         mock_api
             .expect_get_monitors()
             .times(1)
             .returning(create_mock_monitors);
+        // Chat has nowhere to go once Outlook isn't found, so it isn't started
+        mock_api
+            .expect_launch_application()
+            .with(eq("chat.exe"))
+            .times(0);
+        mock_api.expect_launch_application().returning(|_| Ok(()));
+        mock_api
+            .expect_find_window_by_title()
+            .returning(|title| match title {
+                "teams" => Some(1001),
+                "Notes" => Some(1002),
+                _ => None,
+            });
+        mock_api
+            .expect_position_window()
+            .with(eq(1001), eq(0), eq(0), eq(960), eq(1040))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+        mock_api
+            .expect_position_window()
+            .with(eq(1002), eq(960), eq(0), eq(960), eq(1040))
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
 
-        let result = launch_and_position_applications_mock(&empty_config, &mock_api);
-        // This should succeed since there are no applications to launch
-        assert!(result.is_ok());
+        let report = launch_and_position_applications_mock(&test_config, &mock_api).unwrap();
+        assert_eq!(report.results[1].outcome, AppOutcome::Positioned);
+        assert_eq!(report.results[2].outcome, AppOutcome::WindowNotFound);
+        assert_eq!(
+            report.results[3].outcome,
+            AppOutcome::AnchorFailed("Outlook".to_string())
+        );
+        assert_eq!(
+            report.results[3].outcome.to_string(),
+            "not launched because Outlook, which it is placed relative to, was not positioned"
+        );
     }
 
     #[test]
-    fn test_launch_and_position_applications_invalid_display() {
-        // Create a test config with an invalid display number
+    fn test_fail_fast_stops_after_first_failure() {
         let test_config = Config {
-            applications: vec![Application {
-                name: "Test App".to_string(),
-                display: 999, // Invalid display number
-                side: Side::Left,
-                executable: "cmd.exe".to_string(), // Use a valid executable to avoid launch failure
-            }],
+            applications: vec![
+                Application {
+                    name: "Teams".to_string(),
+                    display: Some(1.into()),
+                    side: Some(Side::Left),
+                    executable: "teams.exe".to_string(),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Outlook".to_string(),
+                    display: Some(1.into()),
+                    side: Some(Side::Right),
+                    executable: "outlook.exe".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
         };
 
         let mut mock_api = MockWindowsApi::new();
-
-        // Mock get_monitors to return some monitors
         mock_api
             .expect_get_monitors()
             .times(1)
             .returning(create_mock_monitors);
-
-        // Mock successful launch
+        // Outlook is never launched once Teams has failed
         mock_api
             .expect_launch_application()
-            .with(eq("cmd.exe"))
+            .with(eq("teams.exe"))
             .times(1)
             .returning(|_| Ok(()));
+        mock_api.expect_find_window_by_title().returning(|_| None);
 
-        let result = launch_and_position_applications_mock(&test_config, &mock_api);
-        // The function should succeed even with invalid display number
-        // It just logs an error and continues
-        assert!(result.is_ok());
+        let options = LaunchOptions {
+            fail_fast: true,
+            ..Default::default()
+        };
+        let error = launch_and_position_applications_mock_with(&test_config, &mock_api, &options)
+            .unwrap_err();
+        assert!(error.starts_with("Teams: "), "{}", error);
     }
 
     #[test]
-    fn test_launch_and_position_applications_success() {
+    fn test_total_timeout() {
         let test_config = Config {
             applications: vec![Application {
                 name: "Teams".to_string(),
-                display: 1,
-                side: Side::Left,
                 executable: "teams.exe".to_string(),
+                ..Default::default()
             }],
+            total_timeout_secs: Some(0),
+            ..Default::default()
         };
-
         let mut mock_api = MockWindowsApi::new();
-
-        // Mock get_monitors to return some monitors
         mock_api
             .expect_get_monitors()
-            .times(1)
             .returning(create_mock_monitors);
+        mock_api.expect_launch_application().times(0);
 
-        // Mock successful launch
+        // A spent budget stops the run before the next app and isn't retried
+        let mut runs = 0;
+        let report = launch_with_retries(
+            2,
+            Duration::ZERO,
+            &LaunchOptions::default(),
+            |options| {
+                runs += 1;
+                launch_and_position_applications_mock_with(&test_config, &mock_api, options)
+            },
+            |_| {},
+        )
+        .unwrap();
+        assert!(report.timed_out);
+        assert!(report.results.is_empty());
+        assert!(!report.is_success());
+        assert_eq!(runs, 1);
+
+        // Waits are cut short by the deadline
+        let timeout = Duration::from_secs(30);
+        assert_eq!(within_deadline(timeout, None), timeout);
+        let soon = within_deadline(timeout, Some(Instant::now() + Duration::from_secs(2)));
+        assert!(soon <= Duration::from_secs(2) && soon > Duration::from_secs(1));
+        assert_eq!(
+            within_deadline(timeout, Some(Instant::now() - Duration::from_secs(1))),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_total_timeout_cuts_waits_short() {
+        let test_config = Config {
+            applications: vec![
+                Application {
+                    name: "Teams".to_string(),
+                    executable: "teams.exe".to_string(),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Outlook".to_string(),
+                    executable: "outlook.exe".to_string(),
+                    ..Default::default()
+                },
+            ],
+            total_timeout_secs: Some(1),
+            ..Default::default()
+        };
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .returning(create_mock_monitors);
         mock_api
             .expect_launch_application()
             .with(eq("teams.exe"))
             .times(1)
             .returning(|_| Ok(()));
-
-        // Mock window finding
+        mock_api
+            .expect_launch_application()
+            .with(eq("outlook.exe"))
+            .times(0);
         mock_api
             .expect_find_window_by_title()
-            .with(eq("teams"))
-            .times(1)
             .returning(|_| Some(1001));
-
-        // Mock window positioning
         mock_api
             .expect_position_window()
-            .with(eq(1001), eq(0), eq(0), eq(960), eq(1040))
             .times(1)
             .returning(|_, _, _, _, _| Ok(()));
 
-        let result = launch_and_position_applications_mock(&test_config, &mock_api);
-        assert!(result.is_ok());
+        // The pause after Teams would outlast the budget many times over
+        let options = LaunchOptions {
+            pacing: Pacing {
+                launch_spacing: Duration::from_secs(60),
+                ..TEST_PACING
+            },
+            ..Default::default()
+        };
+        let desktop = MockDesktop::new(&mock_api);
+        let start = Instant::now();
+        let report = launch_and_position_on(
+            &test_config,
+            &desktop,
+            &desktop,
+            &options,
+            &mut IdentityStore::default(),
+            &mut UndoSnapshot::default(),
+        )
+        .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(report.timed_out);
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results[0].outcome, AppOutcome::Positioned);
     }
 
-    // Mock version of launch_and_position_applications for testing
-    fn launch_and_position_applications_mock(
-        config: &Config,
-        api: &dyn WindowsApiTrait,
-    ) -> Result<(), String> {
-        // Get available monitors
-        let monitors = api.get_monitors();
-        info!("Found {} monitors", monitors.len());
-
-        // Create a mapping of application names to their window titles
-        let app_window_titles = create_mock_window_map();
-
-        // Launch and position each application
-        for app in &config.applications {
-            info!("Launching {}...", app.name);
-
-            // Launch the application
-            if let Err(e) = api.launch_application(&app.executable) {
-                error!("Failed to launch {}: {}", app.name, e);
-                continue;
-            }
+    #[test]
+    fn test_cancel_stops_before_next_app() {
+        let test_config = Config {
+            applications: vec![
+                Application {
+                    name: "Teams".to_string(),
+                    executable: "teams.exe".to_string(),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Outlook".to_string(),
+                    executable: "outlook.exe".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let cancel = Arc::new(AtomicBool::new(false));
 
-            // Get the target monitor
-            if app.display > 0 && app.display <= monitors.len() as u32 {
-                let monitor = &monitors[(app.display - 1) as usize];
-                info!(
-                    "Positioning {} on display {} ({})",
-                    app.name, app.display, monitor.device_name
-                );
+        let mut mock_api = MockWindowsApi::new();
+        mock_api
+            .expect_get_monitors()
+            .returning(create_mock_monitors);
+        // Ctrl+C arrives while Teams is starting; Outlook is never launched
+        let pressed = cancel.clone();
+        mock_api
+            .expect_launch_application()
+            .with(eq("teams.exe"))
+            .times(1)
+            .returning(move |_| {
+                pressed.store(true, Ordering::SeqCst);
+                Ok(())
+            });
+        mock_api
+            .expect_find_window_by_title()
+            .returning(|_| Some(12345));
+        // Teams is moved into place, then back once the run is cancelled
+        let before = Rect::new(300, 200, 800, 600);
+        mock_api
+            .expect_position_window()
+            .times(2)
+            .returning(|_, _, _, _, _| Ok(()));
 
-                // Calculate window position
-                let (x, y, width, height) = calculate_mock_window_position(monitor, &app.side);
+        let options = LaunchOptions {
+            cancel: Some(cancel),
+            pacing: TEST_PACING,
+            ..Default::default()
+        };
+        let desktop = MockDesktop::new(&mock_api).with_window(12345, before);
+        let report = launch_and_position_on(
+            &test_config,
+            &desktop,
+            &desktop,
+            &options,
+            &mut IdentityStore::default(),
+            &mut UndoSnapshot::default(),
+        )
+        .unwrap();
+        assert!(report.cancelled);
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results[0].outcome, AppOutcome::Positioned);
+        assert_eq!(desktop.window_rect(12345), Some(before));
 
-                // Try to find the window by title
-                let search_title = app_window_titles
-                    .get(app.name.as_str())
-                    .cloned()
-                    .unwrap_or_else(|| app.name.clone());
+        // A cancelled run isn't retried
+        let mut runs = 0;
+        let result = launch_with_retries(
+            3,
+            Duration::ZERO,
+            &options,
+            |_| {
+                runs += 1;
+                Ok(LaunchReport {
+                    cancelled: true,
+                    ..Default::default()
+                })
+            },
+            |_| {},
+        );
+        assert!(result.unwrap().cancelled);
+        assert_eq!(runs, 1);
+    }
 
-                if let Some(hwnd) = api.find_window_by_title(&search_title) {
-                    // Position the window
-                    if let Err(e) = api.position_window(hwnd, x, y, width, height) {
-                        error!("Failed to position window for {}: {}", app.name, e);
-                    } else {
-                        info!(
-                            "Successfully positioned {} at ({}, {}) with size {}x{}",
-                            app.name, x, y, width, height
-                        );
-                    }
-                } else {
-                    warn!(
-                        "Could not find window for {} (searched for: {})",
-                        app.name, search_title
-                    );
-                }
-            } else {
-                error!("Monitor {} not found for {}", app.display, app.name);
-            }
-        }
+    /// No pause between apps, and a window that isn't there yet is only
+    /// waited for when the app has a `launch_timeout_ms`
+    const TEST_PACING: Pacing = Pacing {
+        launch_spacing: Duration::ZERO,
+        poll_interval: Duration::from_millis(5),
+        window_wait: Duration::ZERO,
+        prefetch: true,
+        follow_up_moves: true,
+    };
 
-        info!("All applications launched and positioned!");
-        Ok(())
+    fn launch_and_position_applications_mock(
+        config: &Config,
+        api: &dyn WindowsApiTrait,
+    ) -> Result<LaunchReport, String> {
+        launch_and_position_applications_mock_with(config, api, &LaunchOptions::default())
     }
 
-    fn calculate_mock_window_position(
-        monitor: &crate::mock::MockMonitorInfo,
-        side: &Side,
-    ) -> (i32, i32, i32, i32) {
-        let work_area = &monitor.work_area;
-        let width = work_area.right - work_area.left;
-        let height = work_area.bottom - work_area.top;
-
-        match side {
-            Side::Left => (work_area.left, work_area.top, width / 2, height),
-            Side::Right => (work_area.left + width / 2, work_area.top, width / 2, height),
-        }
+    /// Runs the launch loop with `api` launching the apps and finding and
+    /// moving their windows
+    fn launch_and_position_applications_mock_with(
+        config: &Config,
+        api: &dyn WindowsApiTrait,
+        options: &LaunchOptions,
+    ) -> Result<LaunchReport, String> {
+        let desktop = MockDesktop::new(api);
+        let options = LaunchOptions {
+            pacing: TEST_PACING,
+            ..options.clone()
+        };
+        launch_and_position_on(
+            config,
+            &desktop,
+            &desktop,
+            &options,
+            &mut IdentityStore::default(),
+            &mut UndoSnapshot::default(),
+        )
     }
 }
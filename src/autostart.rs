@@ -0,0 +1,94 @@
+use std::path::Path;
+
+/// Name of the value under the Run key
+#[cfg(windows)]
+const RUN_VALUE_NAME: &str = "Jumpstart";
+
+#[cfg(windows)]
+const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+
+/// The command line registered to run at login
+pub fn autostart_command(exe: &Path, config: &Path, delay_secs: u64) -> String {
+    let mut command = format!("\"{}\" --cli -f \"{}\"", exe.display(), config.display());
+    if delay_secs > 0 {
+        command.push_str(&format!(" --delay {}", delay_secs));
+    }
+    command
+}
+
+/// Registers `command` in the current user's Run key
+#[cfg(windows)]
+pub fn install(command: &str) -> Result<(), String> {
+    use widestring::U16CString;
+    use winapi::shared::minwindef::DWORD;
+    use winapi::shared::winerror::ERROR_SUCCESS;
+    use winapi::um::winnt::REG_SZ;
+    use winapi::um::winreg::{HKEY_CURRENT_USER, RegSetKeyValueW};
+
+    let key = U16CString::from_str(RUN_KEY).map_err(|e| e.to_string())?;
+    let name = U16CString::from_str(RUN_VALUE_NAME).map_err(|e| e.to_string())?;
+    let value = U16CString::from_str(command).map_err(|e| e.to_string())?;
+    let bytes = (value.len() + 1) * 2;
+
+    let status = unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            key.as_ptr(),
+            name.as_ptr(),
+            REG_SZ,
+            value.as_ptr() as *const _,
+            bytes as DWORD,
+        )
+    };
+    if status as DWORD != ERROR_SUCCESS {
+        return Err(format!("Failed to write the Run key (error {})", status));
+    }
+    Ok(())
+}
+
+/// Removes the Run key entry. Succeeds if it was not installed.
+#[cfg(windows)]
+pub fn uninstall() -> Result<(), String> {
+    use widestring::U16CString;
+    use winapi::shared::minwindef::DWORD;
+    use winapi::shared::winerror::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+    use winapi::um::winreg::{HKEY_CURRENT_USER, RegDeleteKeyValueW};
+
+    let key = U16CString::from_str(RUN_KEY).map_err(|e| e.to_string())?;
+    let name = U16CString::from_str(RUN_VALUE_NAME).map_err(|e| e.to_string())?;
+
+    let status = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr()) };
+    match status as DWORD {
+        ERROR_SUCCESS | ERROR_FILE_NOT_FOUND => Ok(()),
+        _ => Err(format!(
+            "Failed to remove the Run key entry (error {})",
+            status
+        )),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn install(_command: &str) -> Result<(), String> {
+    Err("Autostart is only supported on Windows".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn uninstall() -> Result<(), String> {
+    Err("Autostart is only supported on Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autostart_command() {
+        let exe = Path::new("C:\\Tools\\jumpstart.exe");
+        let config = Path::new("C:\\Users\\me\\My Configs\\desk.yml");
+        assert_eq!(
+            autostart_command(exe, config, 30),
+            "\"C:\\Tools\\jumpstart.exe\" --cli -f \"C:\\Users\\me\\My Configs\\desk.yml\" --delay 30"
+        );
+        assert!(!autostart_command(exe, config, 0).contains("--delay"));
+    }
+}
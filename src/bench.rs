@@ -0,0 +1,201 @@
+use crate::app_launcher::{
+    self, DEFAULT_WINDOW_WAIT, LAUNCH_SPACING, LaunchOptions, LaunchProgress, SMOOTH_MOVE_MS,
+    ShellLauncher,
+};
+use crate::conditions::MachineIdentity;
+use crate::config::Config;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Timings of one application for `jumpstart bench`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchRow {
+    pub name: String,
+    /// Time the launcher spends on the app however quickly it starts: the
+    /// pause before the next app, a smooth move and `enforce` checks
+    pub minimum: Duration,
+    /// The most the launcher waits for the app's `wait_for` service and window
+    pub max_wait: Duration,
+    /// Wall-clock time of a real launch, until the next app starts
+    pub measured: Option<Duration>,
+    /// Why the app isn't launched, e.g. because it is disabled
+    pub skipped: Option<String>,
+}
+
+/// The fixed delays and the waits of each app in `config`
+pub fn plan(config: &Config) -> Vec<BenchRow> {
+    let machine = MachineIdentity::current();
+    config
+        .applications
+        .iter()
+        .map(|app| {
+            let skipped = app.skip_reason(&machine);
+            let (minimum, max_wait) = if skipped.is_some() {
+                (Duration::ZERO, Duration::ZERO)
+            } else {
+                let mut minimum = LAUNCH_SPACING;
+                if app.smooth_move {
+                    minimum += Duration::from_millis(SMOOTH_MOVE_MS);
+                }
+                if let Some(ref enforce) = app.enforce {
+                    minimum += Duration::from_millis(enforce.interval_ms) * enforce.retries;
+                }
+                let window_wait = app
+                    .launch_timeout_ms
+                    .map(Duration::from_millis)
+                    .unwrap_or(DEFAULT_WINDOW_WAIT);
+                let service_wait = app.wait_for.as_ref().map_or(Duration::ZERO, |wait_for| {
+                    Duration::from_millis(wait_for.timeout_ms)
+                });
+                (minimum, window_wait + service_wait)
+            };
+            BenchRow {
+                name: app.name.clone(),
+                minimum,
+                max_wait,
+                measured: None,
+                skipped,
+            }
+        })
+        .collect()
+}
+
+/// Launches `config` for real, filling in each row's measured time from the
+/// launcher's progress events. Returns the time the whole launch took.
+pub fn measure(config: &Config, rows: &mut [BenchRow]) -> Result<Duration, String> {
+    let (sender, receiver) = mpsc::channel();
+    // Timestamp the events as they arrive; an app's time runs until the next one starts
+    let timer = thread::spawn(move || {
+        receiver
+            .into_iter()
+            .filter_map(|event| match event {
+                LaunchProgress::Started { index, .. } => Some((index, Instant::now())),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let options = LaunchOptions {
+        progress: Some(sender),
+        ..Default::default()
+    };
+    let launcher = ShellLauncher::for_config(config);
+    let start = Instant::now();
+    let result = app_launcher::launch_and_position_applications(config, &launcher, &options);
+    let end = Instant::now();
+    // Dropping the sender ends the timer thread
+    drop(options);
+    let starts = timer
+        .join()
+        .map_err(|_| "Timing the launch failed".to_string())?;
+    result?;
+
+    for (position, &(index, started)) in starts.iter().enumerate() {
+        let next = starts.get(position + 1).map_or(end, |&(_, next)| next);
+        if let Some(row) = rows.get_mut(index) {
+            row.measured = Some(next - started);
+        }
+    }
+    Ok(end - start)
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.1} s", duration.as_secs_f64())
+}
+
+/// The rows as a text table with a total line, including a measured column
+/// when there is a `total` from a real launch
+pub fn format_table(rows: &[BenchRow], total: Option<Duration>) -> Vec<String> {
+    let width = rows
+        .iter()
+        .map(|row| row.name.chars().count())
+        .chain(["Total".len()])
+        .max()
+        .unwrap_or_default();
+    let line = |name: &str, minimum: String, max_wait: String, measured: Option<String>| {
+        let mut line = format!("{:<width$}  {:>9}  {:>9}", name, minimum, max_wait);
+        if let Some(measured) = measured {
+            line.push_str(&format!("  {:>9}", measured));
+        }
+        line.trim_end().to_string()
+    };
+    let measured_column = |value: Option<String>| total.map(|_| value.unwrap_or_default());
+
+    let mut lines = vec![line(
+        "App",
+        "Minimum".to_string(),
+        "Max wait".to_string(),
+        measured_column(Some("Measured".to_string())),
+    )];
+    for row in rows {
+        lines.push(match row.skipped {
+            Some(ref reason) => format!("{:<width$}  skipped ({})", row.name, reason),
+            None => line(
+                &row.name,
+                seconds(row.minimum),
+                seconds(row.max_wait),
+                measured_column(row.measured.map(seconds)),
+            ),
+        });
+    }
+    lines.push(line(
+        "Total",
+        seconds(rows.iter().map(|row| row.minimum).sum()),
+        seconds(rows.iter().map(|row| row.max_wait).sum()),
+        measured_column(total.map(seconds)),
+    ));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_table() {
+        let yaml = r#"
+applications:
+  - name: "Editor"
+    side: "left"
+    executable: "editor.exe"
+    smooth_move: true
+  - name: "Chat"
+    side: "right"
+    executable: "chat.exe"
+    launch_timeout_ms: 10000
+    enforce:
+      retries: 3
+      interval_ms: 500
+  - name: "Games"
+    side: "left"
+    executable: "games.exe"
+    enabled: false
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let mut rows = plan(&config);
+        assert_eq!(rows[0].minimum, Duration::from_millis(2200));
+        assert_eq!(rows[0].max_wait, DEFAULT_WINDOW_WAIT);
+        assert_eq!(rows[1].minimum, Duration::from_millis(3500));
+        assert_eq!(rows[1].max_wait, Duration::from_secs(10));
+        assert_eq!(rows[2].skipped.as_deref(), Some("disabled"));
+
+        assert_eq!(
+            format_table(&rows, None),
+            vec![
+                "App       Minimum   Max wait",
+                "Editor      2.2 s      5.0 s",
+                "Chat        3.5 s     10.0 s",
+                "Games   skipped (disabled)",
+                "Total       5.7 s     15.0 s",
+            ]
+        );
+
+        rows[0].measured = Some(Duration::from_millis(4100));
+        rows[1].measured = Some(Duration::from_millis(3600));
+        let table = format_table(&rows, Some(Duration::from_millis(7700)));
+        assert_eq!(table[0], "App       Minimum   Max wait   Measured");
+        assert_eq!(table[1], "Editor      2.2 s      5.0 s      4.1 s");
+        assert_eq!(table[4], "Total       5.7 s     15.0 s      7.7 s");
+    }
+}
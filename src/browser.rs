@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::{debug, warn};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserKind {
+    Chrome,
+    Edge,
+    Firefox,
+}
+
+/// A web app opened in a specific browser (and optionally a specific profile)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrowserLaunch {
+    pub kind: BrowserKind,
+    pub url: String,
+    /// Browser profile: the profile directory name (e.g. "Profile 2") for
+    /// Chrome/Edge, or the profile name for Firefox
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Open the URL as a standalone app window without browser UI (Chrome/Edge only)
+    #[serde(default)]
+    pub app_mode: bool,
+}
+
+impl BrowserLaunch {
+    /// Builds the browser-specific command line arguments
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        match self.kind {
+            BrowserKind::Chrome | BrowserKind::Edge => {
+                if let Some(ref profile) = self.profile {
+                    args.push(format!("--profile-directory={}", profile));
+                }
+                if self.app_mode {
+                    args.push(format!("--app={}", self.url));
+                } else {
+                    args.push(self.url.clone());
+                }
+            }
+            BrowserKind::Firefox => {
+                if let Some(ref profile) = self.profile {
+                    args.push("-P".to_string());
+                    args.push(profile.clone());
+                }
+                if self.app_mode {
+                    warn!(
+                        "Firefox has no app mode; opening {} in a new window",
+                        self.url
+                    );
+                }
+                args.push("-new-window".to_string());
+                args.push(self.url.clone());
+            }
+        }
+        args
+    }
+}
+
+impl BrowserKind {
+    /// Windows executable name, which `start` can resolve through App Paths
+    pub fn windows_executable(&self) -> &'static str {
+        match self {
+            BrowserKind::Chrome => "chrome.exe",
+            BrowserKind::Edge => "msedge.exe",
+            BrowserKind::Firefox => "firefox.exe",
+        }
+    }
+
+    /// Executable names to look for on PATH
+    fn executable_names(&self) -> &'static [&'static str] {
+        if cfg!(windows) {
+            std::slice::from_ref(match self {
+                BrowserKind::Chrome => &"chrome.exe",
+                BrowserKind::Edge => &"msedge.exe",
+                BrowserKind::Firefox => &"firefox.exe",
+            })
+        } else {
+            match self {
+                BrowserKind::Chrome => &["google-chrome", "google-chrome-stable", "chromium"],
+                BrowserKind::Edge => &["microsoft-edge", "microsoft-edge-stable"],
+                BrowserKind::Firefox => &["firefox"],
+            }
+        }
+    }
+
+    /// Default install locations relative to Program Files (Windows only)
+    #[cfg(windows)]
+    fn install_paths(&self) -> &'static [&'static str] {
+        match self {
+            BrowserKind::Chrome => &["Google\\Chrome\\Application\\chrome.exe"],
+            BrowserKind::Edge => &["Microsoft\\Edge\\Application\\msedge.exe"],
+            BrowserKind::Firefox => &["Mozilla Firefox\\firefox.exe"],
+        }
+    }
+}
+
+/// Finds the first of `names` in the directories of a PATH-style list
+pub fn find_in_path(path_var: &std::ffi::OsStr, names: &[&str]) -> Option<PathBuf> {
+    std::env::split_paths(path_var).find_map(|dir| {
+        names
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Resolves the browser executable via PATH, then the registry's App Paths,
+/// then the default install locations
+pub fn resolve_browser_executable(kind: BrowserKind) -> Option<PathBuf> {
+    let names = kind.executable_names();
+
+    if let Some(path_var) = std::env::var_os("PATH")
+        && let Some(found) = find_in_path(&path_var, names)
+    {
+        debug!("Found {:?} on PATH: {}", kind, found.display());
+        return Some(found);
+    }
+
+    #[cfg(windows)]
+    {
+        if let Some(found) = names.iter().find_map(|name| app_path_from_registry(name)) {
+            debug!("Found {:?} via App Paths: {}", kind, found.display());
+            return Some(found);
+        }
+
+        for var in ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"] {
+            let Some(base) = std::env::var_os(var) else {
+                continue;
+            };
+            if let Some(found) = kind
+                .install_paths()
+                .iter()
+                .map(|relative| std::path::Path::new(&base).join(relative))
+                .find(|candidate| candidate.is_file())
+            {
+                return Some(found);
+            }
+        }
+    }
+
+    None
+}
+
+/// Reads `HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\App Paths\<name>`
+#[cfg(windows)]
+fn app_path_from_registry(name: &str) -> Option<PathBuf> {
+    use widestring::U16CString;
+    use winapi::shared::minwindef::DWORD;
+    use winapi::shared::winerror::ERROR_SUCCESS;
+    use winapi::um::winreg::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ, RegGetValueW};
+
+    let subkey = U16CString::from_str(format!(
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\App Paths\\{}",
+        name
+    ))
+    .ok()?;
+
+    for root in [HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE] {
+        let mut buffer = [0u16; 1024];
+        let mut size = (buffer.len() * 2) as DWORD;
+        let status = unsafe {
+            RegGetValueW(
+                root,
+                subkey.as_ptr(),
+                std::ptr::null(),
+                RRF_RT_REG_SZ,
+                std::ptr::null_mut(),
+                buffer.as_mut_ptr() as *mut _,
+                &mut size,
+            )
+        };
+        if status as DWORD == ERROR_SUCCESS {
+            let value = U16CString::from_vec_truncate(buffer.to_vec()).to_string_lossy();
+            let path = PathBuf::from(value.trim_matches('"'));
+            if path.is_file() {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_chrome_app_mode_args() {
+        let launch = BrowserLaunch {
+            kind: BrowserKind::Chrome,
+            url: "https://calendar.example.com".to_string(),
+            profile: Some("Profile 2".to_string()),
+            app_mode: true,
+        };
+        assert_eq!(
+            launch.args(),
+            vec![
+                "--profile-directory=Profile 2",
+                "--app=https://calendar.example.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_edge_and_firefox_args() {
+        let edge = BrowserLaunch {
+            kind: BrowserKind::Edge,
+            url: "https://mail.example.com".to_string(),
+            profile: None,
+            app_mode: false,
+        };
+        assert_eq!(edge.args(), vec!["https://mail.example.com"]);
+
+        let firefox = BrowserLaunch {
+            kind: BrowserKind::Firefox,
+            url: "https://docs.example.com".to_string(),
+            profile: Some("work".to_string()),
+            app_mode: false,
+        };
+        assert_eq!(
+            firefox.args(),
+            vec!["-P", "work", "-new-window", "https://docs.example.com"]
+        );
+    }
+
+    #[test]
+    fn test_find_in_path() {
+        let dir = std::env::temp_dir().join("jumpstart_browser_path_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("firefox"), "").unwrap();
+
+        let path_var = std::env::join_paths([Path::new("/nonexistent"), dir.as_path()]).unwrap();
+        assert_eq!(
+            find_in_path(&path_var, &["chrome", "firefox"]),
+            Some(dir.join("firefox"))
+        );
+        assert_eq!(find_in_path(&path_var, &["chrome"]), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+use crate::config::{Application, Config, DisplaySelector, Side};
+use crate::display::{Monitor, Rect};
+
+/// A window on the desktop, as it goes into a captured config
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedWindow {
+    pub title: String,
+    pub executable: String,
+    /// 1-based display number
+    pub display: u32,
+    pub side: Side,
+}
+
+/// The half of `monitor` the window's center is on
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn infer_side(rect: Rect, monitor: &Monitor) -> Side {
+    let (x, _) = rect.center();
+    if x < monitor.work_area.x + monitor.work_area.width / 2 {
+        Side::Left
+    } else {
+        Side::Right
+    }
+}
+
+/// A config that launches each window's executable and puts it back on its
+/// current display and side. The window titles become the app names, which
+/// are also what finds the windows, so they usually need shortening.
+pub fn config_from_windows(windows: &[CapturedWindow]) -> Config {
+    Config {
+        applications: windows
+            .iter()
+            .map(|window| Application {
+                name: window.title.clone(),
+                display: Some(DisplaySelector::Number(window.display)),
+                side: Some(window.side.clone()),
+                executable: window.executable.clone(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Lists the visible top-level windows of other processes
+#[cfg(windows)]
+pub fn capture_windows() -> Vec<CapturedWindow> {
+    use crate::monitor::{get_monitors, monitor_of_window};
+    use crate::window::{
+        enumerate_windows, get_window_rect, is_window_visible, process_image_path,
+    };
+    use winapi::shared::windef::HWND;
+
+    let monitors = get_monitors();
+    let own_process = std::process::id();
+    enumerate_windows()
+        .into_iter()
+        // The desktop itself is a visible window owned by Explorer
+        .filter(|window| window.process_id != own_process && window.title != "Program Manager")
+        .filter_map(|window| {
+            let hwnd = window.hwnd as HWND;
+            if !is_window_visible(hwnd) {
+                return None;
+            }
+            let rect = get_window_rect(hwnd).filter(|rect| rect.width > 0 && rect.height > 0)?;
+            let monitor = monitor_of_window(hwnd, &monitors)?;
+            let executable = process_image_path(window.process_id)?;
+            Some(CapturedWindow {
+                side: infer_side(rect, monitor),
+                display: monitor.index as u32 + 1,
+                title: window.title,
+                executable,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn capture_windows() -> Vec<CapturedWindow> {
+    tracing::warn!("Capturing windows is only supported on Windows.");
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config_content;
+    use crate::display::test_monitor;
+
+    #[test]
+    fn test_capture_config() {
+        let monitor = test_monitor(1, "\\\\.\\DISPLAY2", Rect::new(1920, 0, 2560, 1440));
+        assert_eq!(
+            infer_side(Rect::new(1920, 0, 1280, 1400), &monitor),
+            Side::Left
+        );
+        assert_eq!(
+            infer_side(Rect::new(2900, 100, 1500, 1200), &monitor),
+            Side::Right
+        );
+
+        let windows = vec![
+            CapturedWindow {
+                title: "Inbox - Outlook".to_string(),
+                executable: "C:\\Program Files\\Microsoft Office\\OUTLOOK.EXE".to_string(),
+                display: 2,
+                side: Side::Right,
+            },
+            CapturedWindow {
+                title: "notes.md - Editor".to_string(),
+                executable: "C:\\Tools\\editor.exe".to_string(),
+                display: 1,
+                side: Side::Left,
+            },
+        ];
+        let yaml = serde_yaml::to_string(&config_from_windows(&windows)).unwrap();
+        let config = parse_config_content(&yaml).unwrap();
+        let outlook = &config.applications[0];
+        assert_eq!(outlook.name, "Inbox - Outlook");
+        assert_eq!(outlook.display, Some(DisplaySelector::Number(2)));
+        assert_eq!(outlook.side, Some(Side::Right));
+        assert_eq!(
+            outlook.executable,
+            "C:\\Program Files\\Microsoft Office\\OUTLOOK.EXE"
+        );
+        assert_eq!(config.applications[1].side, Some(Side::Left));
+    }
+}
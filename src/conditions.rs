@@ -0,0 +1,186 @@
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+
+/// Restricts an application to machines whose hostname and/or username match.
+/// Patterns are case-insensitive regular expressions that must match the whole value.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Conditions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<SessionKind>,
+}
+
+/// How the user is signed in to the machine
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionKind {
+    /// At the machine itself
+    #[default]
+    Console,
+    /// Over Remote Desktop, where the monitors are the client's
+    Remote,
+}
+
+impl std::fmt::Display for SessionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionKind::Console => write!(f, "console"),
+            SessionKind::Remote => write!(f, "remote"),
+        }
+    }
+}
+
+/// The values conditions are evaluated against
+#[derive(Debug, Clone, PartialEq)]
+pub struct MachineIdentity {
+    pub hostname: String,
+    pub username: String,
+    pub session: SessionKind,
+}
+
+impl MachineIdentity {
+    pub fn current() -> Self {
+        Self {
+            hostname: current_hostname(),
+            username: current_username(),
+            session: current_session(),
+        }
+    }
+}
+
+#[cfg(windows)]
+fn current_session() -> SessionKind {
+    use winapi::um::winuser::{GetSystemMetrics, SM_REMOTESESSION};
+    if unsafe { GetSystemMetrics(SM_REMOTESESSION) } != 0 {
+        SessionKind::Remote
+    } else {
+        SessionKind::Console
+    }
+}
+
+#[cfg(not(windows))]
+fn current_session() -> SessionKind {
+    SessionKind::Console
+}
+
+fn current_hostname() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .or_else(|_| std::fs::read_to_string("/etc/hostname").map(|name| name.trim().to_string()))
+        .unwrap_or_default()
+}
+
+fn current_username() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_default()
+}
+
+fn matches(pattern: &str, value: &str) -> Result<bool, String> {
+    let regex = RegexBuilder::new(&format!("^(?:{})$", pattern))
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+    Ok(regex.is_match(value))
+}
+
+impl Conditions {
+    /// Checks that every pattern compiles
+    pub fn validate(&self) -> Result<(), String> {
+        for pattern in [&self.hostname, &self.username].into_iter().flatten() {
+            matches(pattern, "")?;
+        }
+        Ok(())
+    }
+
+    /// `Ok(())` when the machine satisfies every condition, otherwise the reason it doesn't
+    pub fn evaluate(&self, machine: &MachineIdentity) -> Result<(), String> {
+        let checks = [
+            ("hostname", &self.hostname, &machine.hostname),
+            ("username", &self.username, &machine.username),
+        ];
+        for (label, pattern, value) in checks {
+            let Some(pattern) = pattern else {
+                continue;
+            };
+            if !matches(pattern, value)? {
+                return Err(format!(
+                    "{} '{}' does not match '{}'",
+                    label, value, pattern
+                ));
+            }
+        }
+        if let Some(session) = self.session
+            && session != machine.session
+        {
+            return Err(format!("session is {}, not {}", machine.session, session));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn machine(hostname: &str, username: &str) -> MachineIdentity {
+        MachineIdentity {
+            hostname: hostname.to_string(),
+            username: username.to_string(),
+            session: SessionKind::Console,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_conditions() {
+        let work = Conditions {
+            hostname: Some("work-.*".to_string()),
+            ..Default::default()
+        };
+        assert!(work.evaluate(&machine("WORK-LAPTOP", "shawon")).is_ok());
+        assert_eq!(
+            work.evaluate(&machine("home-pc", "shawon")).unwrap_err(),
+            "hostname 'home-pc' does not match 'work-.*'"
+        );
+        // Patterns must match the whole value
+        assert!(work.evaluate(&machine("old-work-pc", "shawon")).is_err());
+
+        let both = Conditions {
+            hostname: Some("desk|laptop".to_string()),
+            username: Some("shawon".to_string()),
+            session: None,
+        };
+        assert!(both.evaluate(&machine("laptop", "Shawon")).is_ok());
+        assert!(both.evaluate(&machine("laptop", "guest")).is_err());
+
+        assert!(Conditions::default().evaluate(&machine("", "")).is_ok());
+    }
+
+    #[test]
+    fn test_session_condition() {
+        let console_only = Conditions {
+            session: Some(SessionKind::Console),
+            ..Default::default()
+        };
+        let mut machine = machine("desk", "me");
+        assert!(console_only.evaluate(&machine).is_ok());
+        machine.session = SessionKind::Remote;
+        assert_eq!(
+            console_only.evaluate(&machine).unwrap_err(),
+            "session is remote, not console"
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let conditions = Conditions {
+            hostname: Some("work-(".to_string()),
+            ..Default::default()
+        };
+        assert!(conditions.validate().is_err());
+        assert!(conditions.evaluate(&machine("work-pc", "me")).is_err());
+    }
+}
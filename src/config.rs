@@ -1,7 +1,20 @@
+use crate::browser::BrowserLaunch;
+use crate::conditions::{Conditions, MachineIdentity};
+use crate::display::{Monitor, Rect};
+use crate::interpolate::{env_lookup, interpolate};
+use crate::known_folders::resolve_known_folders;
+use crate::matching::{IgnoreList, MatchCriteria};
+use crate::readiness::WaitFor;
+use crate::remote;
+use crate::schedule;
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Side {
+    #[default]
     Left,
     Right,
 }
@@ -20,31 +33,1388 @@ impl<'de> Deserialize<'de> for Side {
     }
 }
 
+impl Side {
+    /// Every variant, for UIs that list them
+    pub const ALL: [Side; 2] = [Side::Left, Side::Right];
+
+    /// Splits a span of `total` pixels, returning the `(offset, length)` this side occupies.
+    /// `Left` takes `total * ratio` from the start; `Right` takes `total * ratio` anchored to
+    /// the end, computed as the remainder of the complementary left split so that two apps
+    /// with complementary ratios (e.g. 0.6 and 0.4) tile the span without gaps or overlap.
+    pub fn split(&self, total: i32, ratio: f32) -> (i32, i32) {
+        match self {
+            Side::Left => (0, (total as f32 * ratio).round() as i32),
+            Side::Right => {
+                let offset = (total as f32 * (1.0 - ratio)).round() as i32;
+                (offset, total - offset)
+            }
+        }
+    }
+}
+
+/// Which monitor an application goes on: a 1-based display number, a
+/// monitor name such as "Dell U2720Q" or "primary", or a list of these to
+/// try in order
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(untagged)]
+pub enum DisplaySelector {
+    Number(u32),
+    Name(String),
+    Fallback(Vec<DisplaySelector>),
+}
+
+impl Default for DisplaySelector {
+    fn default() -> Self {
+        DisplaySelector::Number(1)
+    }
+}
+
+impl From<u32> for DisplaySelector {
+    fn from(number: u32) -> Self {
+        DisplaySelector::Number(number)
+    }
+}
+
+impl PartialEq<u32> for DisplaySelector {
+    fn eq(&self, other: &u32) -> bool {
+        matches!(self, DisplaySelector::Number(number) if number == other)
+    }
+}
+
+impl std::fmt::Display for DisplaySelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisplaySelector::Number(number) => write!(f, "{}", number),
+            DisplaySelector::Name(name) => write!(f, "'{}'", name),
+            DisplaySelector::Fallback(selectors) => {
+                let selectors: Vec<String> = selectors.iter().map(ToString::to_string).collect();
+                write!(f, "[{}]", selectors.join(", "))
+            }
+        }
+    }
+}
+
+impl DisplaySelector {
+    /// Compact label for cards and lists, e.g. "D2" or "Dell U2720Q"
+    pub fn short_label(&self) -> String {
+        match self {
+            DisplaySelector::Number(number) => format!("D{}", number),
+            DisplaySelector::Name(name) => name.clone(),
+            DisplaySelector::Fallback(selectors) => {
+                let labels: Vec<String> = selectors.iter().map(Self::short_label).collect();
+                labels.join(" / ")
+            }
+        }
+    }
+
+    /// Finds the selected monitor among `monitors`, which are in display-number
+    /// order. Names match the device name exactly or any part of the friendly
+    /// name, ignoring case; "primary" is the primary monitor.
+    pub fn resolve<'a>(&self, monitors: &'a [Monitor]) -> Option<&'a Monitor> {
+        match self {
+            DisplaySelector::Number(number) => monitors.get(number.checked_sub(1)? as usize),
+            DisplaySelector::Name(name) if name.eq_ignore_ascii_case("primary") => {
+                monitors.iter().find(|monitor| monitor.primary)
+            }
+            DisplaySelector::Fallback(selectors) => resolve_display_fallback(monitors, selectors),
+            DisplaySelector::Name(name) => {
+                let name = name.to_lowercase();
+                monitors.iter().find(|monitor| {
+                    monitor.name.to_lowercase() == name
+                        || (!monitor.friendly_name.is_empty()
+                            && monitor.friendly_name.to_lowercase().contains(&name))
+                })
+            }
+        }
+    }
+}
+
+/// The first of `selectors` that matches a connected monitor, e.g. "display 3,
+/// or else display 2, or else the primary monitor"
+pub fn resolve_display_fallback<'a>(
+    monitors: &'a [Monitor],
+    selectors: &[DisplaySelector],
+) -> Option<&'a Monitor> {
+    selectors
+        .iter()
+        .find_map(|selector| selector.resolve(monitors))
+}
+
+/// Re-applies an app's position after the first move, for apps that restore
+/// their own saved position shortly after starting
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Enforce {
+    /// How many more times the position is checked and, if needed, re-applied
+    #[serde(default = "default_enforce_retries")]
+    pub retries: u32,
+    #[serde(default = "default_enforce_interval_ms")]
+    pub interval_ms: u64,
+}
+
+fn default_enforce_retries() -> u32 {
+    4
+}
+
+fn default_enforce_interval_ms() -> u64 {
+    500
+}
+
+/// A window rectangle computed from expressions over the work area's width
+/// `W` and height `H`, e.g. `w: "W*0.7 - 10"`. `x` and `y` are relative to
+/// the work area's top-left corner.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CustomRect {
+    pub x: String,
+    pub y: String,
+    pub w: String,
+    pub h: String,
+}
+
+impl CustomRect {
+    /// Evaluates the expressions for a work area of `width` x `height`
+    pub fn evaluate(&self, width: i32, height: i32) -> Result<Rect, String> {
+        use evalexpr::{
+            ContextWithMutableVariables, DefaultNumericTypes, HashMapContext, Value,
+            eval_number_with_context,
+        };
+
+        let mut context = HashMapContext::<DefaultNumericTypes>::new();
+        for (name, value) in [("W", width), ("H", height)] {
+            context
+                .set_value(name.to_string(), Value::Float(f64::from(value)))
+                .map_err(|e| e.to_string())?;
+        }
+        let evaluate = |field: &str, expression: &str| {
+            let value = eval_number_with_context(expression, &context)
+                .map_err(|e| format!("Invalid custom_rect {} '{}': {}", field, expression, e))?;
+            // Catches division by zero, which yields infinity or NaN for floats
+            if !value.is_finite() {
+                return Err(format!(
+                    "custom_rect {} '{}' is not a finite number",
+                    field, expression
+                ));
+            }
+            Ok(value.round() as i32)
+        };
+
+        let rect = Rect::new(
+            evaluate("x", &self.x)?,
+            evaluate("y", &self.y)?,
+            evaluate("w", &self.w)?,
+            evaluate("h", &self.h)?,
+        );
+        if rect.width <= 0 || rect.height <= 0 {
+            return Err(format!(
+                "custom_rect is {}x{}; width and height must be positive",
+                rect.width, rect.height
+            ));
+        }
+        Ok(rect)
+    }
+}
+
+/// Smallest window size an app accepts
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Size {
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Width divided by height, written as a number such as `1.78` or as `"16:9"`
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub struct AspectRatio(pub f64);
+
+impl<'de> Deserialize<'de> for AspectRatio {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(f64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Number(ratio) => Ok(AspectRatio(ratio)),
+            Raw::Text(text) => {
+                let invalid = || {
+                    serde::de::Error::custom(format!(
+                        "Invalid aspect ratio '{}'; expected e.g. \"16:9\"",
+                        text
+                    ))
+                };
+                let (width, height) = text.split_once(':').ok_or_else(invalid)?;
+                let width: f64 = width.trim().parse().map_err(|_| invalid())?;
+                let height: f64 = height.trim().parse().map_err(|_| invalid())?;
+                Ok(AspectRatio(width / height))
+            }
+        }
+    }
+}
+
+/// Which console a launched console program writes to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Console {
+    /// A console window of its own
+    #[default]
+    New,
+    /// Jumpstart's console
+    Inherit,
+    /// No console at all, for running a tool headless
+    None,
+}
+
+impl Console {
+    fn is_new(&self) -> bool {
+        *self == Console::New
+    }
+}
+
+/// What happens to the rest of a group when one of its apps isn't positioned
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupFailurePolicy {
+    /// Skip the group's remaining apps
+    #[default]
+    AbortGroup,
+    /// Launch the remaining apps anyway
+    Continue,
+}
+
+impl GroupFailurePolicy {
+    fn is_default(&self) -> bool {
+        *self == GroupFailurePolicy::default()
+    }
+}
+
+/// Applications that depend on each other, named by their `name`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AppGroup {
+    pub apps: Vec<String>,
+    #[serde(default, skip_serializing_if = "GroupFailurePolicy::is_default")]
+    pub on_failure: GroupFailurePolicy,
+}
+
+/// Which GPU an application should render on, on machines with two
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuPreference {
+    /// The discrete GPU
+    HighPerformance,
+    /// The integrated GPU
+    PowerSaving,
+}
+
+/// How much CPU time a launched application gets next to other programs
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessPriority {
+    Idle,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    High,
+}
+
+/// How an application is started
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchMethod {
+    /// Executables directly, anything else through the shell
+    #[default]
+    Auto,
+    /// Through `cmd /C start`
+    Shell,
+    /// As a child process of jumpstart; only works for executables
+    Direct,
+    /// Through the Windows `ShellExecuteEx` API
+    ShellExecute,
+}
+
+impl LaunchMethod {
+    fn is_auto(&self) -> bool {
+        *self == LaunchMethod::Auto
+    }
+}
+
+/// Which way a `split` slices the work area
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitAxis {
+    /// Into side-by-side columns
+    #[default]
+    Horizontal,
+    /// Into stacked rows
+    Vertical,
+}
+
+/// Where a `relative_to` app goes beside its anchor app's window
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelativePlacement {
+    /// From the anchor's right edge to the right of the work area, as tall
+    /// as the anchor
+    RightOf,
+    /// From the anchor's bottom edge to the bottom of the work area, as wide
+    /// as the anchor
+    Below,
+    /// The largest strip of the work area left, right, above or below the
+    /// anchor
+    FillRemaining,
+}
+
+impl std::fmt::Display for RelativePlacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RelativePlacement::RightOf => "right of",
+            RelativePlacement::Below => "below",
+            RelativePlacement::FillRemaining => "filling the space beside",
+        })
+    }
+}
+
+/// Places an app beside another app's window, wherever that window ended up
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RelativeTo {
+    /// Name of the anchor app, which must come earlier in the config
+    pub app: String,
+    pub placement: RelativePlacement,
+}
+
+/// Slice `index` of `count` equal slices of the work area, counting from 0
+/// at the left or top. `side: left` is sugar for `{ count: 2, index: 0 }`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Split {
+    #[serde(default)]
+    pub axis: SplitAxis,
+    pub count: u32,
+    pub index: u32,
+}
+
+impl std::fmt::Display for Split {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let slice = match self.axis {
+            SplitAxis::Horizontal => "column",
+            SplitAxis::Vertical => "row",
+        };
+        write!(f, "{} {} of {}", slice, self.index + 1, self.count)
+    }
+}
+
+/// What the coordinates of an explicit `geometry` are relative to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GeometryOrigin {
+    /// The top-left corner of the virtual desktop
+    #[default]
+    Desktop,
+    /// The top-left corner of the target display's work area
+    Monitor,
+}
+
+impl GeometryOrigin {
+    fn is_desktop(&self) -> bool {
+        *self == GeometryOrigin::Desktop
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Application {
     pub name: String,
-    pub display: u32,
-    pub side: Side,
+    /// Monitor number (1-based) or monitor name. Omitted displays default to
+    /// 1, or are spread across the monitors with `auto_distribute`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<DisplaySelector>,
+    /// May be left out when `has_explicit_placement`; see `side()`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<Side>,
+    /// Path to the executable; may be omitted when `browser` is set
+    #[serde(default)]
     pub executable: String,
+    /// Extra command line arguments passed to the executable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Console for console programs: a new one, jumpstart's, or none
+    #[serde(default, skip_serializing_if = "Console::is_new")]
+    pub console: Console,
+    /// Overrides how the application is started instead of choosing by
+    /// its `executable`
+    #[serde(default, skip_serializing_if = "LaunchMethod::is_auto")]
+    pub launch_method: LaunchMethod,
+    /// GPU to start the application on, e.g. a game on the discrete GPU
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefer_gpu: Option<GpuPreference>,
+    /// Priority of the launched process, e.g. `idle` for a background sync tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<ProcessPriority>,
+    /// Directory the application is started in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// File or document to open, passed after `args`. Relative paths are
+    /// resolved against the config file's directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_file: Option<String>,
+    /// Open a web app in a browser profile instead of (or via) `executable`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser: Option<BrowserLaunch>,
+    /// Fraction (0.0–1.0) of the monitor width this app's side takes
+    #[serde(default = "default_split_ratio")]
+    pub split_ratio: f32,
+    /// One of several equal columns or rows, used instead of `side` and
+    /// `split_ratio`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split: Option<Split>,
+    /// Maximum time to wait for the application's window before giving up on it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_timeout_ms: Option<u64>,
+    /// Disabled applications stay in the config but are skipped when launching
+    #[serde(default = "default_enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+    /// Explicit window rectangle, used instead of `side` and `split_ratio`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<Rect>,
+    /// Window rectangle computed from the work area size, used instead of `side`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_rect: Option<CustomRect>,
+    /// Command that works out the window's rectangle, overriding the
+    /// config's `placement_script`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placement_script: Option<String>,
+    /// Beside another app's window as it actually landed, used instead of `side`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_to: Option<RelativeTo>,
+    /// Centered on its display at the size the window already has, used
+    /// instead of `side`
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub center_fixed: bool,
+    /// Pixels left free on every side of the window, overriding the
+    /// config's `gap` and `outer_gap`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap: Option<u32>,
+    /// Whether `geometry` is in desktop coordinates or relative to the display
+    #[serde(default, skip_serializing_if = "GeometryOrigin::is_desktop")]
+    pub geometry_origin: GeometryOrigin,
+    /// Move the window in several small steps, for apps that undo an instant move
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub smooth_move: bool,
+    /// Only position the window once its title also contains this, for apps
+    /// that show a loading title first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_when_title: Option<String>,
+    /// Rename the window to this once it appears, e.g. to tell several
+    /// terminals apart
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_title: Option<String>,
+    /// What to look for in the window title instead of the app's name, for
+    /// apps whose windows are titled otherwise, e.g. "Rider"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_title: Option<String>,
+    /// What the app's window must look like, instead of a title containing
+    /// the app's name
+    #[serde(rename = "match", default, skip_serializing_if = "Option::is_none")]
+    pub match_criteria: Option<MatchCriteria>,
+    /// Also match windows Windows keeps cloaked, e.g. a suspended Store app's
+    /// background window, which can't be moved
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub include_cloaked: bool,
+    /// Line up the window's visible frame rather than its invisible resize
+    /// borders with its slot
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub align_visible_frame: bool,
+    /// Smallest size the app's window accepts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<Size>,
+    /// Aspect ratio the app's window keeps, e.g. a video player's 16:9
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<AspectRatio>,
+    /// Stacking position among the configured windows; higher is further up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z_order: Option<i32>,
+    /// Keep putting the window back for a while, for apps that move themselves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforce: Option<Enforce>,
+    /// Launch on the desktop of the user logged on to the console, for
+    /// jumpstart running as a service (needs the `user_session` feature)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub user_session: bool,
+    /// Service that must be reachable before the application is launched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_for: Option<WaitFor>,
+    /// Only launch on machines matching these hostname/username patterns
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<Conditions>,
+}
+
+impl Default for Application {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            display: None,
+            side: Some(Side::default()),
+            executable: String::new(),
+            args: Vec::new(),
+            console: Console::default(),
+            launch_method: LaunchMethod::default(),
+            prefer_gpu: None,
+            priority: None,
+            working_dir: None,
+            open_file: None,
+            browser: None,
+            split_ratio: default_split_ratio(),
+            split: None,
+            launch_timeout_ms: None,
+            enabled: true,
+            geometry: None,
+            custom_rect: None,
+            placement_script: None,
+            relative_to: None,
+            center_fixed: false,
+            gap: None,
+            geometry_origin: GeometryOrigin::default(),
+            smooth_move: false,
+            ready_when_title: None,
+            set_title: None,
+            window_title: None,
+            match_criteria: None,
+            include_cloaked: false,
+            align_visible_frame: false,
+            min_size: None,
+            aspect_ratio: None,
+            z_order: None,
+            enforce: None,
+            user_session: false,
+            wait_for: None,
+            when: None,
+        }
+    }
+}
+
+/// File types that can be launched directly from a config entry
+const LAUNCHABLE_EXTENSIONS: &[&str] = &["exe", "lnk", "bat", "cmd"];
+
+impl Application {
+    /// Whether the window's place is set by something other than its `side`,
+    /// given the config's own `placement_script`
+    pub fn has_explicit_placement(&self, placement_script: Option<&String>) -> bool {
+        self.geometry.is_some()
+            || self.custom_rect.is_some()
+            || self.split.is_some()
+            || self.relative_to.is_some()
+            || self.center_fixed
+            || self
+                .placement_script
+                .as_ref()
+                .or(placement_script)
+                .is_some()
+    }
+
+    /// The side of the monitor the window goes on. Apps placed some other
+    /// way may leave it out, and count as on the left.
+    pub fn side(&self) -> Side {
+        self.side.clone().unwrap_or_default()
+    }
+
+    /// Every free-form string in the entry, for the `${VAR}` interpolation pass
+    fn string_fields_mut(&mut self) -> Vec<&mut String> {
+        let mut fields = vec![&mut self.name, &mut self.executable];
+        fields.extend(self.args.iter_mut());
+        fields.extend(self.working_dir.as_mut());
+        fields.extend(self.open_file.as_mut());
+        fields.extend(self.ready_when_title.as_mut());
+        fields.extend(self.set_title.as_mut());
+        fields.extend(self.window_title.as_mut());
+        fields.extend(self.placement_script.as_mut());
+        if let Some(ref mut browser) = self.browser {
+            fields.push(&mut browser.url);
+            fields.extend(browser.profile.as_mut());
+        }
+        if let Some(ref mut wait_for) = self.wait_for {
+            fields.push(&mut wait_for.tcp);
+        }
+        if let Some(ref mut when) = self.when {
+            fields.extend(when.hostname.as_mut());
+            fields.extend(when.username.as_mut());
+        }
+        fields
+    }
+
+    /// The configured `open_file`, if it doesn't exist
+    pub fn missing_open_file(&self) -> Option<&str> {
+        self.open_file
+            .as_deref()
+            .filter(|file| !Path::new(file).exists())
+    }
+
+    /// Why this application should not be launched on `machine`, if anything
+    pub fn skip_reason(&self, machine: &MachineIdentity) -> Option<String> {
+        if !self.enabled {
+            return Some("disabled".to_string());
+        }
+        self.when
+            .as_ref()
+            .and_then(|when| when.evaluate(machine).err())
+    }
+
+    /// Builds an application for an executable or shortcut, named after the file stem
+    pub fn from_executable_path(path: &Path) -> Result<Self, String> {
+        let is_launchable = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| LAUNCHABLE_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        if !is_launchable {
+            return Err(format!(
+                "'{}' is not an executable or shortcut ({})",
+                path.display(),
+                LAUNCHABLE_EXTENSIONS.join(", ")
+            ));
+        }
+
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self {
+            name,
+            display: Some(DisplaySelector::default()),
+            executable: path.to_string_lossy().into_owned(),
+            ..Default::default()
+        })
+    }
+}
+
+fn default_split_ratio() -> f32 {
+    0.5
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
+    #[serde(default)]
     pub applications: Vec<Application>,
+    /// Assign apps without a `display` to the monitors round-robin
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub auto_distribute: bool,
+    /// Show a desktop notification summarizing each CLI run
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub notify_on_complete: bool,
+    /// When `false`, windows that are already open and on their target
+    /// display are left alone instead of being moved into place
+    #[serde(default = "default_enabled", skip_serializing_if = "is_enabled")]
+    pub reposition_if_same_monitor: bool,
+    /// Pixels left free between windows sharing a monitor side by side
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub gap: u32,
+    /// Pixels left free between side-placed windows and the monitor edges
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub outer_gap: u32,
+    /// Launch and position windows without taking focus from the window
+    /// that has it
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub no_activate: bool,
+    /// Profiles to switch to in `--watch` mode by time of day, e.g.
+    /// `"09:00-12:00": morning`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub schedule: BTreeMap<String, String>,
+    /// Number of monitors the layout is meant for, e.g. a docked laptop's
+    /// three; launching with any other number fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_monitors: Option<usize>,
+    /// Longest a launch run may take; apps not reached by then are skipped,
+    /// so a hanging app can't stall an unattended run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_timeout_secs: Option<u64>,
+    /// Named sets of applications that are pointless without each other
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub groups: BTreeMap<String, AppGroup>,
+    /// Window titles never matched to an app, on top of the Windows shell's
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_titles: Vec<String>,
+    /// Window classes never matched to an app, e.g. `Progman`, on top of the
+    /// Windows shell's
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_classes: Vec<String>,
+    /// Command that works out every app's window rectangle, for apps
+    /// without a `placement_script` of their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placement_script: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            applications: Vec::new(),
+            auto_distribute: false,
+            notify_on_complete: false,
+            reposition_if_same_monitor: true,
+            gap: 0,
+            outer_gap: 0,
+            no_activate: false,
+            schedule: BTreeMap::new(),
+            expected_monitors: None,
+            total_timeout_secs: None,
+            groups: BTreeMap::new(),
+            ignore_titles: Vec::new(),
+            ignore_classes: Vec::new(),
+            placement_script: None,
+        }
+    }
+}
+
+/// Options controlling how a config file is loaded
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoadOptions {
+    /// Treat warnings as errors, e.g. undefined `${VAR}` references or two
+    /// apps in the same slot
+    pub strict: bool,
+    /// Ignore unknown fields with a warning instead of rejecting the config
+    pub lenient: bool,
+}
+
+impl Config {
+    /// Checks the executables of the apps that would launch on this machine
+    pub fn check_executables(&self) -> Vec<ConfigIssue> {
+        let machine = MachineIdentity::current();
+        self.applications
+            .iter()
+            .filter(|app| app.skip_reason(&machine).is_none())
+            .filter_map(|app| {
+                check_executable(&app.executable).map(|issue| ConfigIssue {
+                    message: format!("{} for '{}'", issue.message, app.name),
+                    ..issue
+                })
+            })
+            .collect()
+    }
+
+    /// Makes relative `open_file` paths relative to `base` instead of the
+    /// current directory
+    pub fn resolve_relative_paths(&mut self, base: &Path) {
+        for app in &mut self.applications {
+            if let Some(ref mut file) = app.open_file
+                && Path::new(file).is_relative()
+            {
+                *file = base.join(&*file).to_string_lossy().into_owned();
+            }
+        }
+    }
+
+    /// Expands `${VAR}` / `${VAR:-default}` in all string fields using `lookup`.
+    /// Undefined variables are an error in strict mode and are otherwise left
+    /// as written with a warning.
+    pub fn interpolate(
+        &mut self,
+        lookup: &dyn Fn(&str) -> Option<String>,
+        strict: bool,
+    ) -> Result<(), String> {
+        for app in &mut self.applications {
+            let mut undefined = Vec::new();
+            for field in app.string_fields_mut() {
+                *field = interpolate(field, lookup, &mut undefined);
+            }
+            app.executable = resolve_known_folders(&app.executable);
+            if let Some(ref mut dir) = app.working_dir {
+                *dir = resolve_known_folders(dir);
+            }
+            if undefined.is_empty() {
+                continue;
+            }
+
+            undefined.dedup();
+            let message = format!(
+                "Undefined variable(s) {} in '{}'",
+                undefined.join(", "),
+                app.name
+            );
+            if strict {
+                return Err(message);
+            }
+            warn!("{}; leaving them as written", message);
+        }
+        Ok(())
+    }
+
+    /// The display each application goes on, given how many monitors are
+    /// connected. Explicit displays always win; the rest go on display 1, or
+    /// round-robin across the monitors in config order with `auto_distribute`.
+    pub fn assign_displays(&self, monitor_count: usize) -> Vec<DisplaySelector> {
+        let mut next = 0;
+        self.applications
+            .iter()
+            .map(|app| match app.display {
+                Some(ref display) => display.clone(),
+                None if self.auto_distribute && monitor_count > 0 => {
+                    let number = next % monitor_count + 1;
+                    next += 1;
+                    DisplaySelector::Number(number as u32)
+                }
+                None => DisplaySelector::default(),
+            })
+            .collect()
+    }
+
+    /// Apps that would launch on this machine into the same side or `split`
+    /// slice of the same display, so one window covers the other. Apps with an
+    /// explicit `geometry` or `custom_rect`, apps a `placement_script` places
+    /// and apps `auto_distribute` places are left out.
+    pub fn slot_conflicts(&self) -> Vec<String> {
+        let machine = MachineIdentity::current();
+        let mut taken: BTreeMap<(DisplaySelector, String), &str> = BTreeMap::new();
+        let mut conflicts = Vec::new();
+        for app in &self.applications {
+            if app.skip_reason(&machine).is_some()
+                || app.geometry.is_some()
+                || app.custom_rect.is_some()
+                || app.relative_to.is_some()
+                || app.center_fixed
+                || app
+                    .placement_script
+                    .as_ref()
+                    .or(self.placement_script.as_ref())
+                    .is_some()
+                || (app.display.is_none() && self.auto_distribute)
+            {
+                continue;
+            }
+            let display = app.display.clone().unwrap_or_default();
+            let place = match app.split {
+                Some(split) => split.to_string(),
+                None => format!("the {} side", format!("{:?}", app.side()).to_lowercase()),
+            };
+            let slot = (display.clone(), place);
+            match taken.get(&slot) {
+                Some(other) => conflicts.push(format!(
+                    "'{}' and '{}' both take {} of display {}",
+                    other, app.name, slot.1, display
+                )),
+                None => {
+                    taken.insert(slot, &app.name);
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Application indices grouped by `display` (ascending), left before right within a group
+    pub fn indices_by_display(&self, monitor_count: usize) -> Vec<(DisplaySelector, Vec<usize>)> {
+        let mut groups: BTreeMap<DisplaySelector, Vec<usize>> = BTreeMap::new();
+        for (index, display) in self.assign_displays(monitor_count).into_iter().enumerate() {
+            groups.entry(display).or_default().push(index);
+        }
+        groups
+            .into_iter()
+            .map(|(display, mut indices)| {
+                indices.sort_by_key(|&i| self.applications[i].side());
+                (display, indices)
+            })
+            .collect()
+    }
+
+    /// The windows to pass over when matching: the Windows shell's plus the
+    /// config's `ignore_titles` and `ignore_classes`
+    pub fn ignore_list(&self) -> IgnoreList {
+        IgnoreList::extended(&self.ignore_titles, &self.ignore_classes)
+    }
+
+    /// The group `app_name` belongs to, if any
+    pub fn group_of(&self, app_name: &str) -> Option<(&str, &AppGroup)> {
+        self.groups
+            .iter()
+            .find(|(_, group)| group.apps.iter().any(|app| app == app_name))
+            .map(|(name, group)| (name.as_str(), group))
+    }
+
+    fn validate_groups(&self) -> Result<(), String> {
+        let mut grouped: BTreeMap<&str, &str> = BTreeMap::new();
+        for (name, group) in &self.groups {
+            for app in &group.apps {
+                if !self.applications.iter().any(|a| &a.name == app) {
+                    return Err(format!(
+                        "Group '{}' lists unknown application '{}'",
+                        name, app
+                    ));
+                }
+                if let Some(other) = grouped.insert(app, name) {
+                    return Err(format!(
+                        "Application '{}' is in both group '{}' and group '{}'",
+                        app, other, name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The anchor app must be placed first, so it has to come earlier. When
+    /// no order can do that because apps are placed relative_to each other
+    /// in a loop, the error names the apps in the loop instead.
+    fn validate_anchor(&self, app: &Application, relative: &RelativeTo) -> Result<(), String> {
+        let position = |name: &str| self.applications.iter().position(|a| a.name == name);
+        match (position(&relative.app), position(&app.name)) {
+            (None, _) => Err(format!(
+                "Application '{}' is relative_to unknown application '{}'",
+                app.name, relative.app
+            )),
+            (Some(anchor), Some(index)) if anchor >= index => match self.relative_to_loop(app) {
+                Some(chain) => Err(format!(
+                    "Applications are placed relative_to each other in a loop: {}",
+                    chain.join(" → ")
+                )),
+                None => Err(format!(
+                    "Application '{}' is relative_to '{}', which must come before it",
+                    app.name, relative.app
+                )),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// The apps from `app` through their relative_to anchors back to `app`,
+    /// when following the anchors leads back to it
+    fn relative_to_loop<'a>(&'a self, app: &'a Application) -> Option<Vec<&'a str>> {
+        let anchor_of = |name: &str| {
+            self.applications
+                .iter()
+                .find(|app| app.name == name)
+                .and_then(|app| app.relative_to.as_ref())
+                .map(|relative| relative.app.as_str())
+        };
+        let mut chain = vec![app.name.as_str()];
+        let mut next = anchor_of(&app.name);
+        while let Some(anchor) = next {
+            let seen = chain.contains(&anchor);
+            chain.push(anchor);
+            if anchor == app.name {
+                return Some(chain);
+            }
+            // A loop further along that `app` itself isn't part of
+            if seen {
+                return None;
+            }
+            next = anchor_of(anchor);
+        }
+        None
+    }
+
+    /// Checks the connected monitors against `expected_monitors`
+    pub fn check_monitor_count(&self, monitors: &[Monitor]) -> Result<(), String> {
+        let Some(expected) = self.expected_monitors else {
+            return Ok(());
+        };
+        if monitors.len() == expected {
+            return Ok(());
+        }
+        let detected: Vec<String> = monitors
+            .iter()
+            .map(|monitor| format!("{}: {}", monitor.index + 1, monitor.label()))
+            .collect();
+        Err(format!(
+            "The config expects {} monitors but {} are connected ({})",
+            expected,
+            monitors.len(),
+            if detected.is_empty() {
+                "none".to_string()
+            } else {
+                detected.join(", ")
+            }
+        ))
+    }
+
+    /// Checks values that parse fine but make no sense
+    pub fn validate(&self) -> Result<(), String> {
+        schedule::parse_schedule(&self.schedule)?;
+        if self.expected_monitors == Some(0) {
+            return Err("expected_monitors must be at least 1".to_string());
+        }
+        if self.total_timeout_secs == Some(0) {
+            return Err("total_timeout_secs must be at least 1".to_string());
+        }
+        self.validate_groups()?;
+        for app in &self.applications {
+            // Would otherwise quietly go on the left
+            if app.side.is_none() && !app.has_explicit_placement(self.placement_script.as_ref()) {
+                return Err(format!(
+                    "Application '{}' needs a side, or a geometry, custom_rect, split, relative_to, center_fixed or placement_script",
+                    app.name
+                ));
+            }
+            if app.executable.is_empty() && app.browser.is_none() {
+                return Err(format!(
+                    "Application '{}' needs either an executable or a browser",
+                    app.name
+                ));
+            }
+            if let Some(ref when) = app.when {
+                when.validate()
+                    .map_err(|e| format!("Invalid 'when' condition for '{}': {}", app.name, e))?;
+            }
+            if let Some(ref criteria) = app.match_criteria {
+                criteria
+                    .validate()
+                    .map_err(|e| format!("Invalid 'match' for '{}': {}", app.name, e))?;
+            }
+            if let Some(ref wait_for) = app.wait_for {
+                wait_for
+                    .validate()
+                    .map_err(|e| format!("Invalid 'wait_for' for '{}': {}", app.name, e))?;
+            }
+            if let Some(geometry) = app.geometry
+                && (geometry.width <= 0 || geometry.height <= 0)
+            {
+                return Err(format!(
+                    "Invalid geometry for '{}': width and height must be positive",
+                    app.name
+                ));
+            }
+            if let Some(ref custom_rect) = app.custom_rect {
+                if app.geometry.is_some() {
+                    return Err(format!(
+                        "Application '{}' has both geometry and custom_rect; use one of them",
+                        app.name
+                    ));
+                }
+                // Any real work area will do to catch syntax errors and unknown names
+                custom_rect
+                    .evaluate(1920, 1080)
+                    .map_err(|e| format!("{} for '{}'", e, app.name))?;
+            }
+            if let Some(split) = app.split {
+                if app.geometry.is_some() || app.custom_rect.is_some() {
+                    return Err(format!(
+                        "Application '{}' has a split and an explicit geometry or custom_rect; use one of them",
+                        app.name
+                    ));
+                }
+                if split.index >= split.count {
+                    return Err(format!(
+                        "Application '{}' has split index {} of {} slices; index counts from 0 and must be less than count",
+                        app.name, split.index, split.count
+                    ));
+                }
+            }
+            if let Some(ref relative) = app.relative_to {
+                if app.geometry.is_some() || app.custom_rect.is_some() || app.split.is_some() {
+                    return Err(format!(
+                        "Application '{}' has relative_to and a geometry, custom_rect or split; use one of them",
+                        app.name
+                    ));
+                }
+                self.validate_anchor(app, relative)?;
+            }
+            if app.center_fixed
+                && (app.geometry.is_some()
+                    || app.custom_rect.is_some()
+                    || app.split.is_some()
+                    || app.relative_to.is_some()
+                    || app.placement_script.is_some())
+            {
+                return Err(format!(
+                    "Application '{}' has center_fixed and a geometry, custom_rect, split, relative_to or placement_script; use one of them",
+                    app.name
+                ));
+            }
+            if app
+                .set_title
+                .as_ref()
+                .is_some_and(|title| title.trim().is_empty())
+            {
+                return Err(format!("Application '{}' has an empty set_title", app.name));
+            }
+            if app
+                .window_title
+                .as_ref()
+                .is_some_and(|title| title.trim().is_empty())
+            {
+                return Err(format!(
+                    "Application '{}' has an empty window_title",
+                    app.name
+                ));
+            }
+            if app
+                .placement_script
+                .as_ref()
+                .or(self.placement_script.as_ref())
+                .is_some_and(|script| script.trim().is_empty())
+            {
+                return Err(format!(
+                    "Application '{}' has an empty placement_script",
+                    app.name
+                ));
+            }
+            if let Some(min_size) = app.min_size
+                && (min_size.width <= 0 || min_size.height <= 0)
+            {
+                return Err(format!(
+                    "Application '{}' has a min_size of {}x{}; width and height must be positive",
+                    app.name, min_size.width, min_size.height
+                ));
+            }
+            if let Some(AspectRatio(ratio)) = app.aspect_ratio
+                && !(ratio.is_finite() && ratio > 0.0)
+            {
+                return Err(format!(
+                    "Application '{}' has an invalid aspect_ratio; it must be positive, e.g. \"16:9\"",
+                    app.name
+                ));
+            }
+            // A window with no share of the width would be one pixel wide
+            if !(app.split_ratio > 0.0 && app.split_ratio <= 1.0) {
+                return Err(format!(
+                    "Invalid split_ratio {} for '{}': must be in (0, 1], i.e. above 0.0 and at most 1.0 for the whole width",
+                    app.split_ratio, app.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Captures the field names a struct passes to `deserialize_struct`
+struct FieldNamesProbe<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> serde::Deserializer<'de> for FieldNamesProbe<'_> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(serde::de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(serde::de::Error::custom("only probing the field names"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// The field names of a struct deriving `Deserialize`
+fn field_names<T: for<'de> Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNamesProbe(&mut fields));
+    fields
+}
+
+/// The valid fields of the mapping stored under `parent`, the key one level up
+fn known_fields(parent: Option<&str>) -> &'static [&'static str] {
+    match parent {
+        None => field_names::<Config>(),
+        Some("applications") => field_names::<Application>(),
+        Some("browser") => field_names::<BrowserLaunch>(),
+        Some("geometry") => field_names::<Rect>(),
+        Some("custom_rect") => field_names::<CustomRect>(),
+        Some("split") => field_names::<Split>(),
+        Some("relative_to") => field_names::<RelativeTo>(),
+        Some("match" | "all" | "any") => field_names::<MatchCriteria>(),
+        Some("min_size") => field_names::<Size>(),
+        Some("enforce") => field_names::<Enforce>(),
+        Some("wait_for") => field_names::<WaitFor>(),
+        Some("when") => field_names::<Conditions>(),
+        Some("groups") => field_names::<AppGroup>(),
+        _ => &[],
+    }
+}
+
+/// Collects the map keys and sequence indices leading to `path`
+fn path_segments(path: &serde_ignored::Path, segments: &mut Vec<String>) {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => {}
+        Path::Seq { parent, index } => {
+            path_segments(parent, segments);
+            segments.push(index.to_string());
+        }
+        Path::Map { parent, key } => {
+            path_segments(parent, segments);
+            segments.push(key.clone());
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => path_segments(parent, segments),
+    }
+}
+
+/// Describes an unknown field, suggesting the closest valid one when the
+/// name looks like a typo of it
+fn unknown_field_message(path: &serde_ignored::Path) -> String {
+    let mut segments = Vec::new();
+    path_segments(path, &mut segments);
+    let Some((field, parents)) = segments.split_last() else {
+        return format!("Unknown field '{}'", path);
+    };
+    // Indices only say which application it is, not which struct, and
+    // group names which group
+    let parent = if parents.len() >= 2 && parents[parents.len() - 2] == "groups" {
+        Some(&parents[parents.len() - 2])
+    } else {
+        parents
+            .iter()
+            .rev()
+            .find(|segment| segment.parse::<usize>().is_err())
+    };
+
+    let suggestion = known_fields(parent.map(String::as_str))
+        .iter()
+        .map(|known| (strsim::levenshtein(field, known), known))
+        .min()
+        .filter(|&(distance, _)| distance <= 2.max(field.len() / 3));
+    match suggestion {
+        Some((_, known)) => format!(
+            "Unknown field '{}'; did you mean '{}'?",
+            segments.join("."),
+            known
+        ),
+        None => format!("Unknown field '{}'", segments.join(".")),
+    }
+}
+
+/// Deserializes a config, also returning a message per unknown field, since
+/// serde would otherwise silently drop misspelled ones
+fn deserialize_config(content: &str) -> Result<(Config, Vec<String>), String> {
+    let mut unknown = Vec::new();
+    let config = serde_ignored::deserialize(serde_yaml::Deserializer::from_str(content), |path| {
+        unknown.push(unknown_field_message(&path))
+    })
+    .map_err(|e| format!("Failed to parse config: {}", e))?;
+    Ok((config, unknown))
+}
+
+/// Rejects the config for its unknown fields, or only warns about them when lenient
+fn check_unknown_fields(unknown: &[String], lenient: bool) -> Result<(), String> {
+    if unknown.is_empty() {
+        Ok(())
+    } else if lenient {
+        for message in unknown {
+            warn!("{}; ignoring it", message);
+        }
+        Ok(())
+    } else {
+        Err(format!(
+            "{} (run with --lenient to ignore unknown fields)",
+            unknown.join("; ")
+        ))
+    }
 }
 
 pub fn load_default_config() -> Result<Config, String> {
     // Try to load the embedded config file first, fall back to hardcoded default
     let default_content = get_default_config_content();
-    serde_yaml::from_str(default_content).map_err(|e| format!("Failed to parse default config: {}", e))
+    let (mut config, unknown) = deserialize_config(default_content)
+        .map_err(|e| format!("Failed to parse default config: {}", e))?;
+    check_unknown_fields(&unknown, false)?;
+    config.interpolate(&env_lookup, false)?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Built-in starter layouts offered by the GUI, as (name, YAML content)
+pub const LAYOUT_TEMPLATES: &[(&str, &str)] = &[
+    ("Comms on the second monitor", COMMS_TEMPLATE),
+    ("Dev + browser 50/50", DEV_BROWSER_TEMPLATE),
+    ("Editor 70/30 with notes", FOCUS_TEMPLATE),
+    ("Four panes across two monitors", FOUR_PANES_TEMPLATE),
+];
+
+const COMMS_TEMPLATE: &str = r#"# Work on display 1, chat and mail side by side on display 2
+applications:
+  - name: "Outlook"
+    display: 2
+    side: "left"
+    executable: "olk.exe"
+  - name: "Teams"
+    display: 2
+    side: "right"
+    executable: "ms-teams.exe"
+"#;
+
+const DEV_BROWSER_TEMPLATE: &str = r#"# Editor and browser sharing one monitor
+applications:
+  - name: "Visual Studio Code"
+    display: 1
+    side: "left"
+    executable: "${LOCALAPPDATA}\\Programs\\Microsoft VS Code\\Code.exe"
+  - name: "Docs"
+    display: 1
+    side: "right"
+    browser:
+      kind: "edge"
+      url: "https://doc.rust-lang.org/std/"
+"#;
+
+const FOCUS_TEMPLATE: &str = r#"# A wide editor with a narrow notes column next to it
+applications:
+  - name: "Visual Studio Code"
+    display: 1
+    side: "left"
+    split_ratio: 0.7
+    executable: "${LOCALAPPDATA}\\Programs\\Microsoft VS Code\\Code.exe"
+  - name: "Notepad"
+    display: 1
+    side: "right"
+    split_ratio: 0.3
+    executable: "notepad.exe"
+"#;
+
+const FOUR_PANES_TEMPLATE: &str = r#"# Two apps on each of two monitors
+applications:
+  - name: "Visual Studio Code"
+    display: 1
+    side: "left"
+    executable: "${LOCALAPPDATA}\\Programs\\Microsoft VS Code\\Code.exe"
+  - name: "Windows Terminal"
+    display: 1
+    side: "right"
+    executable: "wt.exe"
+  - name: "Outlook"
+    display: 2
+    side: "left"
+    executable: "olk.exe"
+  - name: "Teams"
+    display: 2
+    side: "right"
+    executable: "ms-teams.exe"
+"#;
+
+/// Parses config text that doesn't come from a file, such as a template
+pub fn parse_config_content(content: &str) -> Result<Config, String> {
+    let (mut config, unknown) = deserialize_config(content)?;
+    check_unknown_fields(&unknown, false)?;
+    config.interpolate(&env_lookup, false)?;
+    config.validate()?;
+    Ok(config)
 }
 
 pub fn get_default_config_content() -> &'static str {
     // Try to include the real config file, fall back to hardcoded default if not available
     #[cfg(feature = "embedded_config")]
     {
-        // When embedded_config feature is enabled, try to embed the actual config.yml
-        const EMBEDDED_CONFIG: &str = include_str!("../config.yml");
+        // build.rs picks the file: $JUMPSTART_EMBED_CONFIG, or else config.yml
+        const EMBEDDED_CONFIG: &str = include!(concat!(env!("OUT_DIR"), "/embedded_config.rs"));
         EMBEDDED_CONFIG
     }
     #[cfg(not(feature = "embedded_config"))]
@@ -73,22 +1443,257 @@ applications:
     }
 }
 
-pub fn load_config(config_path: &str) -> Result<Config, String> {
-    let yaml_content = std::fs::read_to_string(config_path)
-        .map_err(|e| format!("Failed to read config file '{}': {}", config_path, e))?;
+/// A problem found while checking config text, e.g. as it is typed in the GUI editor
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub message: String,
+    /// The config still loads despite the issue, e.g. an undefined `${VAR}`
+    /// outside strict mode, which is left as written
+    pub warning: bool,
+}
+
+/// Checks config text the way `load_config` would, collecting the problems
+/// instead of stopping at the first one where possible
+pub fn check_config_content(content: &str, options: &LoadOptions) -> Vec<ConfigIssue> {
+    let (mut config, unknown) = match deserialize_config(content) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            return vec![ConfigIssue {
+                message,
+                warning: false,
+            }];
+        }
+    };
 
-    serde_yaml::from_str(&yaml_content).map_err(|e| format!("Failed to parse config: {}", e))
+    let mut issues: Vec<ConfigIssue> = unknown
+        .into_iter()
+        .map(|message| ConfigIssue {
+            message,
+            warning: options.lenient,
+        })
+        .collect();
+    // Strict interpolation reports undefined variables instead of logging them
+    if let Err(message) = config.interpolate(&env_lookup, true) {
+        issues.push(ConfigIssue {
+            message,
+            warning: !options.strict,
+        });
+    }
+    match config.validate() {
+        Ok(()) => {
+            let conflicts = config
+                .slot_conflicts()
+                .into_iter()
+                .map(|message| ConfigIssue {
+                    message,
+                    warning: true,
+                });
+            for issue in config.check_executables().into_iter().chain(conflicts) {
+                issues.push(ConfigIssue {
+                    warning: issue.warning && !options.strict,
+                    ..issue
+                });
+            }
+        }
+        Err(message) => issues.push(ConfigIssue {
+            message,
+            warning: false,
+        }),
+    }
+    issues
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tracing::warn;
+/// Extensions Windows runs when `PATHEXT` isn't set
+#[cfg_attr(not(windows), allow(dead_code))]
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
 
-    #[test]
-    fn test_side_deserialization() {
-        let yaml_left = "left";
+/// Whether `executable` is left to the shell to resolve: a URI such as
+/// `ms-teams:` or a store app ID such as `Microsoft.WindowsCalculator_8wekyb3d8bbwe!App`
+fn is_uri_or_app_id(executable: &str) -> bool {
+    if executable.contains('!') {
+        return true;
+    }
+    // A single letter before the colon is a drive, as in `C:\Tools`
+    executable.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// Whether `path` has one of the `;`-separated extensions in `pathext`, or
+/// is a shortcut, which the shell opens like a program
+#[cfg_attr(not(windows), allow(dead_code))]
+fn has_runnable_extension(path: &Path, pathext: &str) -> bool {
+    let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+        return false;
+    };
+    pathext
+        .split(';')
+        .map(|allowed| allowed.trim_start_matches('.'))
+        .chain(["lnk", "url"])
+        .any(|allowed| allowed.eq_ignore_ascii_case(ext))
+}
+
+#[cfg(windows)]
+fn is_runnable(path: &Path) -> bool {
+    let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| DEFAULT_PATHEXT.to_string());
+    has_runnable_extension(path, &pathext)
+}
+
+#[cfg(unix)]
+fn is_runnable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+}
+
+/// Where `executable` is on disk: as written when it is a path, otherwise
+/// the first match on `PATH`. Bare names that aren't on `PATH` may still be
+/// found by the shell, e.g. through App Paths, so they give `None`.
+pub fn locate_executable(executable: &str) -> Option<PathBuf> {
+    let path = Path::new(executable);
+    if path.is_absolute() || path.components().count() > 1 {
+        return Some(path.to_path_buf());
+    }
+    let dirs = std::env::var_os("PATH")?;
+    std::env::split_paths(&dirs)
+        .map(|dir| dir.join(path))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Checks that an application's `executable` can be run. Missing files and
+/// directories are errors; files that don't look runnable are warnings.
+pub fn check_executable(executable: &str) -> Option<ConfigIssue> {
+    if executable.is_empty() || is_uri_or_app_id(executable) {
+        return None;
+    }
+    let path = locate_executable(executable)?;
+    let (problem, warning) = if path.is_dir() {
+        ("is a directory", false)
+    } else if !path.exists() {
+        ("does not exist", false)
+    } else if !is_runnable(&path) {
+        let reason = if cfg!(windows) {
+            "does not have a runnable extension"
+        } else {
+            "is not executable"
+        };
+        (reason, true)
+    } else {
+        return None;
+    };
+    Some(ConfigIssue {
+        message: format!("Executable '{}' {}", path.display(), problem),
+        warning,
+    })
+}
+
+pub fn load_config(config_path: &str, options: &LoadOptions) -> Result<Config, String> {
+    let yaml_content = if remote::is_url(config_path) {
+        remote::read_remote_config(config_path)?
+    } else {
+        std::fs::read_to_string(config_path)
+            .map_err(|e| format!("Failed to read config file '{}': {}", config_path, e))?
+    };
+
+    let (mut config, unknown) = deserialize_config(&yaml_content)?;
+    check_unknown_fields(&unknown, options.lenient)?;
+    config.interpolate(&env_lookup, options.strict)?;
+    if !remote::is_url(config_path)
+        && let Some(dir) = Path::new(config_path).parent()
+    {
+        config.resolve_relative_paths(dir);
+    }
+    config.validate()?;
+    for issue in config.check_executables() {
+        if !issue.warning || options.strict {
+            return Err(issue.message);
+        }
+        warn!("{}", issue.message);
+    }
+    for conflict in config.slot_conflicts() {
+        if options.strict {
+            return Err(conflict);
+        }
+        warn!("{}", conflict);
+    }
+    Ok(config)
+}
+
+/// The config files in `dir` by profile name, which is the file name without
+/// its `.yml`, `.yaml` or `.json` extension
+pub fn list_profiles(dir: &Path) -> Result<BTreeMap<String, PathBuf>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read config directory '{}': {}", dir.display(), e))?;
+
+    let mut profiles = BTreeMap::new();
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let is_config = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ["yml", "yaml", "json"].contains(&ext.to_lowercase().as_str()));
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if !is_config || !path.is_file() {
+            continue;
+        }
+        if let Some(existing) = profiles.insert(name.to_string(), path.clone()) {
+            return Err(format!(
+                "Profile '{}' is defined by both '{}' and '{}'; rename one of them",
+                name,
+                existing.display(),
+                path.display()
+            ));
+        }
+    }
+    Ok(profiles)
+}
+
+/// The config file of `profile` in `dir`. Without a profile, the directory
+/// must hold exactly one config file.
+pub fn profile_config_path(dir: &Path, profile: Option<&str>) -> Result<PathBuf, String> {
+    let mut profiles = list_profiles(dir)?;
+    let available = profiles.keys().cloned().collect::<Vec<_>>().join(", ");
+    match profile {
+        Some(name) => profiles.remove(name).ok_or_else(|| {
+            format!(
+                "No profile '{}' in '{}'; available profiles: {}",
+                name,
+                dir.display(),
+                available
+            )
+        }),
+        None if profiles.len() == 1 => Ok(profiles.into_values().next().unwrap()),
+        None if profiles.is_empty() => Err(format!(
+            "No .yml, .yaml or .json config files in '{}'",
+            dir.display()
+        )),
+        None => Err(format!(
+            "'{}' has several profiles; choose one with --profile: {}",
+            dir.display(),
+            available
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::BrowserKind;
+    use crate::conditions::SessionKind;
+    use crate::display::{Rect, test_monitor};
+    use crate::mock::mock_monitors_with_gap;
+    use crate::readiness::OnTimeout;
+    use std::fs;
+    use tracing::warn;
+
+    #[test]
+    fn test_side_deserialization() {
+        let yaml_left = "left";
         let side_left: Side = serde_yaml::from_str(yaml_left).unwrap();
         assert!(matches!(side_left, Side::Left));
 
@@ -101,6 +1706,948 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_launch_timeout_parsing() {
+        let yaml = r#"
+applications:
+  - name: "Installer"
+    display: 1
+    side: "left"
+    executable: "setup.exe"
+    launch_timeout_ms: 1500
+  - name: "Editor"
+    display: 1
+    side: "right"
+    executable: "editor.exe"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.applications[0].launch_timeout_ms, Some(1500));
+        assert_eq!(config.applications[1].launch_timeout_ms, None);
+    }
+
+    #[test]
+    fn test_side_split_ratio() {
+        // Default even split
+        assert_eq!(Side::Left.split(1920, 0.5), (0, 960));
+        assert_eq!(Side::Right.split(1920, 0.5), (960, 960));
+
+        // 60/40 split with complementary ratios
+        assert_eq!(Side::Left.split(1920, 0.6), (0, 1152));
+        assert_eq!(Side::Right.split(1920, 0.4), (1152, 768));
+
+        // With an odd width the right side takes the rounded remainder, so
+        // complementary splits still meet exactly without a gap
+        let (_, left_width) = Side::Left.split(1921, 0.6);
+        let (right_x, right_width) = Side::Right.split(1921, 0.4);
+        assert_eq!(left_width, 1153);
+        assert_eq!(right_x, 1153);
+        assert_eq!(right_x + right_width, 1921);
+    }
+
+    #[test]
+    fn test_split_ratio_validation() {
+        let yaml = r#"
+applications:
+  - name: "Editor"
+    display: 1
+    side: "left"
+    executable: "editor.exe"
+    split_ratio: 1.5
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.contains("split_ratio"));
+        assert!(err.contains("(0, 1]"), "{}", err);
+
+        let config: Config = serde_yaml::from_str(&yaml.replace("1.5", "0.0")).unwrap();
+        assert!(config.validate().unwrap_err().contains("(0, 1]"));
+
+        let yaml = yaml.replace("1.5", "0.6");
+        let config: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.applications[0].split_ratio, 0.6);
+    }
+
+    #[test]
+    fn test_match_criteria() {
+        let yaml = r#"
+applications:
+  - name: "Gmail"
+    side: "left"
+    executable: "chrome.exe"
+    match:
+      all:
+        - title: "Gmail"
+        - class: "Chrome_WidgetWin_1"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let criteria = config.applications[0].match_criteria.as_ref().unwrap();
+        assert_eq!(criteria.to_string(), "Gmail and class Chrome_WidgetWin_1");
+
+        let yaml = yaml.replace("- class: \"Chrome_WidgetWin_1\"", "- {}");
+        let config: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .starts_with("Invalid 'match' for 'Gmail'")
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let yaml = r#"
+applications:
+  - name: "Editor"
+    side: "left"
+    executable: "editor.exe"
+    split: { count: 3, index: 1 }
+  - name: "Logs"
+    side: "left"
+    executable: "logs.exe"
+    split: { axis: "vertical", count: 3, index: 1 }
+  - name: "Notes"
+    side: "left"
+    executable: "notes.exe"
+    split: { count: 3, index: 1 }
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let split = config.applications[1].split.unwrap();
+        assert_eq!(split.axis, SplitAxis::Vertical);
+        assert_eq!(split.to_string(), "row 2 of 3");
+        // The same column is taken twice; the same-numbered row is a different slot
+        assert_eq!(
+            config.slot_conflicts(),
+            vec!["'Editor' and 'Notes' both take column 2 of 3 of display 1"]
+        );
+
+        let yaml = yaml.replace(
+            "count: 3, index: 1 }\n  - name: \"Logs\"",
+            "count: 3, index: 3 }\n  - name: \"Logs\"",
+        );
+        let config: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .contains("split index 3 of 3 slices")
+        );
+    }
+
+    #[test]
+    fn test_set_title() {
+        let yaml = r#"
+applications:
+  - name: "PowerShell"
+    side: "left"
+    executable: "pwsh.exe"
+    set_title: "Build shell"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.applications[0].set_title.as_deref(),
+            Some("Build shell")
+        );
+
+        let empty = yaml.replace("\"Build shell\"", "\" \"");
+        let config: Config = serde_yaml::from_str(&empty).unwrap();
+        assert!(config.validate().unwrap_err().contains("empty set_title"));
+    }
+
+    #[test]
+    fn test_window_title() {
+        let yaml = r#"
+applications:
+  - name: "JetBrains Rider"
+    side: "left"
+    executable: "rider64.exe"
+    window_title: "Rider"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.applications[0].window_title.as_deref(),
+            Some("Rider")
+        );
+
+        let empty = yaml.replace("\"Rider\"", "\"\"");
+        let config: Config = serde_yaml::from_str(&empty).unwrap();
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .contains("empty window_title")
+        );
+    }
+
+    #[test]
+    fn test_relative_to() {
+        let yaml = r#"
+applications:
+  - name: "Editor"
+    side: "left"
+    executable: "editor.exe"
+  - name: "Preview"
+    side: "left"
+    executable: "preview.exe"
+    relative_to: { app: "Editor", placement: "right_of" }
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.applications[1].relative_to,
+            Some(RelativeTo {
+                app: "Editor".to_string(),
+                placement: RelativePlacement::RightOf,
+            })
+        );
+        // Placed beside the editor, so not competing for the left side
+        assert!(config.slot_conflicts().is_empty());
+
+        let invalid = |yaml: &str| {
+            serde_yaml::from_str::<Config>(yaml)
+                .unwrap()
+                .validate()
+                .unwrap_err()
+        };
+        let unknown = yaml.replace("app: \"Editor\"", "app: \"Editr\"");
+        assert!(invalid(&unknown).contains("relative_to unknown application 'Editr'"));
+        let itself = yaml.replace("app: \"Editor\"", "app: \"Preview\"");
+        assert!(invalid(&itself).contains("in a loop: Preview → Preview"));
+        // The editor placed below an app that comes after it
+        let editor_below = |notes_relative_to: &str| {
+            yaml.replace(
+                "    executable: \"editor.exe\"",
+                "    executable: \"editor.exe\"\n    relative_to: { app: \"Notes\", placement: \"below\" }",
+            ) + "  - name: \"Notes\"\n    side: \"right\"\n    executable: \"notes.exe\"\n"
+                + notes_relative_to
+        };
+        assert!(invalid(&editor_below("")).contains("which must come before it"));
+        let looped = editor_below("    relative_to: { app: \"Preview\", placement: \"below\" }\n");
+        assert!(invalid(&looped).contains("in a loop: Editor → Notes → Preview → Editor"));
+        let with_split = yaml.replace(
+            "relative_to:",
+            "split: { count: 2, index: 1 }\n    relative_to:",
+        );
+        assert!(invalid(&with_split).contains("use one of them"));
+    }
+
+    #[test]
+    fn test_enabled_defaults_to_true() {
+        let yaml = r#"
+applications:
+  - name: "Editor"
+    display: 1
+    side: "left"
+    executable: "editor.exe"
+  - name: "Chat"
+    display: 1
+    side: "right"
+    executable: "chat.exe"
+    enabled: false
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.applications[0].enabled);
+        assert!(!config.applications[1].enabled);
+    }
+
+    #[test]
+    fn test_browser_app_parsing() {
+        let yaml = r#"
+applications:
+  - name: "Calendar"
+    display: 2
+    side: "right"
+    browser:
+      kind: "edge"
+      url: "https://outlook.office.com/calendar"
+      profile: "Profile 1"
+      app_mode: true
+  - name: "Nothing"
+    display: 1
+    side: "left"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let browser = config.applications[0].browser.as_ref().unwrap();
+        assert_eq!(browser.kind, BrowserKind::Edge);
+        assert_eq!(browser.profile.as_deref(), Some("Profile 1"));
+        assert!(browser.app_mode);
+        assert!(config.applications[0].executable.is_empty());
+
+        // An app with neither an executable nor a browser cannot be launched
+        assert!(config.validate().unwrap_err().contains("Nothing"));
+    }
+
+    #[test]
+    fn test_indices_by_display() {
+        let app = |display: u32, side: Side| Application {
+            display: Some(display.into()),
+            side: Some(side),
+            executable: "app.exe".to_string(),
+            ..Default::default()
+        };
+        let config = Config {
+            applications: vec![
+                app(3, Side::Right),
+                app(1, Side::Left),
+                app(3, Side::Left),
+                app(2, Side::Right),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            config.indices_by_display(3),
+            vec![
+                (1.into(), vec![1]),
+                (2.into(), vec![3]),
+                (3.into(), vec![2, 0])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_auto_distribute() {
+        let yaml = r#"
+auto_distribute: true
+applications:
+  - name: "Clock"
+    side: "left"
+    executable: "clock.exe"
+  - name: "Dashboard"
+    display: 1
+    side: "right"
+    executable: "dashboard.exe"
+  - name: "Feed"
+    side: "left"
+    executable: "feed.exe"
+  - name: "Weather"
+    side: "right"
+    executable: "weather.exe"
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let numbers = |displays: Vec<DisplaySelector>| -> Vec<String> {
+            displays.iter().map(|d| d.to_string()).collect()
+        };
+        assert_eq!(numbers(config.assign_displays(2)), ["1", "1", "2", "1"]);
+        assert_eq!(numbers(config.assign_displays(3)), ["1", "1", "2", "3"]);
+
+        // Without auto_distribute, or without monitors, omitted displays mean display 1
+        assert_eq!(numbers(config.assign_displays(0)), ["1", "1", "1", "1"]);
+        config.auto_distribute = false;
+        assert_eq!(numbers(config.assign_displays(3)), ["1", "1", "1", "1"]);
+    }
+
+    #[test]
+    fn test_application_from_executable_path() {
+        let app = Application::from_executable_path(Path::new("C:/Tools/Notepad++.EXE")).unwrap();
+        assert_eq!(app.name, "Notepad++");
+        assert_eq!(app.executable, "C:/Tools/Notepad++.EXE");
+        assert_eq!(app.display, Some(1.into()));
+        assert_eq!(app.side, Some(Side::Left));
+
+        assert!(Application::from_executable_path(Path::new("Slack.lnk")).is_ok());
+        assert!(Application::from_executable_path(Path::new("notes.txt")).is_err());
+        assert!(Application::from_executable_path(Path::new("README")).is_err());
+    }
+
+    #[test]
+    fn test_when_conditions() {
+        let yaml = r#"
+applications:
+  - name: "VPN"
+    display: 1
+    side: "left"
+    executable: "vpn.exe"
+    when:
+      hostname: "WORK-.*"
+  - name: "Chat"
+    display: 1
+    side: "right"
+    executable: "chat.exe"
+    enabled: false
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let home = MachineIdentity {
+            hostname: "home-pc".to_string(),
+            username: "me".to_string(),
+            session: SessionKind::Console,
+        };
+        let work = MachineIdentity {
+            hostname: "work-42".to_string(),
+            username: "me".to_string(),
+            session: SessionKind::Console,
+        };
+        assert!(config.applications[0].skip_reason(&work).is_none());
+        assert!(config.applications[0].skip_reason(&home).is_some());
+        assert_eq!(
+            config.applications[1].skip_reason(&work).as_deref(),
+            Some("disabled")
+        );
+    }
+
+    #[test]
+    fn test_interpolate_config() {
+        let yaml = r#"
+applications:
+  - name: "Notes (${PROFILE:-personal})"
+    display: 1
+    side: "left"
+    executable: "${HOME_DIR}\\notes.exe"
+    args: ["--vault", "${HOME_DIR}\\${MISSING}"]
+"#;
+        let lookup = |name: &str| (name == "HOME_DIR").then(|| "C:\\Users\\me".to_string());
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.interpolate(&lookup, false).unwrap();
+        let app = &config.applications[0];
+        assert_eq!(app.name, "Notes (personal)");
+        assert_eq!(app.executable, "C:\\Users\\me\\notes.exe");
+        assert_eq!(app.args[1], "C:\\Users\\me\\${MISSING}");
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let err = config.interpolate(&lookup, true).unwrap_err();
+        assert!(err.contains("MISSING"));
+    }
+
+    #[test]
+    fn test_is_uri_or_app_id() {
+        assert!(is_uri_or_app_id("ms-teams:"));
+        assert!(is_uri_or_app_id("https://calendar.example.com"));
+        assert!(is_uri_or_app_id(
+            "Microsoft.WindowsCalculator_8wekyb3d8bbwe!App"
+        ));
+        assert!(!is_uri_or_app_id("C:\\Tools\\notes.exe"));
+        assert!(!is_uri_or_app_id("notes.exe"));
+    }
+
+    #[test]
+    fn test_has_runnable_extension() {
+        let pathext = ".COM;.EXE;.BAT;.CMD;.PS1";
+        let runnable = |path: &str| has_runnable_extension(Path::new(path), pathext);
+        assert!(runnable("C:/Tools/Notes.EXE"));
+        assert!(runnable("C:/scripts/dev.ps1"));
+        assert!(runnable("C:/Users/me/Desktop/Notes.lnk"));
+        assert!(!runnable("C:/Users/me/todo.txt"));
+        assert!(!runnable("C:/Tools/notes"));
+        assert!(!has_runnable_extension(
+            Path::new("C:/scripts/dev.ps1"),
+            DEFAULT_PATHEXT
+        ));
+    }
+
+    #[test]
+    fn test_check_executable() {
+        let dir = std::env::temp_dir().join("jumpstart_check_executable_test");
+        fs::create_dir_all(&dir).unwrap();
+
+        let issue = check_executable(dir.to_str().unwrap()).unwrap();
+        assert!(issue.message.ends_with("is a directory"));
+        assert!(!issue.warning);
+
+        let missing = dir.join("missing.exe");
+        let issue = check_executable(missing.to_str().unwrap()).unwrap();
+        assert!(issue.message.ends_with("does not exist"));
+        assert!(!issue.warning);
+
+        // URIs and names the shell resolves aren't checked
+        assert_eq!(check_executable("ms-teams:"), None);
+        assert_eq!(check_executable("jumpstart-test-not-on-path.exe"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_executable_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join("jumpstart_check_permissions_test");
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("start.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+        let issue = check_executable(script.to_str().unwrap()).unwrap();
+        assert!(issue.message.ends_with("is not executable"));
+        assert!(issue.warning);
+
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(check_executable(script.to_str().unwrap()), None);
+    }
+
+    #[test]
+    fn test_check_config_content() {
+        let valid = r#"
+applications:
+  - name: "Notes"
+    side: "left"
+    executable: "notes.exe"
+"#;
+        assert!(check_config_content(valid, &LoadOptions::default()).is_empty());
+
+        let issues = check_config_content("applications:\n  - name: [", &LoadOptions::default());
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.starts_with("Failed to parse config"));
+        assert!(!issues[0].warning);
+
+        let yaml = r#"
+applications:
+  - name: "Notes"
+    side: "left"
+    executable: "${JUMPSTART_TEST_UNDEFINED}\\notes.exe"
+    split_ratio: 1.5
+"#;
+        let issues = check_config_content(yaml, &LoadOptions::default());
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].message.contains("JUMPSTART_TEST_UNDEFINED"));
+        assert!(issues[0].warning);
+        assert!(issues[1].message.contains("split_ratio"));
+        assert!(!issues[1].warning);
+
+        let issues = check_config_content(
+            yaml,
+            &LoadOptions {
+                strict: true,
+                ..Default::default()
+            },
+        );
+        assert!(!issues[0].warning);
+    }
+
+    #[test]
+    fn test_layout_templates() {
+        for (name, content) in LAYOUT_TEMPLATES {
+            let config = parse_config_content(content)
+                .unwrap_or_else(|e| panic!("Template '{}' is invalid: {}", name, e));
+            assert!(!config.applications.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_wait_for_parsing() {
+        let yaml = r#"
+applications:
+  - name: "Dashboard"
+    display: 1
+    side: "left"
+    executable: "dashboard.exe"
+    wait_for:
+      tcp: "intranet.local:8080"
+      on_timeout: "skip"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let wait_for = config.applications[0].wait_for.as_ref().unwrap();
+        assert_eq!(wait_for.tcp, "intranet.local:8080");
+        assert_eq!(wait_for.timeout_ms, 30_000);
+        assert_eq!(wait_for.on_timeout, OnTimeout::Skip);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_side_optional_with_geometry() {
+        let yaml = r#"
+applications:
+  - name: "Recorder"
+    executable: "obs64.exe"
+    geometry: { x: 100, y: 50, width: 1280, height: 720 }
+"#;
+        let config = parse_config_content(yaml).unwrap();
+        let geometry = Some(Rect::new(100, 50, 1280, 720));
+        assert_eq!(config.applications[0].geometry, geometry);
+
+        // Survives a round trip through serde
+        let saved = serde_yaml::to_string(&config).unwrap();
+        let reloaded = parse_config_content(&saved).unwrap();
+        assert_eq!(reloaded.applications[0].geometry, geometry);
+
+        // Without a geometry, the app has no place to go
+        let err =
+            parse_config_content(&yaml.replace("    geometry:", "    # geometry:")).unwrap_err();
+        assert!(err.contains("Application 'Recorder' needs a side"));
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let yaml = r#"
+auto_distribte: true
+applications:
+  - name: "Editor"
+    display: 1
+    side: "left"
+    excutable: "editor.exe"
+  - name: "Calendar"
+    side: "right"
+    browser:
+      kind: "edge"
+      url: "https://calendar.example.com"
+      profle: "Work"
+      colour: "blue"
+"#;
+        let (config, unknown) = deserialize_config(yaml).unwrap();
+        assert_eq!(
+            unknown,
+            vec![
+                "Unknown field 'auto_distribte'; did you mean 'auto_distribute'?",
+                "Unknown field 'applications.0.excutable'; did you mean 'executable'?",
+                "Unknown field 'applications.1.browser.profle'; did you mean 'profile'?",
+                "Unknown field 'applications.1.browser.colour'",
+            ]
+        );
+        assert_eq!(config.applications[0].executable, "");
+
+        let error = check_unknown_fields(&unknown, false).unwrap_err();
+        assert!(error.contains("did you mean 'executable'?"));
+        assert!(error.contains("--lenient"));
+        assert!(check_unknown_fields(&unknown, true).is_ok());
+
+        let issues = check_config_content(yaml, &LoadOptions::default());
+        assert!(issues[..4].iter().all(|issue| !issue.warning));
+        let lenient = LoadOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        let issues = check_config_content(yaml, &lenient);
+        assert!(issues[..4].iter().all(|issue| issue.warning));
+    }
+
+    #[test]
+    fn test_config_dir_profiles() {
+        let dir = std::env::temp_dir().join("jumpstart_profiles_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("work.yml"), "applications: []").unwrap();
+        fs::write(dir.join("gaming.json"), r#"{"applications": []}"#).unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let profiles = list_profiles(&dir).unwrap();
+        assert_eq!(profiles.keys().collect::<Vec<_>>(), vec!["gaming", "work"]);
+        assert_eq!(
+            profile_config_path(&dir, Some("gaming")).unwrap(),
+            dir.join("gaming.json")
+        );
+        let missing = profile_config_path(&dir, Some("home")).unwrap_err();
+        assert!(missing.contains("gaming, work"));
+        let ambiguous = profile_config_path(&dir, None).unwrap_err();
+        assert!(ambiguous.contains("--profile"));
+
+        fs::write(dir.join("work.yaml"), "applications: []").unwrap();
+        assert!(list_profiles(&dir).unwrap_err().contains("'work'"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_file_resolution() {
+        let dir = std::env::temp_dir().join("jumpstart_open_file_test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("project.sln"), "").unwrap();
+        let yaml = r#"
+applications:
+  - name: "IDE"
+    display: 1
+    side: "left"
+    executable: "ide.exe"
+    open_file: "project.${EXT:-sln}"
+  - name: "Notes"
+    display: 1
+    side: "right"
+    executable: "notes.exe"
+    open_file: "missing.md"
+"#;
+        let config_path = dir.join("config.yml");
+        fs::write(&config_path, yaml).unwrap();
+
+        let config = load_config(config_path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+        let ide = &config.applications[0];
+        assert_eq!(
+            ide.open_file.as_deref(),
+            Some(dir.join("project.sln").to_str().unwrap())
+        );
+        assert_eq!(ide.missing_open_file(), None);
+        assert!(
+            config.applications[1]
+                .missing_open_file()
+                .unwrap()
+                .ends_with("missing.md")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_display_selector() {
+        let yaml = r#"
+applications:
+  - name: "Editor"
+    display: 2
+    side: "left"
+    executable: "editor.exe"
+  - name: "Docs"
+    display: "dell"
+    side: "right"
+    executable: "docs.exe"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let displays = config.assign_displays(3);
+        assert_eq!(displays[0], 2);
+        assert_eq!(displays[1], DisplaySelector::Name("dell".to_string()));
+
+        let monitors: Vec<Monitor> = [
+            ("\\\\.\\DISPLAY1", "LG HDR 4K"),
+            ("\\\\.\\DISPLAY2", "DELL U2720Q"),
+            ("\\\\.\\DISPLAY3", ""),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, friendly_name))| Monitor {
+            friendly_name: friendly_name.to_string(),
+            ..test_monitor(index, name, Rect::new(1920 * index as i32, 0, 1920, 1080))
+        })
+        .collect();
+        let resolved = |selector: &DisplaySelector| selector.resolve(&monitors).map(|m| m.index);
+        assert_eq!(resolved(&displays[0]), Some(1));
+        assert_eq!(resolved(&displays[1]), Some(1));
+        assert_eq!(
+            resolved(&DisplaySelector::Name("\\\\.\\display3".to_string())),
+            Some(2)
+        );
+        assert_eq!(resolved(&DisplaySelector::Number(4)), None);
+        assert_eq!(resolved(&DisplaySelector::Number(0)), None);
+        assert_eq!(
+            resolved(&DisplaySelector::Name("Samsung".to_string())),
+            None
+        );
+        assert_eq!(
+            resolved(&DisplaySelector::Name("Primary".to_string())),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_aspect_ratio() {
+        let ratio: AspectRatio = serde_yaml::from_str("\"16:9\"").unwrap();
+        assert_eq!(ratio, AspectRatio(16.0 / 9.0));
+        let ratio: AspectRatio = serde_yaml::from_str("1.5").unwrap();
+        assert_eq!(ratio, AspectRatio(1.5));
+        assert!(serde_yaml::from_str::<AspectRatio>("\"wide\"").is_err());
+
+        let yaml = r#"
+applications:
+  - name: "Player"
+    side: "right"
+    executable: "player.exe"
+    aspect_ratio: "16:0"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().unwrap_err().contains("aspect_ratio"));
+    }
+
+    #[test]
+    fn test_custom_rect() {
+        let rect = |x: &str, y: &str, w: &str, h: &str| CustomRect {
+            x: x.to_string(),
+            y: y.to_string(),
+            w: w.to_string(),
+            h: h.to_string(),
+        };
+
+        // The right 70% of the work area, minus a 10 px gap on each side
+        let right = rect("W*0.3 + 10", "10", "W*0.7 - 20", "H - 20");
+        assert_eq!(
+            right.evaluate(2560, 1400).unwrap(),
+            Rect::new(778, 10, 1772, 1380)
+        );
+
+        assert!(rect("0", "0", "W/0", "H").evaluate(1920, 1080).is_err());
+        assert!(rect("0", "0", "1/0", "H").evaluate(1920, 1080).is_err());
+        assert!(rect("0", "0", "W*", "H").evaluate(1920, 1080).is_err());
+        assert!(rect("0", "0", "X", "H").evaluate(1920, 1080).is_err());
+        assert!(rect("0", "0", "W - W", "H").evaluate(1920, 1080).is_err());
+
+        let yaml = r#"
+applications:
+  - name: "Editor"
+    side: "left"
+    executable: "editor.exe"
+    custom_rect: { x: "0", y: "0", w: "W * 0.7", h: "H / " }
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().unwrap_err().contains("'Editor'"));
+    }
+
+    #[test]
+    fn test_display_fallback() {
+        let yaml = r#"
+applications:
+  - name: "Dashboard"
+    display: [3, 2, "primary"]
+    side: "left"
+    executable: "dashboard.exe"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let display = config.applications[0].display.clone().unwrap();
+        assert_eq!(display.to_string(), "[3, 2, 'primary']");
+        assert_eq!(display.short_label(), "D3 / D2 / primary");
+
+        let monitors: Vec<Monitor> = (0..3)
+            .map(|index| {
+                let bounds = Rect::new(1920 * index as i32, 0, 1920, 1080);
+                test_monitor(index, &format!("Monitor{}", index + 1), bounds)
+            })
+            .collect();
+        let resolved = |count: usize| display.resolve(&monitors[..count]).map(|m| m.index);
+        assert_eq!(resolved(3), Some(2));
+        assert_eq!(resolved(2), Some(1));
+        assert_eq!(resolved(1), Some(0));
+        assert_eq!(resolved(0), None);
+
+        // The primary monitor need not be display 1
+        let monitors = mock_monitors_with_gap();
+        assert_eq!(display.resolve(&monitors).unwrap().name, "External");
+        assert_eq!(display.resolve(&monitors[..1]), None);
+    }
+
+    #[test]
+    fn test_groups() {
+        let yaml = r#"
+applications:
+  - name: "Database"
+    side: "left"
+    executable: "db.exe"
+  - name: "Backend"
+    side: "right"
+    executable: "backend.exe"
+  - name: "Chat"
+    side: "left"
+    display: 2
+    executable: "chat.exe"
+groups:
+  dev:
+    apps: ["Database", "Backend"]
+  social:
+    apps: ["Chat"]
+    on_failure: continue
+"#;
+        let config = parse_config_content(yaml).unwrap();
+        let (name, group) = config.group_of("Backend").unwrap();
+        assert_eq!(name, "dev");
+        assert_eq!(group.on_failure, GroupFailurePolicy::AbortGroup);
+        assert_eq!(
+            config.group_of("Chat").unwrap().1.on_failure,
+            GroupFailurePolicy::Continue
+        );
+
+        let error = parse_config_content(&yaml.replace("\"Chat\"]", "\"Chatt\"]")).unwrap_err();
+        assert_eq!(error, "Group 'social' lists unknown application 'Chatt'");
+        let error = parse_config_content(&yaml.replace("[\"Chat\"]", "[\"Backend\"]")).unwrap_err();
+        assert_eq!(
+            error,
+            "Application 'Backend' is in both group 'dev' and group 'social'"
+        );
+
+        let (_, unknown) = deserialize_config(&yaml.replace("on_failure", "on_falure")).unwrap();
+        assert_eq!(
+            unknown,
+            vec!["Unknown field 'groups.social.on_falure'; did you mean 'on_failure'?"]
+        );
+    }
+
+    #[test]
+    fn test_check_monitor_count() {
+        let mut config = Config::default();
+        let monitors = mock_monitors_with_gap();
+        assert!(config.check_monitor_count(&monitors).is_ok());
+
+        config.expected_monitors = Some(2);
+        assert!(config.check_monitor_count(&monitors).is_ok());
+
+        config.expected_monitors = Some(3);
+        assert_eq!(
+            config.check_monitor_count(&monitors).unwrap_err(),
+            "The config expects 3 monitors but 2 are connected (1: Laptop, 2: External)"
+        );
+        assert!(
+            config
+                .check_monitor_count(&[])
+                .unwrap_err()
+                .ends_with("but 0 are connected (none)")
+        );
+
+        config.expected_monitors = Some(0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_slot_conflicts() {
+        let yaml = r#"
+applications:
+  - name: "Editor"
+    display: 1
+    side: "left"
+    executable: "editor.exe"
+  - name: "Terminal"
+    display: 1
+    side: "left"
+    executable: "terminal.exe"
+  - name: "Browser"
+    display: 1
+    side: "right"
+    executable: "browser.exe"
+  - name: "Notes"
+    display: 1
+    side: "left"
+    executable: "notes.exe"
+    enabled: false
+  - name: "Clock"
+    display: 1
+    side: "left"
+    executable: "clock.exe"
+    custom_rect: { x: "0", y: "0", w: "200", h: "100" }
+  - name: "Chat"
+    display: 1
+    side: "right"
+    executable: "chat.exe"
+    placement_script: "place.ps1"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.slot_conflicts(),
+            vec!["'Editor' and 'Terminal' both take the left side of display 1"]
+        );
+
+        // Apps the config-wide script places don't take a side either
+        let scripted: Config = serde_yaml::from_str(
+            r#"
+placement_script: "place.ps1"
+applications:
+  - name: "Editor"
+    display: 1
+    executable: "editor.exe"
+  - name: "Terminal"
+    display: 1
+    executable: "terminal.exe"
+"#,
+        )
+        .unwrap();
+        assert!(scripted.slot_conflicts().is_empty());
+        assert!(scripted.validate().is_ok());
+
+        let conflict = |options: &LoadOptions| {
+            check_config_content(yaml, options)
+                .into_iter()
+                .find(|issue| issue.message.contains("both take"))
+                .unwrap()
+        };
+        assert!(conflict(&LoadOptions::default()).warning);
+        assert!(
+            !conflict(&LoadOptions {
+                strict: true,
+                ..Default::default()
+            })
+            .warning
+        );
+    }
+
     #[test]
     fn test_config_loading() {
         // Create a temporary config file for testing
@@ -125,11 +2672,11 @@ applications:
         // Rename our test file to config.yml
         fs::rename(temp_file_path, "config.yml").unwrap();
 
-        let config = load_config("config.yml").unwrap();
+        let config = load_config("config.yml", &LoadOptions::default()).unwrap();
         assert_eq!(config.applications.len(), 1);
         assert_eq!(config.applications[0].name, "Test App");
-        assert_eq!(config.applications[0].display, 1);
-        assert!(matches!(config.applications[0].side, Side::Left));
+        assert_eq!(config.applications[0].display, Some(1.into()));
+        assert!(matches!(config.applications[0].side, Some(Side::Left)));
         assert_eq!(config.applications[0].executable, "test.exe");
 
         // Clean up
@@ -152,7 +2699,7 @@ applications:
                 .expect("Failed to backup original config file");
         }
 
-        let result = load_config("config.yml");
+        let result = load_config("config.yml", &LoadOptions::default());
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Failed to read config file"));
 
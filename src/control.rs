@@ -0,0 +1,437 @@
+use crate::config::{self, LoadOptions};
+use crate::schedule;
+use crate::watch::{self, WatchedLayout};
+use std::io::{self, BufRead, BufReader, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// A command sent to a running `--watch` over its control endpoint, one per line
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlCommand {
+    /// Launch and position the watched config again
+    Reapply,
+    /// Switch to another profile in the config's directory
+    Launch(String),
+    /// Report which windows are being watched
+    Status,
+}
+
+impl ControlCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (name, argument) = match line.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, Some(argument.trim())),
+            None => (line, None),
+        };
+        match (name, argument) {
+            ("reapply", None) => Ok(ControlCommand::Reapply),
+            ("status", None) => Ok(ControlCommand::Status),
+            ("launch", Some(profile)) => Ok(ControlCommand::Launch(profile.to_string())),
+            ("launch", None) => Err("Usage: launch <profile>".to_string()),
+            ("reapply" | "status", Some(_)) => Err(format!("'{}' takes no arguments", name)),
+            _ => Err(format!(
+                "Unknown command '{}'; expected reapply, launch <profile> or status",
+                line
+            )),
+        }
+    }
+}
+
+/// A command received from a client, with where to send its reply
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    pub reply: Sender<String>,
+}
+
+/// Runs a command against the watched layout, where `apply` launches and
+/// positions a config and returns the layout to watch. The reply is one
+/// line starting with `ok` or `error`.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn execute(
+    command: &ControlCommand,
+    layout: &mut WatchedLayout,
+    mut apply: impl FnMut(&str) -> Result<WatchedLayout, String>,
+) -> String {
+    let result = match command {
+        ControlCommand::Status => {
+            let apps: Vec<&str> = layout.windows.iter().map(|w| w.app.as_str()).collect();
+            let mut status = format!(
+                "watching {} windows of '{}'",
+                apps.len(),
+                layout.config_path
+            );
+            if !apps.is_empty() {
+                status.push_str(&format!(": {}", apps.join(", ")));
+            }
+            Ok(status)
+        }
+        ControlCommand::Reapply => apply(&layout.config_path).map(|next| {
+            *layout = next;
+            format!(
+                "reapplied '{}'; watching {} windows",
+                layout.config_path,
+                layout.windows.len()
+            )
+        }),
+        ControlCommand::Launch(profile) => schedule::profile_dir(&layout.config_path)
+            .and_then(|dir| config::profile_config_path(&dir, Some(profile)))
+            .and_then(|path| {
+                *layout = apply(&path.to_string_lossy())?;
+                Ok(format!(
+                    "launched profile '{}'; watching {} windows",
+                    profile,
+                    layout.windows.len()
+                ))
+            }),
+    };
+    match result {
+        Ok(message) => format!("ok {}", message),
+        Err(e) => format!("error {}", e),
+    }
+}
+
+/// The per-user control endpoint: a named pipe on Windows, a Unix socket
+/// in the runtime directory elsewhere
+pub fn default_endpoint() -> String {
+    let user = std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "user".to_string());
+    if cfg!(windows) {
+        format!(r"\\.\pipe\jumpstart-{}", user)
+    } else {
+        let dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir);
+        dir.join(format!("jumpstart-{}.sock", user))
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Answers each command read from a client until it disconnects
+fn serve_lines(
+    reader: impl BufRead,
+    mut writer: impl Write,
+    requests: &Sender<ControlRequest>,
+) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match ControlCommand::parse(&line) {
+            Ok(command) => {
+                let (reply, response) = mpsc::channel();
+                if requests.send(ControlRequest { command, reply }).is_err() {
+                    return Ok(());
+                }
+                response
+                    .recv()
+                    .unwrap_or_else(|_| "error jumpstart stopped watching".to_string())
+            }
+            Err(e) => format!("error {}", e),
+        };
+        writeln!(writer, "{}", reply)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Listens for commands on a background thread while `--watch` runs. Only
+/// the current user can connect: the socket is private to them, and the
+/// pipe rejects remote clients and only lets other users read.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct ControlServer {
+    requests: Receiver<ControlRequest>,
+    load_options: LoadOptions,
+}
+
+impl ControlServer {
+    pub fn start(endpoint: &str, load_options: &LoadOptions) -> Result<Self, String> {
+        let (sender, requests) = mpsc::channel();
+        listen(endpoint, sender)?;
+        info!("Listening for control commands on '{}'", endpoint);
+        Ok(ControlServer {
+            requests,
+            load_options: *load_options,
+        })
+    }
+
+    /// Waits up to `timeout` for a command
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn next_request(&self, timeout: Duration) -> Option<ControlRequest> {
+        match self.requests.recv_timeout(timeout) {
+            Ok(request) => Some(request),
+            Err(RecvTimeoutError::Timeout) => None,
+            // The listener failed; keep watching without it
+            Err(RecvTimeoutError::Disconnected) => {
+                thread::sleep(timeout);
+                None
+            }
+        }
+    }
+
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn respond(&self, request: ControlRequest, layout: &mut WatchedLayout) {
+        info!("Control command: {:?}", request.command);
+        let reply = execute(&request.command, layout, |config_path| {
+            watch::apply_layout(config_path, &self.load_options)
+        });
+        let _ = request.reply.send(reply);
+    }
+}
+
+#[cfg(unix)]
+fn listen(endpoint: &str, requests: Sender<ControlRequest>) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    // A socket file left behind by a process that has exited is replaced
+    if UnixStream::connect(endpoint).is_ok() {
+        return Err(format!(
+            "Another jumpstart is already listening on '{}'",
+            endpoint
+        ));
+    }
+    let _ = std::fs::remove_file(endpoint);
+    let listener = UnixListener::bind(endpoint)
+        .map_err(|e| format!("Failed to listen on '{}': {}", endpoint, e))?;
+    std::fs::set_permissions(endpoint, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict '{}': {}", endpoint, e))?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let result =
+                stream.and_then(|stream| serve_lines(BufReader::new(&stream), &stream, &requests));
+            if let Err(e) = result {
+                warn!("Control connection failed: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(windows)]
+fn create_pipe(name: &str, first: bool) -> Result<std::fs::File, String> {
+    use std::os::windows::io::{FromRawHandle, RawHandle};
+    use std::ptr::null_mut;
+    use widestring::U16CString;
+    use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+    use winapi::um::namedpipeapi::CreateNamedPipeW;
+    use winapi::um::winbase::{
+        FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX, PIPE_READMODE_BYTE,
+        PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    let wide_name = U16CString::from_str(name).map_err(|e| e.to_string())?;
+    let mut open_mode = PIPE_ACCESS_DUPLEX;
+    // Fails when another jumpstart already owns the pipe
+    if first {
+        open_mode |= FILE_FLAG_FIRST_PIPE_INSTANCE;
+    }
+    let handle = unsafe {
+        CreateNamedPipeW(
+            wide_name.as_ptr(),
+            open_mode,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_UNLIMITED_INSTANCES,
+            4096,
+            4096,
+            0,
+            null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(format!(
+            "Failed to create control pipe '{}': {}",
+            name,
+            io::Error::last_os_error()
+        ));
+    }
+    Ok(unsafe { std::fs::File::from_raw_handle(handle as RawHandle) })
+}
+
+#[cfg(windows)]
+fn listen(endpoint: &str, requests: Sender<ControlRequest>) -> Result<(), String> {
+    use std::os::windows::io::AsRawHandle;
+    use std::ptr::null_mut;
+    use winapi::shared::winerror::ERROR_PIPE_CONNECTED;
+    use winapi::um::namedpipeapi::ConnectNamedPipe;
+
+    let name = endpoint.to_string();
+    let mut pipe = Some(create_pipe(&name, true)?);
+    thread::spawn(move || {
+        loop {
+            // Each client gets its own instance of the pipe
+            let instance = match pipe.take().map_or_else(|| create_pipe(&name, false), Ok) {
+                Ok(instance) => instance,
+                Err(e) => {
+                    warn!("{}", e);
+                    return;
+                }
+            };
+            let connected = unsafe { ConnectNamedPipe(instance.as_raw_handle() as _, null_mut()) }
+                != 0
+                || io::Error::last_os_error().raw_os_error() == Some(ERROR_PIPE_CONNECTED as i32);
+            if connected
+                && let Err(e) = serve_lines(BufReader::new(&instance), &instance, &requests)
+            {
+                warn!("Control connection failed: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Sends one command to the jumpstart watching on `endpoint` and returns
+/// its reply
+pub fn send(endpoint: &str, command: &str) -> Result<String, String> {
+    ControlCommand::parse(command)?;
+    let not_listening =
+        |e: io::Error| format!("No jumpstart is listening on '{}': {}", endpoint, e);
+    #[cfg(unix)]
+    let stream = std::os::unix::net::UnixStream::connect(endpoint).map_err(not_listening)?;
+    #[cfg(windows)]
+    let stream = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(endpoint)
+        .map_err(not_listening)?;
+
+    let mut writer = &stream;
+    writeln!(writer, "{}", command.trim()).map_err(|e| e.to_string())?;
+    let mut reply = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut reply)
+        .map_err(|e| e.to_string())?;
+    Ok(reply.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::Rect;
+    use crate::matching::MatchCriteria;
+    use crate::watch::TrackedWindow;
+    use std::fs;
+
+    fn tracked(app: &str) -> TrackedWindow {
+        TrackedWindow {
+            app: app.to_string(),
+            hwnd: 1,
+            search: MatchCriteria::title(&app.to_lowercase()),
+            rect: Rect::new(0, 0, 960, 1040),
+        }
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            ControlCommand::parse("reapply"),
+            Ok(ControlCommand::Reapply)
+        );
+        assert_eq!(
+            ControlCommand::parse(" status \r"),
+            Ok(ControlCommand::Status)
+        );
+        assert_eq!(
+            ControlCommand::parse("launch  evening"),
+            Ok(ControlCommand::Launch("evening".to_string()))
+        );
+        assert!(ControlCommand::parse("launch").is_err());
+        assert!(ControlCommand::parse("status now").is_err());
+        assert!(
+            ControlCommand::parse("quit")
+                .unwrap_err()
+                .contains("Unknown command 'quit'")
+        );
+    }
+
+    #[test]
+    fn test_execute() {
+        let dir = std::env::temp_dir().join("jumpstart_control_test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day.yml"), "applications: []\n").unwrap();
+        fs::write(dir.join("evening.yml"), "applications: []\n").unwrap();
+        let day = dir.join("day.yml").to_string_lossy().into_owned();
+
+        let mut layout = WatchedLayout {
+            config_path: day.clone(),
+            windows: vec![tracked("Teams")],
+            ..Default::default()
+        };
+        let mut applied = Vec::new();
+        let mut run = |command: ControlCommand, layout: &mut WatchedLayout| {
+            execute(&command, layout, |path| {
+                applied.push(path.to_string());
+                Ok(WatchedLayout {
+                    config_path: path.to_string(),
+                    windows: vec![tracked("Teams"), tracked("Slack")],
+                    ..Default::default()
+                })
+            })
+        };
+
+        assert_eq!(
+            run(ControlCommand::Status, &mut layout),
+            format!("ok watching 1 windows of '{}': Teams", day)
+        );
+        assert_eq!(
+            run(ControlCommand::Reapply, &mut layout),
+            format!("ok reapplied '{}'; watching 2 windows", day)
+        );
+        assert_eq!(
+            run(ControlCommand::Launch("evening".to_string()), &mut layout),
+            "ok launched profile 'evening'; watching 2 windows"
+        );
+        assert!(layout.config_path.ends_with("evening.yml"));
+        assert!(
+            run(ControlCommand::Launch("night".to_string()), &mut layout)
+                .starts_with("error No profile 'night'")
+        );
+        assert_eq!(applied.len(), 2);
+
+        let failed = execute(&ControlCommand::Reapply, &mut layout, |_| {
+            Err("Config is invalid".to_string())
+        });
+        assert_eq!(failed, "error Config is invalid");
+        // A failed reapply keeps watching the previous windows
+        assert_eq!(layout.windows.len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_control_socket() {
+        let endpoint = std::env::temp_dir()
+            .join("jumpstart_control_test.sock")
+            .to_string_lossy()
+            .into_owned();
+        let server = ControlServer::start(&endpoint, &LoadOptions::default()).unwrap();
+        assert!(ControlServer::start(&endpoint, &LoadOptions::default()).is_err());
+
+        let client = {
+            let endpoint = endpoint.clone();
+            thread::spawn(move || send(&endpoint, "status"))
+        };
+        let mut layout = WatchedLayout {
+            config_path: "config.yml".to_string(),
+            windows: vec![tracked("Teams")],
+            ..Default::default()
+        };
+        let request = server.next_request(Duration::from_secs(5)).unwrap();
+        server.respond(request, &mut layout);
+        assert_eq!(
+            client.join().unwrap().unwrap(),
+            "ok watching 1 windows of 'config.yml': Teams"
+        );
+
+        // Invalid commands are rejected before connecting
+        assert!(send(&endpoint, "restart").is_err());
+        fs::remove_file(&endpoint).unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+use crate::display::{Monitor, Rect, ShowState};
+use crate::identity::WindowIdentity;
+use crate::matching::{FoundWindow, IgnoreList, MatchCriteria, WindowCandidate};
+use crate::prefetch::Prefetch;
+use crate::undo::UndoSnapshot;
+use std::time::Duration;
+
+#[cfg(windows)]
+use crate::monitor::{get_monitors, monitor_of_window};
+#[cfg(windows)]
+use crate::window;
+#[cfg(windows)]
+use winapi::shared::windef::HWND;
+
+/// The monitors and windows a launch run finds and moves. Windows are
+/// referred to by their raw handle, as in `FoundWindow`.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub trait Desktop {
+    /// The connected monitors in display-number order
+    fn monitors(&self) -> Vec<Monitor>;
+
+    /// Lists the titled top-level windows, including cloaked ones when asked
+    fn enumerate_windows(&self, include_cloaked: bool) -> Vec<WindowCandidate>;
+
+    /// Enumerates windows on a background thread every `interval`, or `None`
+    /// when windows can only be enumerated on the calling thread
+    fn prefetch_windows(
+        &self,
+        interval: Duration,
+        include_cloaked: bool,
+    ) -> Option<Prefetch<Vec<WindowCandidate>>>;
+
+    /// The window of an enumeration that matches `search` and whose title
+    /// contains `ready_title` when given, preferring the one most like the
+    /// `remembered` window
+    fn find_in_windows(
+        &self,
+        windows: &[WindowCandidate],
+        search: &MatchCriteria,
+        ready_title: Option<&str>,
+        ignore: &IgnoreList,
+        remembered: Option<&WindowIdentity>,
+    ) -> Option<FoundWindow>;
+
+    /// The largest window of an enumeration that matches `search`
+    fn find_largest_in_windows(
+        &self,
+        windows: &[WindowCandidate],
+        search: &MatchCriteria,
+        ignore: &IgnoreList,
+    ) -> Option<FoundWindow>;
+
+    /// A window of `process_id` or of a process it started
+    fn find_by_process_tree(
+        &self,
+        process_id: u32,
+        windows: &[WindowCandidate],
+        ignore: &IgnoreList,
+    ) -> Option<FoundWindow>;
+
+    fn window_rect(&self, hwnd: usize) -> Option<Rect>;
+
+    /// Size the window has when it is neither minimized nor maximized
+    fn normal_size(&self, hwnd: usize) -> Option<(i32, i32)>;
+
+    fn monitor_of_window<'a>(&self, hwnd: usize, monitors: &'a [Monitor]) -> Option<&'a Monitor>;
+
+    /// Whether activating the window would switch to another virtual desktop
+    fn is_on_other_desktop(&self, hwnd: usize) -> bool;
+
+    fn set_title(&self, hwnd: usize, title: &str) -> Result<(), String>;
+
+    fn restore_without_activating(&self, hwnd: usize);
+
+    fn position_window(&self, hwnd: usize, rect: Rect, activate: bool) -> Result<(), String>;
+
+    /// Moves the window to `rect` in `steps` moves spread over `total_ms`
+    fn position_window_smooth(
+        &self,
+        hwnd: usize,
+        rect: Rect,
+        steps: u32,
+        total_ms: u64,
+        activate: bool,
+    ) -> Result<(), String>;
+
+    /// Moves the window so its visible frame fills `rect`, returning where
+    /// the window itself went
+    fn align_visible_frame(&self, hwnd: usize, rect: Rect) -> Result<Rect, String>;
+
+    fn show_state(&self, hwnd: usize) -> ShowState;
+
+    fn set_show_state(&self, hwnd: usize, state: ShowState);
+
+    /// Stacks the windows top to bottom in the given order
+    fn set_zorder(&self, windows_in_order: &[usize]) -> Result<(), String>;
+
+    fn foreground_window(&self) -> Option<usize>;
+
+    fn set_foreground_window(&self, hwnd: usize) -> bool;
+
+    /// What the window can be recognized by in a later run
+    fn window_identity(&self, hwnd: usize, title: &str) -> WindowIdentity;
+
+    /// Moves the snapshot's windows back, returning how many were restored
+    fn restore(&self, snapshot: &UndoSnapshot) -> usize;
+}
+
+/// The real desktop, through the Win32 API
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsDesktop;
+
+#[cfg(windows)]
+impl Desktop for WindowsDesktop {
+    fn monitors(&self) -> Vec<Monitor> {
+        get_monitors()
+    }
+
+    fn enumerate_windows(&self, include_cloaked: bool) -> Vec<WindowCandidate> {
+        window::enumerate_windows_with(include_cloaked)
+    }
+
+    fn prefetch_windows(
+        &self,
+        interval: Duration,
+        include_cloaked: bool,
+    ) -> Option<Prefetch<Vec<WindowCandidate>>> {
+        Some(Prefetch::spawn(interval, move || {
+            window::enumerate_windows_with(include_cloaked)
+        }))
+    }
+
+    fn find_in_windows(
+        &self,
+        windows: &[WindowCandidate],
+        search: &MatchCriteria,
+        ready_title: Option<&str>,
+        ignore: &IgnoreList,
+        remembered: Option<&WindowIdentity>,
+    ) -> Option<FoundWindow> {
+        window::find_in_windows(windows, search, ready_title, ignore, remembered)
+    }
+
+    fn find_largest_in_windows(
+        &self,
+        windows: &[WindowCandidate],
+        search: &MatchCriteria,
+        ignore: &IgnoreList,
+    ) -> Option<FoundWindow> {
+        window::find_largest_in_windows(windows, search, ignore)
+    }
+
+    fn find_by_process_tree(
+        &self,
+        process_id: u32,
+        windows: &[WindowCandidate],
+        ignore: &IgnoreList,
+    ) -> Option<FoundWindow> {
+        window::find_window_by_process_tree(process_id, windows, ignore)
+    }
+
+    fn window_rect(&self, hwnd: usize) -> Option<Rect> {
+        window::get_window_rect(hwnd as HWND)
+    }
+
+    fn normal_size(&self, hwnd: usize) -> Option<(i32, i32)> {
+        window::normal_size(hwnd as HWND)
+    }
+
+    fn monitor_of_window<'a>(&self, hwnd: usize, monitors: &'a [Monitor]) -> Option<&'a Monitor> {
+        monitor_of_window(hwnd as HWND, monitors)
+    }
+
+    fn is_on_other_desktop(&self, hwnd: usize) -> bool {
+        crate::virtual_desktop::is_on_other_desktop(hwnd as HWND)
+    }
+
+    fn set_title(&self, hwnd: usize, title: &str) -> Result<(), String> {
+        window::set_window_title(hwnd as HWND, title)
+    }
+
+    fn restore_without_activating(&self, hwnd: usize) {
+        window::restore_without_activating(hwnd as HWND)
+    }
+
+    fn position_window(&self, hwnd: usize, rect: Rect, activate: bool) -> Result<(), String> {
+        window::position_window(
+            hwnd as HWND,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            activate,
+        )
+    }
+
+    fn position_window_smooth(
+        &self,
+        hwnd: usize,
+        rect: Rect,
+        steps: u32,
+        total_ms: u64,
+        activate: bool,
+    ) -> Result<(), String> {
+        window::position_window_smooth(
+            hwnd as HWND,
+            (rect.x, rect.y, rect.width, rect.height),
+            steps,
+            total_ms,
+            activate,
+        )
+    }
+
+    fn align_visible_frame(&self, hwnd: usize, rect: Rect) -> Result<Rect, String> {
+        window::align_visible_frame(hwnd as HWND, rect)
+    }
+
+    fn show_state(&self, hwnd: usize) -> ShowState {
+        window::show_state(hwnd as HWND)
+    }
+
+    fn set_show_state(&self, hwnd: usize, state: ShowState) {
+        window::set_show_state(hwnd as HWND, state)
+    }
+
+    fn set_zorder(&self, windows_in_order: &[usize]) -> Result<(), String> {
+        let windows: Vec<HWND> = windows_in_order.iter().map(|&hwnd| hwnd as HWND).collect();
+        window::set_window_zorder(&windows)
+    }
+
+    fn foreground_window(&self) -> Option<usize> {
+        window::foreground_window().map(|hwnd| hwnd as usize)
+    }
+
+    fn set_foreground_window(&self, hwnd: usize) -> bool {
+        window::set_foreground_window(hwnd as HWND)
+    }
+
+    fn window_identity(&self, hwnd: usize, title: &str) -> WindowIdentity {
+        window::window_identity(hwnd as HWND, title)
+    }
+
+    fn restore(&self, snapshot: &UndoSnapshot) -> usize {
+        crate::undo::restore(snapshot)
+    }
+}
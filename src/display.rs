@@ -0,0 +1,1159 @@
+use crate::config::{
+    Application, Config, DisplaySelector, GeometryOrigin, RelativePlacement, RelativeTo, Side,
+    Split, SplitAxis,
+};
+use serde::{Deserialize, Serialize};
+
+/// A rectangle in virtual-desktop pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    /// Whether the point lies inside; right and bottom edges are exclusive
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    /// Whether the two rectangles share any pixels
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+
+    /// A `width` by `height` rectangle centered on this one. One that doesn't
+    /// fit starts at this one's top-left corner, keeping its title bar in view.
+    pub fn center_in(&self, width: i32, height: i32) -> Rect {
+        Rect::new(
+            self.x + ((self.width - width) / 2).max(0),
+            self.y + ((self.height - height) / 2).max(0),
+            width,
+            height,
+        )
+    }
+
+    /// The rectangle a window must be given for its visible `frame` to fill
+    /// this one, measured while the window was at `window` with invisible
+    /// resize borders around the frame
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn outset_by_borders(&self, window: Rect, frame: Rect) -> Rect {
+        let left = (frame.x - window.x).max(0);
+        let top = (frame.y - window.y).max(0);
+        let right = (window.right() - frame.right()).max(0);
+        let bottom = (window.bottom() - frame.bottom()).max(0);
+        Rect::new(
+            self.x - left,
+            self.y - top,
+            self.width + left + right,
+            self.height + top + bottom,
+        )
+    }
+}
+
+/// Whether a window is shown at its own size, maximized or minimized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum ShowState {
+    #[default]
+    Normal,
+    Maximized,
+    Minimized,
+}
+
+/// A connected monitor, independent of the platform API that enumerated it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    /// Position in enumeration order; display number `n` is index `n - 1`
+    pub index: usize,
+    /// Platform device name, e.g. `\\.\DISPLAY1`
+    pub name: String,
+    /// Monitor model name, e.g. "DELL U2720Q"; empty if unavailable
+    pub friendly_name: String,
+    pub bounds: Rect,
+    /// Bounds minus the taskbar and docked toolbars
+    pub work_area: Rect,
+    pub primary: bool,
+    /// Scale factor relative to 96 DPI
+    pub scale: f32,
+    /// Platform handle, used to match windows to monitors
+    pub handle: usize,
+}
+
+impl Monitor {
+    /// Human-readable monitor name, e.g. "DELL U2720Q (\\.\DISPLAY2)"
+    pub fn label(&self) -> String {
+        if self.friendly_name.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.friendly_name, self.name)
+        }
+    }
+}
+
+/// Free space kept around windows placed by `side`, in pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Gaps {
+    /// Between the left and right windows
+    pub inner: u32,
+    /// Between the windows and the work area edges
+    pub outer: u32,
+}
+
+impl Gaps {
+    /// These gaps, or the app's own `gap` between it and everything around it
+    pub fn for_app(self, app: &Application) -> Gaps {
+        app.gap.map_or(self, |gap| Gaps {
+            inner: gap,
+            outer: gap,
+        })
+    }
+}
+
+impl From<&Config> for Gaps {
+    fn from(config: &Config) -> Self {
+        Gaps {
+            inner: config.gap,
+            outer: config.outer_gap,
+        }
+    }
+}
+
+/// Where a window on `side` of the monitor goes, taking `split_ratio` of
+/// the work area's width and its full height, less `gaps`. The left window
+/// gives up `inner / 2` pixels and the right one the rest, so odd gaps
+/// still leave exactly `inner` pixels between them. Even halves are the
+/// two slices of a horizontal `split`.
+pub fn calculate_window_position(
+    monitor: &Monitor,
+    side: &Side,
+    split_ratio: f32,
+    gaps: Gaps,
+) -> Rect {
+    if split_ratio == 0.5 {
+        let split = Split {
+            axis: SplitAxis::Horizontal,
+            count: 2,
+            index: match side {
+                Side::Left => 0,
+                Side::Right => 1,
+            },
+        };
+        return calculate_slice_position(monitor, &split, gaps);
+    }
+    let inner = gaps.inner as i32;
+    let area = inset_work_area(monitor, gaps);
+    let (offset, width) = side.split(area.width, split_ratio);
+    let (offset, width) = match side {
+        Side::Left => (offset, width - inner / 2),
+        Side::Right => (offset + (inner - inner / 2), width - (inner - inner / 2)),
+    };
+    Rect::new(area.x + offset, area.y, width.max(1), area.height.max(1))
+}
+
+/// Where a window in slice `split.index` of `split.count` goes: an equal
+/// share of the work area along `split.axis`, less `gaps`, and all of it the
+/// other way. Slice edges are rounded to the nearest pixel, so the slices
+/// cover the work area exactly. As with sides, each window gives up
+/// `inner / 2` pixels before a gap and the rest after it.
+pub fn calculate_slice_position(monitor: &Monitor, split: &Split, gaps: Gaps) -> Rect {
+    let area = inset_work_area(monitor, gaps);
+    let inner = gaps.inner as i32;
+    let count = i64::from(split.count.max(1));
+    let index = i64::from(split.index).min(count - 1);
+    let length = match split.axis {
+        SplitAxis::Horizontal => area.width,
+        SplitAxis::Vertical => area.height,
+    };
+    let edge = |i: i64| ((i64::from(length) * i * 2 + count) / (2 * count)) as i32;
+
+    let mut start = edge(index);
+    let mut end = edge(index + 1);
+    if index > 0 {
+        start += inner - inner / 2;
+    }
+    if index < count - 1 {
+        end -= inner / 2;
+    }
+    let size = (end - start).max(1);
+    match split.axis {
+        SplitAxis::Horizontal => Rect::new(area.x + start, area.y, size, area.height.max(1)),
+        SplitAxis::Vertical => Rect::new(area.x, area.y + start, area.width.max(1), size),
+    }
+}
+
+/// The monitor's work area less the outer gap on every edge
+fn inset_work_area(monitor: &Monitor, gaps: Gaps) -> Rect {
+    let outer = gaps.outer as i32;
+    Rect::new(
+        monitor.work_area.x + outer,
+        monitor.work_area.y + outer,
+        monitor.work_area.width - 2 * outer,
+        monitor.work_area.height - 2 * outer,
+    )
+}
+
+/// Where the app's window goes on `monitor`: its slot, shrunk to its
+/// `aspect_ratio` or grown to its `min_size` when it has them. Comes with
+/// what chose the slot, as `slot_rect` describes it.
+pub fn target_rect(
+    monitor: &Monitor,
+    app: &Application,
+    gaps: Gaps,
+) -> (Rect, Result<String, String>) {
+    let (slot, reason) = slot_rect(monitor, app, gaps);
+    let rect = constrain_rect(
+        slot,
+        app.min_size.map(|size| (size.width, size.height)),
+        app.aspect_ratio.map(|ratio| ratio.0),
+    );
+    (rect, reason)
+}
+
+/// The part of `work_area` a `relative_to` app takes beside its anchor's
+/// window at `anchor`, or `None` when there is no room there
+pub fn relative_rect(anchor: Rect, placement: RelativePlacement, work_area: Rect) -> Option<Rect> {
+    let right = Rect::new(
+        anchor.right(),
+        anchor.y,
+        work_area.right() - anchor.right(),
+        anchor.height,
+    );
+    let below = Rect::new(
+        anchor.x,
+        anchor.bottom(),
+        anchor.width,
+        work_area.bottom() - anchor.bottom(),
+    );
+    let rect = match placement {
+        RelativePlacement::RightOf => right,
+        RelativePlacement::Below => below,
+        RelativePlacement::FillRemaining => {
+            let strips = [
+                Rect {
+                    y: work_area.y,
+                    height: work_area.height,
+                    ..right
+                },
+                Rect::new(
+                    work_area.x,
+                    work_area.y,
+                    anchor.x - work_area.x,
+                    work_area.height,
+                ),
+                Rect {
+                    x: work_area.x,
+                    width: work_area.width,
+                    ..below
+                },
+                Rect::new(
+                    work_area.x,
+                    work_area.y,
+                    work_area.width,
+                    anchor.y - work_area.y,
+                ),
+            ];
+            // Ties go to the first strip: right, left, below, above
+            let area =
+                |strip: &Rect| i64::from(strip.width.max(0)) * i64::from(strip.height.max(0));
+            strips.into_iter().reduce(|best, next| {
+                if area(&next) > area(&best) {
+                    next
+                } else {
+                    best
+                }
+            })?
+        }
+    };
+    (rect.width > 0 && rect.height > 0).then_some(rect)
+}
+
+/// Where a `relative_to` app goes beside its anchor's window at `anchor`,
+/// or its side when there is no room there, as for `target_rect`
+pub fn relative_target_rect(
+    monitor: &Monitor,
+    app: &Application,
+    relative: &RelativeTo,
+    anchor: Rect,
+    gaps: Gaps,
+) -> (Rect, Result<String, String>) {
+    let reason = format!("{} '{}'", relative.placement, relative.app);
+    match relative_rect(anchor, relative.placement, monitor.work_area) {
+        Some(slot) => (
+            constrain_rect(
+                slot,
+                app.min_size.map(|size| (size.width, size.height)),
+                app.aspect_ratio.map(|ratio| ratio.0),
+            ),
+            Ok(reason),
+        ),
+        None => (
+            target_rect(monitor, app, gaps).0,
+            Err(format!("no room {}", reason)),
+        ),
+    }
+}
+
+/// Where one app's window would go, worked out without touching any window
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedPlacement {
+    pub app_name: String,
+    /// The display from the config, or the one `auto_distribute` picked
+    pub display: DisplaySelector,
+    /// The monitor `display` resolves to; `None` when it is not connected
+    pub monitor_index: Option<usize>,
+    /// The window's target, moved onto a monitor if it would be off-screen
+    pub rect: Option<Rect>,
+    /// How the target was chosen, e.g. "left side" or "geometry"
+    pub reason: String,
+    /// Something about the target worth a warning, e.g. that it was moved
+    pub warning: Option<String>,
+}
+
+/// Where every app in the config would go on `monitors`, in config order.
+/// Launching, the dry run and the GUI all place windows from this plan.
+/// Apps are planned whether or not they would be skipped on this machine.
+/// A `relative_to` app is planned beside where its anchor is planned; the
+/// launch places it again beside where the anchor actually landed.
+pub fn plan_layout(config: &Config, monitors: &[Monitor]) -> Vec<PlannedPlacement> {
+    let gaps = Gaps::from(config);
+    let mut plan: Vec<PlannedPlacement> = Vec::with_capacity(config.applications.len());
+    for (app, display) in config
+        .applications
+        .iter()
+        .zip(config.assign_displays(monitors.len()))
+    {
+        let Some(monitor) = placement_monitor(app, &display, monitors) else {
+            plan.push(PlannedPlacement {
+                app_name: app.name.clone(),
+                reason: format!("display {} was not found", display),
+                display,
+                monitor_index: None,
+                rect: None,
+                warning: None,
+            });
+            continue;
+        };
+
+        let anchor = app.relative_to.as_ref().map(|relative| {
+            let planned = plan.iter().find(|planned| planned.app_name == relative.app);
+            (relative, planned.and_then(|planned| planned.rect))
+        });
+        let gaps = gaps.for_app(app);
+        let (target, chosen) = match anchor {
+            Some((relative, Some(anchor))) => {
+                relative_target_rect(monitor, app, relative, anchor, gaps)
+            }
+            Some((relative, None)) => (
+                target_rect(monitor, app, gaps).0,
+                Err(format!("'{}' has no position", relative.app)),
+            ),
+            None => target_rect(monitor, app, gaps),
+        };
+        let (mut reason, mut warning) = match chosen {
+            Ok(reason) => (reason, None),
+            Err(e) => (
+                side_reason(app),
+                Some(format!("{}; using its side instead", e)),
+            ),
+        };
+        if app.aspect_ratio.is_some() {
+            reason.push_str(", fit to its aspect_ratio");
+        }
+        if app.min_size.is_some() {
+            reason.push_str(", at least its min_size");
+        }
+
+        let rect = clamp_to_visible(target, monitors);
+        if rect != target {
+            warning = Some(format!(
+                "target at ({}, {}) is off-screen; moving it to ({}, {}) instead",
+                target.x, target.y, rect.x, rect.y
+            ));
+        }
+        plan.push(PlannedPlacement {
+            app_name: app.name.clone(),
+            display,
+            monitor_index: Some(monitor.index),
+            rect: Some(rect),
+            reason,
+            warning,
+        });
+    }
+    plan
+}
+
+/// The monitor `app` is placed on: the one `display` resolves to, or for a
+/// desktop-origin `geometry`, which doesn't need its display, the one the
+/// geometry lands on
+pub fn placement_monitor<'a>(
+    app: &Application,
+    display: &DisplaySelector,
+    monitors: &'a [Monitor],
+) -> Option<&'a Monitor> {
+    if let Some(monitor) = display.resolve(monitors) {
+        return Some(monitor);
+    }
+    match app.geometry {
+        Some(geometry)
+            if app.geometry_origin == GeometryOrigin::Desktop && app.custom_rect.is_none() =>
+        {
+            let rect = clamp_to_visible(geometry, monitors);
+            let (x, y) = rect.center();
+            monitor_at_point(monitors, x, y).or_else(|| {
+                monitors
+                    .iter()
+                    .find(|monitor| monitor.bounds.intersects(&rect))
+            })
+        }
+        _ => None,
+    }
+}
+
+fn side_reason(app: &Application) -> String {
+    let side = format!("{:?}", app.side()).to_lowercase();
+    if app.split_ratio == 0.5 {
+        format!("{} side", side)
+    } else {
+        format!("{} side, split at {}", side, app.split_ratio)
+    }
+}
+
+/// The largest rectangle of `aspect_ratio` that fits in `slot`, grown to at
+/// least `min_size` (keeping the ratio) and centered on the slot. A window
+/// grown past its slot overlaps its neighbours evenly on both sides.
+pub fn constrain_rect(slot: Rect, min_size: Option<(i32, i32)>, aspect_ratio: Option<f64>) -> Rect {
+    let (mut width, mut height) = (f64::from(slot.width), f64::from(slot.height));
+    if let Some(ratio) = aspect_ratio {
+        if width / height > ratio {
+            width = height * ratio;
+        } else {
+            height = width / ratio;
+        }
+    }
+    if let Some((min_width, min_height)) = min_size {
+        if aspect_ratio.is_some() {
+            let scale = (f64::from(min_width) / width)
+                .max(f64::from(min_height) / height)
+                .max(1.0);
+            width *= scale;
+            height *= scale;
+        } else {
+            width = width.max(f64::from(min_width));
+            height = height.max(f64::from(min_height));
+        }
+    }
+
+    let (width, height) = (width.round() as i32, height.round() as i32);
+    Rect::new(
+        slot.x + (slot.width - width) / 2,
+        slot.y + (slot.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// The area set aside for the app, chosen in this order: the work area for
+/// `center_fixed`, then `custom_rect`, `geometry` and `split`, otherwise its
+/// `side` of the work area. Comes with what chose it, or why a `custom_rect`
+/// could not be used and the next choice was instead.
+fn slot_rect(monitor: &Monitor, app: &Application, gaps: Gaps) -> (Rect, Result<String, String>) {
+    let work_area = &monitor.work_area;
+    if app.center_fixed {
+        // The launch centers the window at its own size once it is found
+        let area = inset_work_area(monitor, gaps);
+        return (area, Ok("centered at its own size".to_string()));
+    }
+    let mut reason = Ok(side_reason(app));
+    if let Some(ref custom_rect) = app.custom_rect {
+        match custom_rect.evaluate(work_area.width, work_area.height) {
+            Ok(rect) => {
+                let rect = Rect {
+                    x: work_area.x + rect.x,
+                    y: work_area.y + rect.y,
+                    ..rect
+                };
+                return (rect, Ok("custom_rect".to_string()));
+            }
+            Err(e) => reason = Err(e),
+        }
+    }
+
+    match app.geometry {
+        Some(geometry) => match app.geometry_origin {
+            GeometryOrigin::Desktop => (geometry, Ok("geometry".to_string())),
+            GeometryOrigin::Monitor => (
+                Rect {
+                    x: monitor.work_area.x + geometry.x,
+                    y: monitor.work_area.y + geometry.y,
+                    ..geometry
+                },
+                Ok("geometry on its monitor".to_string()),
+            ),
+        },
+        None => match app.split {
+            Some(split) => (
+                calculate_slice_position(monitor, &split, gaps),
+                Ok(split.to_string()),
+            ),
+            _ => (
+                calculate_window_position(monitor, &app.side(), app.split_ratio, gaps),
+                reason,
+            ),
+        },
+    }
+}
+
+/// `rect` unchanged when any part of it is on a monitor. Otherwise, e.g. for
+/// a position saved while a since-unplugged monitor was connected, it is
+/// moved into the work area of the monitor nearest to it, shrinking to fit.
+pub fn clamp_to_visible(rect: Rect, monitors: &[Monitor]) -> Rect {
+    if monitors
+        .iter()
+        .any(|monitor| monitor.bounds.intersects(&rect))
+    {
+        return rect;
+    }
+    let (center_x, center_y) = rect.center();
+    let distance = |area: &Rect| {
+        let dx = (area.x - center_x).max(center_x - area.right()).max(0) as i64;
+        let dy = (area.y - center_y).max(center_y - area.bottom()).max(0) as i64;
+        dx * dx + dy * dy
+    };
+    let Some(area) = monitors
+        .iter()
+        .map(|monitor| monitor.work_area)
+        .min_by_key(distance)
+    else {
+        return rect;
+    };
+
+    let width = rect.width.min(area.width);
+    let height = rect.height.min(area.height);
+    Rect::new(
+        rect.x.clamp(area.x, area.right() - width),
+        rect.y.clamp(area.y, area.bottom() - height),
+        width,
+        height,
+    )
+}
+
+/// Returns the monitor whose bounds contain the point, if any
+pub fn monitor_at_point(monitors: &[Monitor], x: i32, y: i32) -> Option<&Monitor> {
+    monitors
+        .iter()
+        .find(|monitor| monitor.bounds.contains(x, y))
+}
+
+/// Parses a simulated monitor set such as `1920x1080@0,0;2560x1440@1920,0`.
+/// Each monitor may be followed by `=name` to give it a friendly name. The
+/// first monitor is the primary one, and work areas match the bounds.
+pub fn parse_monitor_spec(spec: &str) -> Result<Vec<Monitor>, String> {
+    let monitors = spec
+        .split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .enumerate()
+        .map(|(index, item)| {
+            let invalid = || {
+                format!(
+                    "Invalid monitor '{}': expected WIDTHxHEIGHT@X,Y[=NAME]",
+                    item
+                )
+            };
+            let (geometry, friendly_name) = match item.split_once('=') {
+                Some((geometry, name)) => (geometry.trim(), name.trim()),
+                None => (item, ""),
+            };
+            let (size, origin) = geometry.split_once('@').ok_or_else(invalid)?;
+            let (width, height) = size.split_once(['x', 'X']).ok_or_else(invalid)?;
+            let (x, y) = origin.split_once(',').ok_or_else(invalid)?;
+            let number = |value: &str| value.trim().parse::<i32>().map_err(|_| invalid());
+            let bounds = Rect::new(number(x)?, number(y)?, number(width)?, number(height)?);
+            if bounds.width <= 0 || bounds.height <= 0 {
+                return Err(invalid());
+            }
+
+            Ok(Monitor {
+                index,
+                name: format!("SIMULATED{}", index + 1),
+                friendly_name: friendly_name.to_string(),
+                bounds,
+                work_area: bounds,
+                primary: index == 0,
+                scale: 1.0,
+                handle: 0,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if monitors.is_empty() {
+        return Err("No monitors in simulated monitor spec".to_string());
+    }
+    Ok(monitors)
+}
+
+#[cfg(test)]
+pub fn test_monitor(index: usize, name: &str, bounds: Rect) -> Monitor {
+    Monitor {
+        index,
+        name: name.to_string(),
+        friendly_name: String::new(),
+        bounds,
+        work_area: Rect {
+            height: bounds.height - 40,
+            ..bounds
+        },
+        primary: index == 0,
+        scale: 1.0,
+        handle: index + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AspectRatio, CustomRect, Size};
+    use crate::mock::{mock_monitors_vertical, mock_monitors_with_gap};
+
+    #[test]
+    fn test_calculate_window_position() {
+        let monitor = test_monitor(0, "Test Monitor", Rect::new(0, 0, 1920, 1080));
+
+        assert_eq!(
+            calculate_window_position(&monitor, &Side::Left, 0.5, Gaps::default()),
+            Rect::new(0, 0, 960, 1040)
+        );
+        assert_eq!(
+            calculate_window_position(&monitor, &Side::Right, 0.5, Gaps::default()),
+            Rect::new(960, 0, 960, 1040)
+        );
+    }
+
+    #[test]
+    fn test_center_fixed() {
+        let monitor = test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080));
+        let app = Application {
+            name: "Calculator".to_string(),
+            center_fixed: true,
+            ..Default::default()
+        };
+        let (slot, reason) = target_rect(&monitor, &app, Gaps::default());
+        assert_eq!(slot, Rect::new(0, 0, 1920, 1040));
+        assert_eq!(reason, Ok("centered at its own size".to_string()));
+
+        // Centered at the window's own size
+        assert_eq!(slot.center_in(400, 600), Rect::new(760, 220, 400, 600));
+        // Too tall to fit: the title bar stays at the top of the work area
+        assert_eq!(slot.center_in(400, 1200), Rect::new(760, 0, 400, 1200));
+    }
+
+    #[test]
+    fn test_outset_by_borders() {
+        // Windows 10/11 at 100%: 7 px invisible borders left, right and bottom
+        let window = Rect::new(-7, 0, 974, 1047);
+        let frame = Rect::new(0, 0, 960, 1040);
+        let slot = Rect::new(0, 0, 960, 1040);
+        assert_eq!(
+            slot.outset_by_borders(window, frame),
+            Rect::new(-7, 0, 974, 1047)
+        );
+        // Without invisible borders the slot is used as is
+        assert_eq!(slot.outset_by_borders(frame, frame), slot);
+    }
+
+    #[test]
+    fn test_calculate_window_position_split_ratio() {
+        let monitor = test_monitor(1, "Odd Width Monitor", Rect::new(1920, 0, 1921, 1080));
+
+        // 60% on the left
+        let left = calculate_window_position(&monitor, &Side::Left, 0.6, Gaps::default());
+        assert_eq!(left.x, 1920);
+        assert_eq!(left.width, 1153);
+
+        // Complementary 40% on the right takes the rounded remainder
+        let right = calculate_window_position(&monitor, &Side::Right, 0.4, Gaps::default());
+        assert_eq!(right.x, 1920 + 1153);
+        assert_eq!(right.width, 768);
+        assert_eq!(right.right(), 3841);
+
+        // 30% Slack on the right of an ultrawide-style split, editor on the left
+        let monitor = test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080));
+        assert_eq!(
+            calculate_window_position(&monitor, &Side::Right, 0.3, Gaps::default()),
+            Rect::new(1344, 0, 576, 1040)
+        );
+        assert_eq!(
+            calculate_window_position(&monitor, &Side::Left, 0.7, Gaps::default()),
+            Rect::new(0, 0, 1344, 1040)
+        );
+        assert_eq!(
+            calculate_window_position(&monitor, &Side::Left, 0.3, Gaps::default()),
+            Rect::new(0, 0, 576, 1040)
+        );
+    }
+
+    #[test]
+    fn test_calculate_slice_position() {
+        // 1923 pixels don't divide into 5, so slice edges are rounded
+        let monitor = test_monitor(0, "Monitor1", Rect::new(0, 0, 1923, 1080));
+        let column = |index, gaps| {
+            let split = Split {
+                axis: SplitAxis::Horizontal,
+                count: 5,
+                index,
+            };
+            calculate_slice_position(&monitor, &split, gaps)
+        };
+        let widths: Vec<i32> = (0..5).map(|i| column(i, Gaps::default()).width).collect();
+        assert_eq!(widths, [385, 384, 385, 384, 385]);
+        assert_eq!(column(0, Gaps::default()), Rect::new(0, 0, 385, 1040));
+        assert_eq!(column(4, Gaps::default()), Rect::new(1538, 0, 385, 1040));
+
+        // Every pair of neighbours is exactly `inner` apart, and the outer
+        // gap is kept at both ends
+        let gaps = Gaps { inner: 7, outer: 4 };
+        let columns: Vec<Rect> = (0..5).map(|i| column(i, gaps)).collect();
+        assert_eq!(columns[0].x, 4);
+        assert_eq!(columns[4].right(), 1923 - 4);
+        for pair in columns.windows(2) {
+            assert_eq!(pair[1].x - pair[0].right(), 7);
+        }
+        assert!(columns.iter().all(|c| c.y == 4 && c.height == 1040 - 8));
+
+        // Rows split the height the same way
+        let split = Split {
+            axis: SplitAxis::Vertical,
+            count: 5,
+            index: 4,
+        };
+        assert_eq!(
+            calculate_slice_position(&monitor, &split, Gaps::default()),
+            Rect::new(0, 832, 1923, 208)
+        );
+
+        // Even halves are the two columns of a two-way split
+        for (index, side) in Side::ALL.iter().enumerate() {
+            let split = Split {
+                axis: SplitAxis::Horizontal,
+                count: 2,
+                index: index as u32,
+            };
+            assert_eq!(
+                calculate_window_position(&monitor, side, 0.5, gaps),
+                calculate_slice_position(&monitor, &split, gaps)
+            );
+        }
+    }
+
+    #[test]
+    fn test_calculate_window_position_gaps() {
+        let monitor = test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080));
+        let gaps = Gaps { inner: 7, outer: 0 };
+
+        // An odd gap gives the left window the smaller half
+        let left = calculate_window_position(&monitor, &Side::Left, 0.5, gaps);
+        let right = calculate_window_position(&monitor, &Side::Right, 0.5, gaps);
+        assert_eq!(left, Rect::new(0, 0, 957, 1040));
+        assert_eq!(right, Rect::new(964, 0, 956, 1040));
+        assert_eq!(right.x - left.right(), 7);
+
+        // The outer gap insets both windows from every edge of the work area
+        let gaps = Gaps {
+            inner: 5,
+            outer: 11,
+        };
+        let left = calculate_window_position(&monitor, &Side::Left, 0.5, gaps);
+        let right = calculate_window_position(&monitor, &Side::Right, 0.5, gaps);
+        assert_eq!(left, Rect::new(11, 11, 947, 1018));
+        assert_eq!(right, Rect::new(963, 11, 946, 1018));
+        assert_eq!(right.x - left.right(), 5);
+        assert_eq!(right.right(), 1920 - 11);
+    }
+
+    #[test]
+    fn test_app_gap() {
+        let monitor = test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080));
+        let mut app = Application {
+            side: Some(Side::Left),
+            ..Default::default()
+        };
+        let gaps = Gaps::default();
+        assert_eq!(
+            target_rect(&monitor, &app, gaps.for_app(&app)).0,
+            Rect::new(0, 0, 960, 1040)
+        );
+
+        // A gap of 10 insets the left half from the edges and the split
+        app.gap = Some(10);
+        assert_eq!(
+            target_rect(&monitor, &app, gaps.for_app(&app)).0,
+            Rect::new(10, 10, 945, 1020)
+        );
+        // and replaces the config's gaps rather than adding to them
+        let config_gaps = Gaps {
+            inner: 40,
+            outer: 40,
+        };
+        assert_eq!(
+            config_gaps.for_app(&app),
+            Gaps {
+                inner: 10,
+                outer: 10
+            }
+        );
+    }
+
+    #[test]
+    fn test_target_rect_with_geometry() {
+        // Second monitor to the right of a 1920 wide primary
+        let monitor = test_monitor(1, "Monitor2", Rect::new(1920, 0, 2560, 1440));
+        let mut app = Application {
+            side: Some(Side::Right),
+            ..Default::default()
+        };
+        assert_eq!(
+            target_rect(&monitor, &app, Gaps::default()).0,
+            Rect::new(1920 + 1280, 0, 1280, 1400)
+        );
+
+        app.geometry = Some(Rect::new(100, 50, 800, 600));
+        assert_eq!(
+            target_rect(&monitor, &app, Gaps::default()).0,
+            Rect::new(100, 50, 800, 600)
+        );
+
+        app.geometry_origin = GeometryOrigin::Monitor;
+        assert_eq!(
+            target_rect(&monitor, &app, Gaps::default()).0,
+            Rect::new(2020, 50, 800, 600)
+        );
+    }
+
+    #[test]
+    fn test_constrain_rect() {
+        let slot = Rect::new(0, 0, 960, 1040);
+        assert_eq!(constrain_rect(slot, None, None), slot);
+
+        // A 16:9 player in a tall half gets the full width, centered vertically
+        assert_eq!(
+            constrain_rect(slot, None, Some(16.0 / 9.0)),
+            Rect::new(0, 250, 960, 540)
+        );
+        // A wide slot limits the height instead
+        assert_eq!(
+            constrain_rect(Rect::new(1920, 0, 1920, 540), None, Some(4.0 / 3.0)),
+            Rect::new(1920 + 600, 0, 720, 540)
+        );
+
+        // Too narrow for the app's minimum: grow it, overlapping both sides evenly
+        assert_eq!(
+            constrain_rect(slot, Some((1200, 600)), None),
+            Rect::new(-120, 0, 1200, 1040)
+        );
+        // Growing keeps the aspect ratio
+        assert_eq!(
+            constrain_rect(slot, Some((1280, 0)), Some(16.0 / 9.0)),
+            Rect::new(-160, 160, 1280, 720)
+        );
+
+        let mut app = Application {
+            aspect_ratio: Some(AspectRatio(16.0 / 9.0)),
+            ..Default::default()
+        };
+        let monitor = test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080));
+        assert_eq!(
+            target_rect(&monitor, &app, Gaps::default()).0,
+            Rect::new(0, 250, 960, 540)
+        );
+        app.aspect_ratio = None;
+        app.min_size = Some(Size {
+            width: 1000,
+            height: 200,
+        });
+        assert_eq!(
+            target_rect(&monitor, &app, Gaps::default()).0,
+            Rect::new(-20, 0, 1000, 1040)
+        );
+    }
+
+    #[test]
+    fn test_parse_monitor_spec() {
+        let monitors =
+            parse_monitor_spec("1920x1080@0,0; 2560x1440@1920,-180=DELL U2720Q").unwrap();
+        assert_eq!(monitors.len(), 2);
+        assert_eq!(monitors[0].bounds, Rect::new(0, 0, 1920, 1080));
+        assert!(monitors[0].primary);
+        assert_eq!(monitors[1].bounds, Rect::new(1920, -180, 2560, 1440));
+        assert_eq!(monitors[1].work_area, monitors[1].bounds);
+        assert_eq!(monitors[1].label(), "DELL U2720Q (SIMULATED2)");
+
+        assert!(parse_monitor_spec("").is_err());
+        assert!(parse_monitor_spec("1920x1080").is_err());
+        assert!(parse_monitor_spec("1920x0@0,0").is_err());
+        assert!(
+            parse_monitor_spec("1920x1080@0,0;wide@1920,0")
+                .unwrap_err()
+                .contains("wide@1920,0")
+        );
+    }
+
+    #[test]
+    fn test_clamp_to_visible() {
+        let monitors = mock_monitors_with_gap();
+
+        // Partly visible windows are left alone
+        let partly = Rect::new(-500, 100, 800, 600);
+        assert_eq!(clamp_to_visible(partly, &monitors), partly);
+        // So are windows in the gap touching a monitor
+        let touching = Rect::new(1900, 100, 100, 100);
+        assert_eq!(clamp_to_visible(touching, &monitors), touching);
+
+        // A third monitor to the right was unplugged
+        assert_eq!(
+            clamp_to_visible(Rect::new(5000, 200, 1280, 1400), &monitors),
+            Rect::new(4680 - 1280, 0, 1280, 1400)
+        );
+        // Far above the laptop, and larger than its work area
+        assert_eq!(
+            clamp_to_visible(Rect::new(100, -3000, 2560, 1440), &monitors),
+            Rect::new(0, 0, 1920, 1040)
+        );
+        // Below the gap between the monitors
+        assert_eq!(
+            clamp_to_visible(Rect::new(1950, 2000, 100, 100), &monitors),
+            Rect::new(2120, 1400 - 100, 100, 100)
+        );
+
+        assert_eq!(clamp_to_visible(partly, &[]), partly);
+    }
+
+    #[test]
+    fn test_plan_layout() {
+        let monitors = vec![
+            test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080)),
+            test_monitor(1, "Monitor2", Rect::new(1920, 0, 2560, 1440)),
+        ];
+        let config = Config {
+            applications: vec![
+                Application {
+                    name: "Editor".to_string(),
+                    side: Some(Side::Right),
+                    display: Some(2.into()),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Player".to_string(),
+                    // Saved while a third monitor was connected
+                    geometry: Some(Rect::new(5000, 100, 800, 600)),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Chat".to_string(),
+                    display: Some(3.into()),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Recorder".to_string(),
+                    display: Some(3.into()),
+                    geometry: Some(Rect::new(2000, 100, 1280, 720)),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let plan = plan_layout(&config, &monitors);
+        assert_eq!(plan.len(), 4);
+        assert_eq!(plan[0].app_name, "Editor");
+        assert_eq!(plan[0].monitor_index, Some(1));
+        assert_eq!(plan[0].rect, Some(Rect::new(3200, 0, 1280, 1400)));
+        assert_eq!(plan[0].reason, "right side");
+        assert_eq!(plan[0].warning, None);
+
+        // Moved onto the nearest monitor, with a warning saying so
+        assert_eq!(plan[1].rect, Some(Rect::new(3680, 100, 800, 600)));
+        assert_eq!(plan[1].reason, "geometry");
+        assert_eq!(
+            plan[1].warning.as_deref(),
+            Some("target at (5000, 100) is off-screen; moving it to (3680, 100) instead")
+        );
+
+        assert_eq!(plan[2].monitor_index, None);
+        assert_eq!(plan[2].rect, None);
+        assert_eq!(plan[2].reason, "display 3 was not found");
+
+        // Desktop coordinates don't need the display; the monitor is the one
+        // the geometry is on
+        assert_eq!(plan[3].monitor_index, Some(1));
+        assert_eq!(plan[3].rect, Some(Rect::new(2000, 100, 1280, 720)));
+        assert_eq!(plan[3].reason, "geometry");
+    }
+
+    #[test]
+    fn test_relative_rect() {
+        let work_area = Rect::new(0, 0, 1920, 1040);
+        let anchor = Rect::new(0, 0, 1200, 700);
+        assert_eq!(
+            relative_rect(anchor, RelativePlacement::RightOf, work_area),
+            Some(Rect::new(1200, 0, 720, 700))
+        );
+        assert_eq!(
+            relative_rect(anchor, RelativePlacement::Below, work_area),
+            Some(Rect::new(0, 700, 1200, 340))
+        );
+        // The full-height strip to the right is larger than the one below
+        assert_eq!(
+            relative_rect(anchor, RelativePlacement::FillRemaining, work_area),
+            Some(Rect::new(1200, 0, 720, 1040))
+        );
+        let centered = Rect::new(200, 100, 1520, 900);
+        assert_eq!(
+            relative_rect(centered, RelativePlacement::FillRemaining, work_area),
+            Some(Rect::new(1720, 0, 200, 1040))
+        );
+
+        // An anchor reaching the edge leaves no room beside it
+        let full_width = Rect::new(0, 0, 1920, 700);
+        assert_eq!(
+            relative_rect(full_width, RelativePlacement::RightOf, work_area),
+            None
+        );
+        assert_eq!(
+            relative_rect(work_area, RelativePlacement::FillRemaining, work_area),
+            None
+        );
+    }
+
+    #[test]
+    fn test_plan_layout_relative_to() {
+        let monitors = vec![test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080))];
+        let relative = |anchor: &str, placement| {
+            Some(RelativeTo {
+                app: anchor.to_string(),
+                placement,
+            })
+        };
+        let config = Config {
+            applications: vec![
+                Application {
+                    name: "Editor".to_string(),
+                    custom_rect: Some(CustomRect {
+                        x: "0".to_string(),
+                        y: "0".to_string(),
+                        w: "1200".to_string(),
+                        h: "H".to_string(),
+                    }),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Preview".to_string(),
+                    relative_to: relative("Editor", RelativePlacement::RightOf),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Terminal".to_string(),
+                    side: Some(Side::Right),
+                    relative_to: relative("Editor", RelativePlacement::Below),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let plan = plan_layout(&config, &monitors);
+        assert_eq!(plan[1].rect, Some(Rect::new(1200, 0, 720, 1040)));
+        assert_eq!(plan[1].reason, "right of 'Editor'");
+        // The editor takes the full height, so the terminal keeps its side
+        assert_eq!(plan[2].rect, Some(Rect::new(960, 0, 960, 1040)));
+        assert_eq!(plan[2].reason, "right side");
+        assert_eq!(
+            plan[2].warning.as_deref(),
+            Some("no room below 'Editor'; using its side instead")
+        );
+    }
+
+    #[test]
+    fn test_monitor_at_point() {
+        // A primary monitor with a second one to the right and a third one above it
+        let monitors = vec![
+            test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080)),
+            test_monitor(1, "Monitor2", Rect::new(1920, 0, 2560, 1440)),
+            test_monitor(2, "Monitor3", Rect::new(0, -1080, 1920, 1080)),
+        ];
+
+        assert_eq!(
+            monitor_at_point(&monitors, 960, 540).unwrap().name,
+            "Monitor1"
+        );
+        // Right/bottom edges are exclusive
+        assert_eq!(
+            monitor_at_point(&monitors, 1920, 0).unwrap().name,
+            "Monitor2"
+        );
+        assert_eq!(
+            monitor_at_point(&monitors, 100, -1).unwrap().name,
+            "Monitor3"
+        );
+        // In the gap below the primary, next to the taller second monitor
+        assert!(monitor_at_point(&monitors, 100, 1200).is_none());
+
+        // Between monitors that don't touch
+        let monitors = mock_monitors_with_gap();
+        assert!(monitor_at_point(&monitors, 2000, 500).is_none());
+        assert_eq!(
+            monitor_at_point(&monitors, 2120, 500).unwrap().name,
+            "External"
+        );
+
+        let monitors = mock_monitors_vertical();
+        assert_eq!(monitor_at_point(&monitors, -100, -1).unwrap().name, "Above");
+        assert!(monitor_at_point(&monitors, -100, 0).is_none());
+    }
+
+    #[test]
+    fn test_calculate_window_position_stacked_monitors() {
+        let monitors = mock_monitors_vertical();
+        assert_eq!(
+            calculate_window_position(&monitors[1], &Side::Right, 0.5, Gaps::default()),
+            Rect::new(-320 + 1280, -1440, 1280, 1400)
+        );
+
+        // Gaps are measured from the monitor, not the desktop origin
+        let gaps = Gaps {
+            inner: 0,
+            outer: 10,
+        };
+        assert_eq!(
+            calculate_window_position(&monitors[1], &Side::Left, 0.5, gaps),
+            Rect::new(-310, -1430, 1270, 1380)
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::config::{Application, Config, DisplaySelector};
+
+/// Characters that make cmd.exe split or reinterpret an unquoted argument
+const BATCH_SPECIAL: &[char] = &[' ', '\t', '&', '|', '<', '>', '^', '(', ')', ',', ';', '='];
+
+/// Quotes an argument for a batch file when needed. `%` is doubled because
+/// batch files expand it even inside quotes.
+fn quote_batch(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%");
+    if arg.is_empty() || arg.contains(BATCH_SPECIAL) || arg.contains('"') {
+        format!("\"{}\"", escaped.replace('"', "\"\""))
+    } else {
+        escaped
+    }
+}
+
+/// The `start` line launching one application. Browser apps without an
+/// executable rely on `start` finding the browser through App Paths, since
+/// install locations differ between machines.
+fn start_line(app: &Application) -> String {
+    let mut line = String::from("start \"\"");
+    if let Some(ref dir) = app.working_dir {
+        line.push_str(&format!(" /D {}", quote_batch(dir)));
+    }
+
+    let mut args = Vec::new();
+    let program = match app.browser {
+        Some(ref browser) => {
+            args.extend(browser.args());
+            if app.executable.is_empty() {
+                browser.kind.windows_executable().to_string()
+            } else {
+                app.executable.clone()
+            }
+        }
+        None => app.executable.clone(),
+    };
+    args.extend(app.args.iter().cloned());
+    args.extend(app.open_file.iter().cloned());
+
+    // Always quote the program so `start` doesn't take it as the window title
+    line.push_str(&format!(" \"{}\"", program.replace('%', "%%")));
+    for arg in &args {
+        line.push(' ');
+        line.push_str(&quote_batch(arg));
+    }
+    line
+}
+
+/// Renders a `.bat` script that launches every enabled application in `config`
+pub fn batch_script(config: &Config, source: &str) -> String {
+    let mut lines = vec![
+        "@echo off".to_string(),
+        format!("rem Generated by jumpstart from {}", source),
+        "rem Window positions (display, side, split_ratio) cannot be expressed in a batch"
+            .to_string(),
+        "rem file, so apps open wherever Windows puts them. Use jumpstart to position them."
+            .to_string(),
+    ];
+
+    for app in &config.applications {
+        lines.push(String::new());
+        if !app.enabled {
+            lines.push(format!("rem {} is disabled", app.name));
+            continue;
+        }
+        let display = match app.display {
+            Some(ref display) => display.to_string(),
+            None if config.auto_distribute => "assigned at launch".to_string(),
+            None => DisplaySelector::default().to_string(),
+        };
+        lines.push(format!(
+            "rem {} (display {}, {:?})",
+            app.name,
+            display,
+            app.side()
+        ));
+        if let Some(ref when) = app.when {
+            lines.push(format!(
+                "rem Only launched by jumpstart when: {}",
+                serde_yaml::to_string(when)
+                    .unwrap_or_default()
+                    .trim()
+                    .replace('\n', ", ")
+            ));
+        }
+        lines.push(start_line(app));
+    }
+
+    // Batch files are expected to use CRLF line endings
+    let mut script = lines.join("\r\n");
+    script.push_str("\r\n");
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::{BrowserKind, BrowserLaunch};
+    use crate::config::Side;
+
+    #[test]
+    fn test_quote_batch() {
+        assert_eq!(quote_batch("--reuse"), "--reuse");
+        assert_eq!(quote_batch("C:\\My Projects"), "\"C:\\My Projects\"");
+        assert_eq!(quote_batch("a&b"), "\"a&b\"");
+        assert_eq!(quote_batch("50%"), "50%%");
+        assert_eq!(quote_batch("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_batch_script() {
+        let config = Config {
+            applications: vec![
+                Application {
+                    name: "Editor".to_string(),
+                    executable: "C:\\Program Files\\Editor\\editor.exe".to_string(),
+                    args: vec!["--profile".to_string(), "work".to_string()],
+                    working_dir: Some("C:\\My Projects".to_string()),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Calendar".to_string(),
+                    display: Some(2.into()),
+                    side: Some(Side::Right),
+                    browser: Some(BrowserLaunch {
+                        kind: BrowserKind::Edge,
+                        url: "https://calendar.example.com/?q=a%20b".to_string(),
+                        profile: Some("Profile 1".to_string()),
+                        app_mode: true,
+                    }),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Chat".to_string(),
+                    executable: "chat.exe".to_string(),
+                    enabled: false,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let script = batch_script(&config, "config.yml");
+        let lines: Vec<&str> = script.lines().collect();
+        assert_eq!(lines[0], "@echo off");
+        assert!(script.contains("\r\n"));
+        assert!(lines.contains(
+            &"start \"\" /D \"C:\\My Projects\" \"C:\\Program Files\\Editor\\editor.exe\" --profile work"
+        ));
+        assert!(lines.contains(
+            &"start \"\" \"msedge.exe\" \"--profile-directory=Profile 1\" \"--app=https://calendar.example.com/?q=a%%20b\""
+        ));
+        assert!(lines.contains(&"rem Chat is disabled"));
+        assert!(!script.contains("chat.exe"));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::config::GpuPreference;
+
+/// Per-app GPU choices Windows keeps for DirectX apps, keyed by executable path
+#[cfg(all(windows, feature = "gpu_preference"))]
+const USER_GPU_PREFERENCES_KEY: &str = "Software\\Microsoft\\DirectX\\UserGpuPreferences";
+
+/// Environment variables that steer an app to a GPU. Each vendor's driver
+/// only reads its own: Mesa's `DRI_PRIME`, NVIDIA's PRIME render offload on
+/// Linux and its Optimus `SHIM_MCCOMPAT` override on Windows.
+#[cfg(any(feature = "gpu_preference", test))]
+pub fn env_vars(preference: GpuPreference) -> &'static [(&'static str, &'static str)] {
+    match preference {
+        GpuPreference::HighPerformance => &[
+            ("DRI_PRIME", "1"),
+            ("__NV_PRIME_RENDER_OFFLOAD", "1"),
+            ("__GLX_VENDOR_LIBRARY_NAME", "nvidia"),
+            ("SHIM_MCCOMPAT", "0x800000001"),
+        ],
+        GpuPreference::PowerSaving => &[("DRI_PRIME", "0"), ("SHIM_MCCOMPAT", "0x800000000")],
+    }
+}
+
+/// The value Windows' graphics settings store for an app
+#[cfg(any(all(windows, feature = "gpu_preference"), test))]
+pub fn registry_value(preference: GpuPreference) -> &'static str {
+    match preference {
+        GpuPreference::PowerSaving => "GpuPreference=1;",
+        GpuPreference::HighPerformance => "GpuPreference=2;",
+    }
+}
+
+/// Records `preference` for `program` in Windows' graphics settings, as if
+/// chosen under Settings > Display > Graphics. The setting outlives the run.
+#[cfg(all(windows, feature = "gpu_preference"))]
+fn set_user_gpu_preference(program: &str, preference: GpuPreference) -> Result<(), String> {
+    use widestring::U16CString;
+    use winapi::shared::minwindef::DWORD;
+    use winapi::shared::winerror::ERROR_SUCCESS;
+    use winapi::um::winnt::REG_SZ;
+    use winapi::um::winreg::{HKEY_CURRENT_USER, RegSetKeyValueW};
+
+    // The setting is keyed by the full path of the executable
+    let path = crate::config::locate_executable(program)
+        .and_then(|path| std::path::absolute(path).ok())
+        .ok_or_else(|| format!("Could not find '{}' to set its GPU preference", program))?;
+
+    let key = U16CString::from_str(USER_GPU_PREFERENCES_KEY).map_err(|e| e.to_string())?;
+    let name = U16CString::from_str(path.to_string_lossy()).map_err(|e| e.to_string())?;
+    let value = U16CString::from_str(registry_value(preference)).map_err(|e| e.to_string())?;
+    let bytes = (value.len() + 1) * 2;
+
+    let status = unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            key.as_ptr(),
+            name.as_ptr(),
+            REG_SZ,
+            value.as_ptr() as *const _,
+            bytes as DWORD,
+        )
+    };
+    if status as DWORD != ERROR_SUCCESS {
+        return Err(format!(
+            "Failed to set the GPU preference of '{}' (error {})",
+            path.display(),
+            status
+        ));
+    }
+    Ok(())
+}
+
+/// Prepares `program` to run on the preferred GPU, returning the
+/// environment variables to start it with
+#[cfg(feature = "gpu_preference")]
+pub fn prepare(program: &str, preference: GpuPreference) -> Vec<(String, String)> {
+    #[cfg(windows)]
+    if let Err(e) = set_user_gpu_preference(program, preference) {
+        tracing::warn!("{}", e);
+    }
+    #[cfg(not(windows))]
+    let _ = program;
+    env_vars(preference)
+        .iter()
+        .map(|&(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[cfg(not(feature = "gpu_preference"))]
+pub fn prepare(program: &str, _preference: GpuPreference) -> Vec<(String, String)> {
+    tracing::warn!(
+        "Ignoring prefer_gpu for '{}': jumpstart was built without the gpu_preference feature",
+        program
+    );
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpu_preference() {
+        let config: crate::config::Config = serde_yaml::from_str(
+            r#"
+applications:
+  - name: "Renderer"
+    side: "left"
+    executable: "render.exe"
+    prefer_gpu: "high_performance"
+"#,
+        )
+        .unwrap();
+        let preference = config.applications[0].prefer_gpu.unwrap();
+        assert_eq!(preference, GpuPreference::HighPerformance);
+        assert_eq!(registry_value(preference), "GpuPreference=2;");
+        assert!(env_vars(preference).contains(&("DRI_PRIME", "1")));
+
+        assert_eq!(
+            registry_value(GpuPreference::PowerSaving),
+            "GpuPreference=1;"
+        );
+        assert!(env_vars(GpuPreference::PowerSaving).contains(&("DRI_PRIME", "0")));
+    }
+}
@@ -1,12 +1,27 @@
-use crate::config::{load_config, load_default_config, get_default_config_content, Config};
-use crate::app_launcher;
+use crate::config::{load_config, load_default_config, get_default_config_content, check_config_content, parse_config_content, Application, Config, ConfigIssue, DisplaySelector, LoadOptions, Side, LAYOUT_TEMPLATES};
+use crate::app_launcher::{self, AppStatus, LaunchProgress};
+use crate::display::{plan_layout, Monitor, PlannedPlacement};
+use crate::matching::title_matches;
+use crate::undo;
 use eframe::egui::{self, Color32, RichText, Vec2};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant, SystemTime};
 use tracing::{error, info, warn};
 
+/// How long the editor must be idle before its content is validated again
+const VALIDATION_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How often the config file is checked for changes made outside Jumpstart
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Default)]
 pub struct JumpstartGui {
     config_path: String,
+    load_options: LoadOptions,
     selected_config: Option<PathBuf>,
     config: Option<Config>,
     is_running: bool,
@@ -14,25 +29,118 @@ pub struct JumpstartGui {
     operation_in_progress: bool,
     show_config_editor: bool,
     editor_content: String,
+    /// Problems in `editor_content` as of the last validation
+    editor_issues: Vec<ConfigIssue>,
+    /// Hash of the editor content `editor_issues` was computed for
+    validated_hash: Option<u64>,
+    /// When the editor content was last edited, for debouncing validation
+    last_edit: Option<Instant>,
+    /// Hash of the editor content as last loaded or saved; anything else is unsaved
+    saved_hash: Option<u64>,
+    /// Modification time of the config file as last loaded or saved
+    config_modified: Option<SystemTime>,
+    /// When the config file was last checked for outside changes
+    last_change_check: Option<Instant>,
+    /// Action that would lose unsaved editor changes, waiting for confirmation
+    pending_discard: Option<DiscardAction>,
     theme: Theme,
+    progress_rx: Option<Receiver<LaunchProgress>>,
+    app_status: Vec<AppStatus>,
+    group_by_monitor: bool,
+    /// Only cards whose app name contains this are shown
+    app_filter: String,
+    /// Launch shows what would happen instead of launching
+    dry_run: bool,
+    /// Launch first asks for confirmation, showing where each app will go
+    confirm_launch: bool,
+    /// A launch waiting for the user to confirm it
+    pending_launch: bool,
+    /// The running launch only starts the apps, leaving their windows alone
+    quick_launching: bool,
+    monitors: Vec<Monitor>,
+    can_undo: bool,
+    /// Card highlighted after being added by drag and drop
+    focused_app: Option<usize>,
+    /// Card to bring into view on the next frame
+    scroll_to_app: Option<usize>,
+    /// Index into `LAYOUT_TEMPLATES` waiting for the user to confirm replacing the config
+    pending_template: Option<usize>,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Theme {
-    Light,
-    Dark,
+/// Actions that replace the editor content
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiscardAction {
+    CloseEditor,
+    LoadConfig,
+}
+
+/// Storage key for `GuiState`
+const GUI_STATE_KEY: &str = "gui_state";
+
+/// GUI state remembered between runs. eframe remembers the window's own size
+/// and position alongside it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GuiState {
+    config_editor_open: bool,
+    #[serde(default)]
+    confirm_launch: bool,
 }
 
-impl Default for Theme {
-    fn default() -> Self {
-        Theme::Dark
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Opens the file with the program the system associates with it
+#[cfg(windows)]
+fn open_with_default_app(path: &Path) -> Result<(), String> {
+    use widestring::U16CString;
+    use winapi::um::shellapi::ShellExecuteW;
+    use winapi::um::winuser::SW_SHOWNORMAL;
+
+    let verb = U16CString::from_str("open").map_err(|e| e.to_string())?;
+    let file = U16CString::from_os_str(path.as_os_str()).map_err(|e| e.to_string())?;
+    let result = unsafe {
+        ShellExecuteW(std::ptr::null_mut(), verb.as_ptr(), file.as_ptr(), std::ptr::null(), std::ptr::null(), SW_SHOWNORMAL)
+    };
+    // Values up to 32 are error codes
+    if result as usize > 32 {
+        Ok(())
+    } else {
+        Err(format!("no program is set up to open {} (error {})", path.display(), result as usize))
     }
 }
 
+/// Opens the file with the program the system associates with it
+#[cfg(not(windows))]
+fn open_with_default_app(path: &Path) -> Result<(), String> {
+    let opener = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+    let mut child = std::process::Command::new(opener)
+        .arg(path)
+        .spawn()
+        .map_err(|e| format!("failed to run {}: {}", opener, e))?;
+    // Reap the opener once it has handed the file over
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+enum Theme {
+    Light,
+    #[default]
+    Dark,
+}
+
 impl JumpstartGui {
     pub fn new() -> Self {
         let mut gui = Self {
             config_path: "config.yml".to_string(),
+            load_options: LoadOptions::default(),
             selected_config: None,
             config: None,
             is_running: false,
@@ -40,8 +148,29 @@ impl JumpstartGui {
             operation_in_progress: false,
             show_config_editor: false,
             editor_content: get_default_config_content().to_string(),
+            editor_issues: Vec::new(),
+            validated_hash: None,
+            last_edit: None,
+            saved_hash: None,
+            config_modified: None,
+            last_change_check: None,
+            pending_discard: None,
             theme: Theme::Dark,
+            progress_rx: None,
+            app_status: Vec::new(),
+            group_by_monitor: false,
+            app_filter: String::new(),
+            dry_run: false,
+            confirm_launch: false,
+            pending_launch: false,
+            quick_launching: false,
+            monitors: app_launcher::monitors(),
+            can_undo: undo::has_snapshot(),
+            focused_app: None,
+            scroll_to_app: None,
+            pending_template: None,
         };
+        gui.mark_saved();
 
         // Try to load default embedded config
         if let Ok(default_config) = load_default_config() {
@@ -52,15 +181,17 @@ impl JumpstartGui {
         gui
     }
 
-    pub fn with_initial_config(config_path: String) -> Self {
+    pub fn with_initial_config(config_path: String, load_options: LoadOptions) -> Self {
         let mut gui = Self::new();
         gui.config_path = config_path.clone();
+        gui.load_options = load_options;
 
         // Try to load the initial config
-        match load_config(&config_path) {
+        match load_config(&config_path, &gui.load_options) {
             Ok(config) => {
                 gui.config = Some(config);
                 gui.selected_config = Some(PathBuf::from(config_path.clone()));
+                gui.reload_editor_content();
                 gui.status_message = format!("✅ Loaded configuration from {}", config_path);
             }
             Err(e) => {
@@ -71,6 +202,16 @@ impl JumpstartGui {
         gui
     }
 
+    /// Restores the state saved by the last run, if any
+    pub fn with_saved_state(mut self, storage: Option<&dyn eframe::Storage>) -> Self {
+        let state: Option<GuiState> = storage.and_then(|storage| eframe::get_value(storage, GUI_STATE_KEY));
+        if let Some(state) = state {
+            self.show_config_editor = state.config_editor_open;
+            self.confirm_launch = state.confirm_launch;
+        }
+        self
+    }
+
     fn select_config_file(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
             .add_filter("YAML Files", &["yml", "yaml"])
@@ -81,9 +222,10 @@ impl JumpstartGui {
             self.config_path = path.to_string_lossy().to_string();
 
             // Try to load the config
-            match load_config(&self.config_path) {
+            match load_config(&self.config_path, &self.load_options) {
                 Ok(config) => {
                     self.config = Some(config);
+                    self.reload_editor_content();
                     self.status_message = format!("✅ Loaded configuration from {}", path.display());
                     info!("Loaded config from: {}", path.display());
                 }
@@ -101,8 +243,9 @@ impl JumpstartGui {
             match std::fs::write(path, &self.editor_content) {
                 Ok(()) => {
                     self.status_message = format!("✅ Saved configuration to {}", path.display());
+                    self.mark_saved();
                     // Reload the config
-                    match load_config(&self.config_path) {
+                    match load_config(&self.config_path, &self.load_options) {
                         Ok(config) => {
                             self.config = Some(config);
                         }
@@ -124,11 +267,12 @@ impl JumpstartGui {
             {
                 match std::fs::write(&path, &self.editor_content) {
                     Ok(()) => {
+                        self.mark_saved();
                         self.selected_config = Some(path.clone());
                         self.config_path = path.to_string_lossy().to_string();
                         self.status_message = format!("✅ Saved new configuration to {}", path.display());
                         // Reload the config
-                        match load_config(&self.config_path) {
+                        match load_config(&self.config_path, &self.load_options) {
                             Ok(config) => {
                                 self.config = Some(config);
                             }
@@ -145,11 +289,274 @@ impl JumpstartGui {
         }
     }
 
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped: Vec<PathBuf> = ctx.input(|i| {
+            i.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect()
+        });
+
+        if !dropped.is_empty() && self.is_running {
+            self.status_message = "⚠️ Cannot add applications while launching".to_string();
+            return;
+        }
+
+        for path in dropped {
+            let app = match Application::from_executable_path(&path) {
+                Ok(app) => app,
+                Err(e) => {
+                    self.status_message = format!("❌ {}", e);
+                    warn!("Rejected dropped file: {}", e);
+                    continue;
+                }
+            };
+
+            let config = self.config.get_or_insert_with(Config::default);
+            info!("Adding {} from dropped file {}", app.name, path.display());
+            self.status_message = format!("➕ Added {}. Adjust its display and side in the editor, then save.", app.name);
+            config.applications.push(app);
+
+            let index = config.applications.len() - 1;
+            self.focused_app = Some(index);
+            self.scroll_to_app = Some(index);
+            // Make sure the new card isn't hidden by the filter
+            self.app_filter.clear();
+            match serde_yaml::to_string(config) {
+                Ok(content) => {
+                    self.editor_content = content;
+                    self.show_config_editor = true;
+                }
+                Err(e) => error!("Failed to serialize config: {}", e),
+            }
+        }
+    }
+
     fn load_default_config_content(&mut self) {
         self.editor_content = get_default_config_content().to_string();
         self.status_message = "Loaded default configuration template".to_string();
     }
 
+    /// Puts the selected config file, or the default config, in the editor as the saved state
+    fn reload_editor_content(&mut self) {
+        match self.selected_config.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => self.editor_content = content,
+            _ => self.load_default_config_content(),
+        }
+        self.mark_saved();
+    }
+
+    fn mark_saved(&mut self) {
+        self.saved_hash = Some(content_hash(&self.editor_content));
+        self.config_modified = self.selected_config.as_deref().and_then(modified_time);
+    }
+
+    /// Opens the config file in the system's editor for YAML files
+    fn open_in_editor(&mut self) {
+        let Some(path) = self.selected_config.clone() else {
+            return;
+        };
+        match open_with_default_app(&path) {
+            Ok(()) => {
+                self.status_message = format!("📝 Opened {}. Saved changes are loaded automatically.", path.display());
+                info!("Opened {} in the default editor", path.display());
+            }
+            Err(e) => {
+                self.status_message = format!("❌ Could not open the config: {}", e);
+                error!("Failed to open '{}': {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Loads the config again when its file changed outside Jumpstart, e.g.
+    /// in the editor opened by "Open in editor". Unsaved edits in the
+    /// built-in editor are kept.
+    fn reload_if_changed(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.selected_config.clone() else {
+            return;
+        };
+        ctx.request_repaint_after(CONFIG_CHECK_INTERVAL);
+        if self.is_running || self.last_change_check.is_some_and(|checked| checked.elapsed() < CONFIG_CHECK_INTERVAL) {
+            return;
+        }
+        self.last_change_check = Some(Instant::now());
+
+        let modified = modified_time(&path);
+        if modified.is_none() || modified == self.config_modified {
+            return;
+        }
+        // The first time stamp seen is the one the loaded config has
+        let first_check = self.config_modified.is_none();
+        self.config_modified = modified;
+        if first_check {
+            return;
+        }
+
+        match load_config(&self.config_path, &self.load_options) {
+            Ok(config) => {
+                self.config = Some(config);
+                if self.is_dirty() {
+                    self.status_message = format!("⚠️ {} changed on disk; the editor still shows your unsaved changes", path.display());
+                } else {
+                    self.reload_editor_content();
+                    self.status_message = format!("🔄 Reloaded {} after it changed on disk", path.display());
+                }
+                info!("Reloaded config from {} after it changed", path.display());
+            }
+            Err(e) => {
+                self.status_message = format!("⚠️ {} changed on disk but failed to load: {}", path.display(), e);
+                warn!("Failed to reload changed config '{}': {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Whether the editor content differs from what was last loaded or saved
+    fn is_dirty(&self) -> bool {
+        self.saved_hash != Some(content_hash(&self.editor_content))
+    }
+
+    /// Runs `action` now, or after confirmation when it would lose unsaved edits
+    fn request_discard(&mut self, action: DiscardAction) {
+        if self.is_dirty() {
+            self.pending_discard = Some(action);
+        } else {
+            self.discard_and(action);
+        }
+    }
+
+    fn discard_and(&mut self, action: DiscardAction) {
+        self.reload_editor_content();
+        match action {
+            DiscardAction::CloseEditor => self.show_config_editor = false,
+            DiscardAction::LoadConfig => self.select_config_file(),
+        }
+    }
+
+    fn render_discard_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.pending_discard else {
+            return;
+        };
+
+        egui::Window::new("Discard unsaved changes?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label("The configuration in the editor has changes that haven't been saved.");
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Discard").clicked() {
+                        self.pending_discard = None;
+                        self.discard_and(action);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending_discard = None;
+                    }
+                });
+            });
+    }
+
+    /// Replaces the current config and editor content with a built-in template.
+    /// The result isn't tied to a file, so saving asks where to put it.
+    fn apply_template(&mut self, index: usize) {
+        let (name, content) = LAYOUT_TEMPLATES[index];
+        match parse_config_content(content) {
+            Ok(config) => {
+                self.config = Some(config);
+                self.selected_config = None;
+                self.editor_content = content.to_string();
+                self.show_config_editor = true;
+                self.app_status.clear();
+                self.status_message = format!("✅ Loaded the '{}' template. Edit it and save it to keep it.", name);
+                info!("Loaded layout template: {}", name);
+            }
+            Err(e) => {
+                error!("Failed to load layout template '{}': {}", name, e);
+                self.status_message = format!("❌ Failed to load template '{}': {}", name, e);
+            }
+        }
+    }
+
+    fn render_template_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(index) = self.pending_template else {
+            return;
+        };
+        let (name, _) = LAYOUT_TEMPLATES[index];
+
+        egui::Window::new("Use template?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("Replace the current configuration with the '{}' template?", name));
+                if self.is_dirty() {
+                    ui.label(RichText::new("Unsaved changes in the editor will be lost.").color(Color32::from_rgb(200, 150, 100)));
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Replace").clicked() {
+                        self.apply_template(index);
+                        self.pending_template = None;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending_template = None;
+                    }
+                });
+            });
+    }
+
+    /// Launches now, or after confirmation when `confirm_launch` is set
+    fn request_launch(&mut self) {
+        if self.confirm_launch && !self.dry_run && self.config.is_some() {
+            self.pending_launch = true;
+        } else {
+            self.start_applications();
+        }
+    }
+
+    fn render_launch_confirmation(&mut self, ctx: &egui::Context) {
+        if !self.pending_launch {
+            return;
+        }
+        let Some(ref config) = self.config else {
+            self.pending_launch = false;
+            return;
+        };
+        let plan = plan_layout(config, &self.monitors);
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        egui::Window::new("Launch applications?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label("These applications will be launched and their windows moved:");
+                ui.add_space(4.0);
+                egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    for (app, planned) in config.applications.iter().zip(&plan) {
+                        if !app.enabled {
+                            continue;
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(&app.name).strong());
+                            ui.label(RichText::new(format!("→ display {}, {}", planned.display, planned.reason))
+                                .color(display_color(&planned.display)));
+                        }).response.on_hover_text(planned_description(planned));
+                    }
+                });
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Launch").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+
+        if confirmed {
+            self.pending_launch = false;
+            self.start_applications();
+        } else if cancelled {
+            self.pending_launch = false;
+        }
+    }
+
     fn start_applications(&mut self) {
         if self.config.is_none() {
             self.status_message = "❌ No configuration loaded. Please select a config file first.".to_string();
@@ -161,25 +568,138 @@ impl JumpstartGui {
             return;
         }
 
+        if self.dry_run {
+            self.preview_launch();
+            return;
+        }
+
         self.is_running = true;
+        self.operation_in_progress = true;
+        self.quick_launching = false;
         self.status_message = "🚀 Starting applications...".to_string();
 
-        // For now, run synchronously to avoid threading complications
-        // The GUI will be responsive enough for this short operation
+        // Launch on a background thread so the GUI stays responsive; progress
+        // comes back over the channel and is drained in `poll_progress`
         let config = self.config.as_ref().unwrap().clone();
+        self.app_status = vec![AppStatus::Idle; config.applications.len()];
 
-        match app_launcher::launch_and_position_applications(&config) {
-            Ok(()) => {
+        let (tx, rx) = mpsc::channel();
+        self.progress_rx = Some(rx);
+        app_launcher::spawn_launch(config, tx);
+    }
+
+    /// Starts the enabled applications without waiting for or moving their
+    /// windows. Nothing is moved, so there is nothing to confirm.
+    fn quick_launch(&mut self) {
+        let Some(ref config) = self.config else {
+            return;
+        };
+        if self.is_running {
+            return;
+        }
+
+        self.is_running = true;
+        self.operation_in_progress = true;
+        self.quick_launching = true;
+        self.status_message = "🚀 Starting applications...".to_string();
+        let config = config.clone();
+        self.app_status = vec![AppStatus::Idle; config.applications.len()];
+
+        let (tx, rx) = mpsc::channel();
+        self.progress_rx = Some(rx);
+        app_launcher::spawn_launch_only(config, tx);
+    }
+
+    /// Shows what a launch would do, like `--dry-run`, without launching or moving anything
+    fn preview_launch(&mut self) {
+        let Some(ref config) = self.config else {
+            return;
+        };
+        let lines = app_launcher::dry_run(config, None, None);
+        info!("Dry run from the GUI:\n{}", lines.join("\n"));
+        self.status_message = format!("🔍 Dry run, nothing was launched:\n{}", lines.join("\n"));
+    }
+
+    fn poll_progress(&mut self) {
+        let Some(ref rx) = self.progress_rx else {
+            return;
+        };
+
+        let mut completed = None;
+        while let Ok(event) = rx.try_recv() {
+            AppStatus::apply(&mut self.app_status, &event);
+            if let LaunchProgress::Completed(result) = event {
+                completed = Some(result);
+            }
+        }
+
+        let Some(result) = completed else {
+            return;
+        };
+
+        match result {
+            Ok(report) if report.is_success() && self.quick_launching => {
+                self.status_message = "✅ Applications started; their windows were left where they opened.".to_string();
+                info!("All applications started without positioning");
+            }
+            Ok(report) if self.quick_launching => {
+                let failed: Vec<&str> = report
+                    .results
+                    .iter()
+                    .filter(|r| r.outcome.is_failure())
+                    .map(|r| r.name.as_str())
+                    .collect();
+                self.status_message = format!(
+                    "⚠️ Started {} of {} applications. Failed: {}",
+                    report.results.len() - failed.len(),
+                    report.results.len(),
+                    failed.join(", ")
+                );
+                warn!("Some applications could not be started: {}", failed.join(", "));
+            }
+            Ok(report) if report.is_success() => {
                 self.status_message = "✅ Applications launched successfully!".to_string();
                 info!("All applications launched and positioned successfully");
             }
+            Ok(report) => {
+                let failed: Vec<&str> = report
+                    .results
+                    .iter()
+                    .filter(|r| r.outcome.is_failure())
+                    .map(|r| r.name.as_str())
+                    .collect();
+                self.status_message = format!(
+                    "⚠️ Positioned {} of {} applications. Failed: {}",
+                    report.positioned_count(),
+                    report.results.len(),
+                    failed.join(", ")
+                );
+                warn!("Some applications could not be positioned: {}", failed.join(", "));
+            }
             Err(e) => {
                 self.status_message = format!("❌ Error launching applications: {}", e);
                 error!("Failed to launch and position applications: {}", e);
             }
         }
 
+        self.progress_rx = None;
+        self.operation_in_progress = false;
         self.is_running = false;
+        self.can_undo = undo::has_snapshot();
+    }
+
+    fn undo_last_launch(&mut self) {
+        match undo::undo_last_launch() {
+            Ok(restored) => {
+                self.status_message = format!("↩ Restored {} windows to their previous positions", restored);
+                info!("Restored {} windows", restored);
+            }
+            Err(e) => {
+                self.status_message = format!("❌ Undo failed: {}", e);
+                error!("Undo failed: {}", e);
+            }
+        }
+        self.can_undo = undo::has_snapshot();
     }
 
     fn update_status(&mut self) {
@@ -229,48 +749,83 @@ impl JumpstartGui {
             // Action buttons
             ui.horizontal(|ui| {
                 if ui.button("📂 Load").clicked() {
-                    self.select_config_file();
+                    self.request_discard(DiscardAction::LoadConfig);
                 }
 
                 if ui.button("📝 Edit").clicked() {
-                    self.show_config_editor = !self.show_config_editor;
                     if self.show_config_editor {
-                        // Load current config content for editing
-                        if let Some(ref path) = self.selected_config {
-                            match std::fs::read_to_string(path) {
-                                Ok(content) => {
-                                    self.editor_content = content;
-                                }
-                                Err(_) => {
-                                    self.load_default_config_content();
-                                }
-                            }
-                        } else {
-                            self.load_default_config_content();
+                        self.request_discard(DiscardAction::CloseEditor);
+                    } else {
+                        // Keep unsaved content, e.g. from a dropped file; otherwise show what's on disk
+                        if !self.is_dirty() {
+                            self.reload_editor_content();
                         }
+                        self.show_config_editor = true;
                     }
                 }
 
-                if ui.button("🔄 Reset").clicked() {
-                    if let Ok(default_config) = load_default_config() {
-                        self.config = Some(default_config);
-                        self.selected_config = None;
-                        self.status_message = "✅ Reset to default configuration".to_string();
-                    }
+                if ui.button("🔄 Reset").clicked()
+                    && let Ok(default_config) = load_default_config()
+                {
+                    self.config = Some(default_config);
+                    self.selected_config = None;
+                    self.status_message = "✅ Reset to default configuration".to_string();
+                }
+
+                if ui.add_enabled(self.selected_config.is_some(), egui::Button::new("✏️ Open in editor"))
+                    .on_hover_text("Open the config file in your usual editor; saved changes are loaded automatically")
+                    .on_disabled_hover_text("The embedded default has no file to open; save it first")
+                    .clicked()
+                {
+                    self.open_in_editor();
                 }
+
+                ui.menu_button("📋 Templates", |ui| {
+                    for (index, (name, _)) in LAYOUT_TEMPLATES.iter().enumerate() {
+                        if ui.button(*name).clicked() {
+                            self.pending_template = Some(index);
+                            ui.close_menu();
+                        }
+                    }
+                });
             });
         });
     }
 
     fn render_applications_preview(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
-            ui.heading(RichText::new("🚀 Applications").size(16.0).color(self.get_accent_color()));
+            ui.horizontal(|ui| {
+                ui.heading(RichText::new("🚀 Applications").size(16.0).color(self.get_accent_color()));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.checkbox(&mut self.group_by_monitor, "Group by monitor");
+                });
+            });
             ui.add_space(8.0);
 
+            self.render_color_legend(ui);
+            ui.add_space(4.0);
+
+            let scroll_to = self.scroll_to_app.take();
             if let Some(ref config) = self.config {
+                let visible: Vec<bool> = config.applications.iter()
+                    .map(|app| title_matches(&app.name, self.app_filter.trim()))
+                    .collect();
+                if !config.applications.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label("🔍");
+                        ui.add(egui::TextEdit::singleline(&mut self.app_filter)
+                            .hint_text("Filter by name")
+                            .desired_width(200.0));
+                        let shown = visible.iter().filter(|&&shown| shown).count();
+                        ui.label(RichText::new(format!("showing {} of {}", shown, visible.len()))
+                            .color(Color32::from_rgb(150, 150, 150)));
+                    });
+                    ui.add_space(4.0);
+                }
+
                 if config.applications.is_empty() {
                     ui.centered_and_justified(|ui| {
-                        ui.label(RichText::new("No applications configured").color(Color32::from_rgb(150, 150, 150)));
+                        ui.label(RichText::new("No applications configured. Drop an .exe here to add one.").color(Color32::from_rgb(150, 150, 150)));
                     });
                 } else {
                     // Create a scrollable area for applications
@@ -278,8 +833,37 @@ impl JumpstartGui {
                     egui::ScrollArea::vertical()
                         .min_scrolled_height(480.0)
                         .show(ui, |ui| {
-                            for (index, app) in config.applications.iter().enumerate() {
-                                self.render_application_card(ui, app, index);
+                            let plan = plan_layout(config, &self.monitors);
+                            if self.group_by_monitor {
+                                for (display, mut indices) in config.indices_by_display(self.monitors.len()) {
+                                    indices.retain(|&index| visible[index]);
+                                    if indices.is_empty() {
+                                        continue;
+                                    }
+                                    egui::CollapsingHeader::new(self.display_group_title(&display, indices.len()))
+                                        .id_salt(("display_group", display))
+                                        .default_open(true)
+                                        .show(ui, |ui| {
+                                            for index in indices {
+                                                let status = self.app_status.get(index).unwrap_or(&AppStatus::Idle);
+                                                let card = self.render_application_card(ui, &config.applications[index], &plan[index], index, status);
+                                                if scroll_to == Some(index) {
+                                                    card.scroll_to_me(Some(egui::Align::Center));
+                                                }
+                                            }
+                                        });
+                                }
+                            } else {
+                                for (index, app) in config.applications.iter().enumerate() {
+                                    if !visible[index] {
+                                        continue;
+                                    }
+                                    let status = self.app_status.get(index).unwrap_or(&AppStatus::Idle);
+                                    let card = self.render_application_card(ui, app, &plan[index], index, status);
+                                    if scroll_to == Some(index) {
+                                        card.scroll_to_me(Some(egui::Align::Center));
+                                    }
+                                }
                             }
                         });
                 }
@@ -291,8 +875,37 @@ impl JumpstartGui {
         });
     }
 
-    fn render_application_card(&self, ui: &mut egui::Ui, app: &crate::config::Application, index: usize) {
-        ui.group(|ui| {
+    fn render_color_legend(&self, ui: &mut egui::Ui) {
+        let muted = Color32::from_rgb(150, 150, 150);
+        ui.horizontal_wrapped(|ui| {
+            ui.label(RichText::new("Display:").size(11.0).color(muted));
+            for (label, display) in [("1", 1), ("2", 2), ("other", 3)] {
+                ui.label(RichText::new(format!("● {}", label)).size(11.0).color(display_color(&display.into())));
+            }
+            ui.separator();
+            ui.label(RichText::new("Side:").size(11.0).color(muted));
+            for side in Side::ALL {
+                ui.label(RichText::new(format!("● {:?}", side)).size(11.0).color(side_color(&side)));
+            }
+        });
+    }
+
+    fn display_group_title(&self, display: &DisplaySelector, count: usize) -> String {
+        let apps = if count == 1 { "app" } else { "apps" };
+        match display.resolve(&self.monitors) {
+            Some(monitor) => format!("🖥 Display {} ({}) · {} {}", display, monitor.label(), count, apps),
+            None if self.monitors.is_empty() => format!("🖥 Display {} · {} {}", display, count, apps),
+            None => format!("🖥 Display {} (not connected) · {} {}", display, count, apps),
+        }
+    }
+
+    fn render_application_card(&self, ui: &mut egui::Ui, app: &crate::config::Application, planned: &PlannedPlacement, index: usize, status: &AppStatus) -> egui::Response {
+        let mut frame = egui::Frame::group(ui.style());
+        if self.focused_app == Some(index) {
+            frame = frame.stroke(egui::Stroke::new(2.0, self.get_accent_color()));
+        }
+        let display = &planned.display;
+        let response = frame.show(ui, |ui| {
             ui.horizontal(|ui| {
                 // Application icon/number
                 ui.label(RichText::new(format!("{}. {}", index + 1, app.name))
@@ -302,25 +915,43 @@ impl JumpstartGui {
                 ui.separator();
 
                 // Display info
-                let display_color = match app.display {
-                    1 => Color32::from_rgb(100, 200, 100),
-                    2 => Color32::from_rgb(200, 200, 100),
-                    _ => Color32::from_rgb(200, 150, 100),
-                };
-                ui.label(RichText::new(format!("D{}", app.display))
+                ui.label(RichText::new(display.short_label())
                     .size(12.0)
-                    .color(display_color));
+                    .color(display_color(display)));
 
                 ui.separator();
 
                 // Side info
-                let side_color = match app.side {
-                    crate::config::Side::Left => Color32::from_rgb(150, 150, 200),
-                    crate::config::Side::Right => Color32::from_rgb(200, 150, 150),
-                };
-                ui.label(RichText::new(format!("{:?}", app.side))
+                let side = ui.label(RichText::new(format!("{:?}", app.side()))
                     .size(12.0)
-                    .color(side_color));
+                    .color(side_color(&app.side())));
+                if !self.monitors.is_empty() {
+                    side.on_hover_text(planned_description(planned));
+                }
+
+                if !app.enabled {
+                    ui.separator();
+                    ui.label(RichText::new("disabled").size(12.0).color(Color32::from_rgb(150, 150, 150)));
+                }
+
+                // Live launch status
+                match status {
+                    AppStatus::Idle => {}
+                    AppStatus::Launching => {
+                        ui.separator();
+                        ui.spinner();
+                    }
+                    AppStatus::Done(outcome) => {
+                        ui.separator();
+                        let (icon, color) = if outcome.is_failure() {
+                            ("❌", Color32::from_rgb(220, 100, 100))
+                        } else {
+                            ("✅", Color32::from_rgb(100, 200, 100))
+                        };
+                        ui.label(RichText::new(icon).size(12.0).color(color))
+                            .on_hover_text(outcome.to_string());
+                    }
+                }
             });
 
             // Show executable path on a smaller line
@@ -328,8 +959,9 @@ impl JumpstartGui {
             ui.label(RichText::new(format!("📄 {}", app.executable))
                 .size(10.0)
                 .color(Color32::from_rgb(120, 120, 120)));
-        });
+        }).response;
         ui.add_space(6.0);
+        response
     }
 
     fn render_controls(&mut self, ui: &mut egui::Ui) {
@@ -342,6 +974,8 @@ impl JumpstartGui {
 
             let start_text = if self.is_running {
                 "⏳ Launching..."
+            } else if self.dry_run {
+                "🔍 Dry run"
             } else {
                 "🚀 Launch"
             };
@@ -352,13 +986,35 @@ impl JumpstartGui {
                 Color32::from_rgb(150, 150, 150) // Gray
             };
 
+            ui.horizontal(|ui| {
+                if ui.add_enabled(
+                    start_button_enabled,
+                    egui::Button::new(RichText::new(start_text).size(15.0).color(Color32::WHITE))
+                        .fill(button_color)
+                        .min_size(Vec2::new(190.0, 36.0))
+                ).clicked() {
+                    self.request_launch();
+                }
+                ui.add_enabled(!self.is_running, egui::Checkbox::new(&mut self.dry_run, "Dry run"))
+                    .on_hover_text("Show what would be launched and where, without launching or moving anything");
+            });
+            if ui.add_enabled(
+                start_button_enabled && !self.dry_run,
+                egui::Button::new(RichText::new("⚡ Quick Launch").size(13.0))
+                    .min_size(Vec2::new(190.0, 28.0))
+            ).on_hover_text("Start the enabled applications without waiting for their windows or moving them").clicked() {
+                self.quick_launch();
+            }
+            ui.checkbox(&mut self.confirm_launch, "Confirm before launch")
+                .on_hover_text("Show where each application will go and ask before rearranging the desktop");
+
+            ui.add_space(4.0);
             if ui.add_enabled(
-                start_button_enabled,
-                egui::Button::new(RichText::new(start_text).size(15.0).color(Color32::WHITE))
-                    .fill(button_color)
-                    .min_size(Vec2::new(280.0, 36.0))
-            ).clicked() {
-                self.start_applications();
+                self.can_undo && !self.is_running,
+                egui::Button::new(RichText::new("↩ Undo last launch").size(13.0))
+                    .min_size(Vec2::new(280.0, 28.0))
+            ).on_hover_text("Move windows back to where they were before the last launch").clicked() {
+                self.undo_last_launch();
             }
 
             ui.add_space(12.0);
@@ -385,13 +1041,18 @@ impl JumpstartGui {
 
     fn render_config_editor(&mut self, ctx: &egui::Context) {
         let mut keep_open = true;
-        let window_title = if let Some(ref path) = self.selected_config {
+        let mut window_title = if let Some(ref path) = self.selected_config {
             format!("Editing: {}", path.file_name().unwrap().to_str().unwrap_or("config.yml"))
         } else {
             "Editing: New Configuration".to_string()
         };
+        if self.is_dirty() {
+            window_title.push_str("  ● Unsaved");
+        }
 
+        // A fixed ID keeps the window in place when its title changes
         egui::Window::new(window_title)
+            .id(egui::Id::new("config_editor"))
             .open(&mut keep_open)
             .default_size(Vec2::new(800.0, 600.0))
             .show(ctx, |ui| {
@@ -410,22 +1071,67 @@ impl JumpstartGui {
                     ui.label("YAML Configuration:");
                 });
 
+                ui.add_space(8.0);
+                self.render_editor_issues(ui);
                 ui.add_space(8.0);
 
                 egui::ScrollArea::vertical()
                     .id_salt("config_editor")
                     .show(ui, |ui| {
-                        ui.add_sized(
+                        let response = ui.add_sized(
                             [ui.available_width(), ui.available_height() - 20.0],
                             egui::TextEdit::multiline(&mut self.editor_content)
                                 .font(egui::TextStyle::Monospace)
                                 .desired_width(f32::INFINITY)
                         );
+                        if response.changed() {
+                            self.last_edit = Some(Instant::now());
+                        }
                     });
             });
 
         if !keep_open {
-            self.show_config_editor = false;
+            self.request_discard(DiscardAction::CloseEditor);
+        }
+    }
+
+    /// Revalidates the editor content once typing pauses. Results are cached
+    /// by content hash, so frames that don't change the text never re-parse it.
+    fn validate_editor_content(&mut self, ctx: &egui::Context) {
+        let hash = content_hash(&self.editor_content);
+        if self.validated_hash == Some(hash) {
+            return;
+        }
+
+        let idle = self
+            .last_edit
+            .map_or(VALIDATION_DEBOUNCE, |edited| edited.elapsed());
+        if idle < VALIDATION_DEBOUNCE {
+            // Come back when the debounce runs out, even without further input
+            ctx.request_repaint_after(VALIDATION_DEBOUNCE - idle);
+            return;
+        }
+
+        self.editor_issues = check_config_content(&self.editor_content, &self.load_options);
+        self.validated_hash = Some(hash);
+    }
+
+    fn render_editor_issues(&self, ui: &mut egui::Ui) {
+        if self.validated_hash.is_none() {
+            return;
+        }
+        if self.editor_issues.is_empty() {
+            ui.label(
+                RichText::new("✅ Configuration is valid").color(Color32::from_rgb(100, 200, 100)),
+            );
+        }
+        for issue in &self.editor_issues {
+            let (icon, color) = if issue.warning {
+                ("⚠️", Color32::from_rgb(220, 180, 80))
+            } else {
+                ("❌", Color32::from_rgb(220, 100, 100))
+            };
+            ui.label(RichText::new(format!("{} {}", icon, issue.message)).color(color));
         }
     }
 
@@ -437,18 +1143,70 @@ impl JumpstartGui {
     }
 }
 
+/// Color coding for display numbers on application cards
+/// Where the app would be placed, for the card's tooltip
+fn planned_description(planned: &PlannedPlacement) -> String {
+    let mut text = match planned.rect {
+        Some(rect) => format!(
+            "{}x{} at ({}, {}): {}",
+            rect.width, rect.height, rect.x, rect.y, planned.reason
+        ),
+        None => format!("Not placed: {}", planned.reason),
+    };
+    if let Some(ref warning) = planned.warning {
+        text.push_str(&format!("\n⚠ {}", warning));
+    }
+    text
+}
+
+fn display_color(display: &DisplaySelector) -> Color32 {
+    match display {
+        DisplaySelector::Number(1) => Color32::from_rgb(100, 200, 100),
+        DisplaySelector::Number(2) => Color32::from_rgb(200, 200, 100),
+        _ => Color32::from_rgb(200, 150, 100),
+    }
+}
+
+/// Color coding for sides on application cards
+fn side_color(side: &Side) -> Color32 {
+    match side {
+        Side::Left => Color32::from_rgb(150, 150, 200),
+        Side::Right => Color32::from_rgb(200, 150, 150),
+    }
+}
+
 impl eframe::App for JumpstartGui {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let state = GuiState {
+            config_editor_open: self.show_config_editor,
+            confirm_launch: self.confirm_launch,
+        };
+        eframe::set_value(storage, GUI_STATE_KEY, &state);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Apply theme
         self.apply_theme(ctx);
 
         // Update internal state
+        self.poll_progress();
         self.update_status();
+        self.handle_dropped_files(ctx);
+        self.reload_if_changed(ctx);
+
+        // Keep repainting while a launch runs so progress shows up without input
+        if self.is_running {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
 
         // Show config editor if open
         if self.show_config_editor {
+            self.validate_editor_content(ctx);
             self.render_config_editor(ctx);
         }
+        self.render_template_confirmation(ctx);
+        self.render_discard_confirmation(ctx);
+        self.render_launch_confirmation(ctx);
 
         // Main layout with compact design and proper padding
         egui::CentralPanel::default().show(ctx, |ui| {
@@ -0,0 +1,167 @@
+use crate::matching::WindowCandidate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[cfg(windows)]
+use std::path::PathBuf;
+
+/// What an app's window looked like the last time it was positioned, to
+/// tell it apart from other windows its criteria match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowIdentity {
+    pub class: String,
+    /// Full path of the program that owned the window, when it could be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executable: Option<String>,
+    pub title: String,
+}
+
+impl WindowIdentity {
+    /// How much `candidate`, owned by `executable`, resembles this window.
+    /// The program counts most, then the class, then the exact title.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn resemblance(&self, candidate: &WindowCandidate, executable: Option<&str>) -> u32 {
+        let same_executable = self
+            .executable
+            .as_deref()
+            .zip(executable)
+            .is_some_and(|(remembered, executable)| remembered.eq_ignore_ascii_case(executable));
+        4 * u32::from(same_executable)
+            + 2 * u32::from(self.class == candidate.class)
+            + u32::from(self.title == candidate.title)
+    }
+}
+
+/// Of the windows that match an app, the one most like its `remembered`
+/// window; ties go to the first. `executable_of` is only asked when there is
+/// more than one window to choose from.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn most_like<'a>(
+    matching: &[&'a WindowCandidate],
+    remembered: &WindowIdentity,
+    executable_of: impl Fn(&WindowCandidate) -> Option<String>,
+) -> Option<&'a WindowCandidate> {
+    if matching.len() < 2 {
+        return matching.first().copied();
+    }
+    matching
+        .iter()
+        .map(|&candidate| {
+            let executable = executable_of(candidate);
+            (
+                remembered.resemblance(candidate, executable.as_deref()),
+                candidate,
+            )
+        })
+        .reduce(|best, next| if next.0 > best.0 { next } else { best })
+        .map(|(_, candidate)| candidate)
+}
+
+/// The window each app was last positioned with, by app name
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdentityStore {
+    #[serde(default)]
+    pub apps: BTreeMap<String, WindowIdentity>,
+}
+
+impl IdentityStore {
+    /// The stored identities, or none when the file is missing or unreadable
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+        }
+        let content = serde_yaml::to_string(self)
+            .map_err(|e| format!("Failed to serialize window identities: {}", e))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+    }
+}
+
+/// Location of the remembered window identities, next to the undo snapshot
+#[cfg(windows)]
+pub fn store_path() -> PathBuf {
+    std::env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("jumpstart")
+        .join("windows.yml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(hwnd: usize, title: &str, class: &str, process_id: u32) -> WindowCandidate {
+        WindowCandidate {
+            hwnd,
+            title: title.to_string(),
+            class: class.to_string(),
+            cloaked: false,
+            process_id,
+        }
+    }
+
+    #[test]
+    fn test_most_like_prefers_the_remembered_window() {
+        let remembered = WindowIdentity {
+            class: "CASCADIA_HOSTING_WINDOW_CLASS".to_string(),
+            executable: Some("C:\\Program Files\\WindowsTerminal.exe".to_string()),
+            title: "Build shell".to_string(),
+        };
+        let console = window(1, "Build shell", "ConsoleWindowClass", 10);
+        let terminal = window(2, "PowerShell", "CASCADIA_HOSTING_WINDOW_CLASS", 20);
+        let executable_of = |candidate: &WindowCandidate| {
+            (candidate.process_id == 20)
+                .then(|| "c:\\program files\\windowsterminal.exe".to_string())
+        };
+
+        // The same program and class outweigh a title that happens to match
+        assert_eq!(
+            most_like(&[&console, &terminal], &remembered, executable_of),
+            Some(&terminal)
+        );
+        // Nothing in common with either: the first match, as without memory
+        let other = WindowIdentity {
+            class: "Notepad".to_string(),
+            executable: None,
+            title: "notes.txt".to_string(),
+        };
+        assert_eq!(
+            most_like(&[&console, &terminal], &other, executable_of),
+            Some(&console)
+        );
+        assert_eq!(most_like(&[], &other, executable_of), None);
+    }
+
+    #[test]
+    fn test_identity_store_round_trip() {
+        let mut store = IdentityStore::default();
+        store.apps.insert(
+            "Terminal".to_string(),
+            WindowIdentity {
+                class: "CASCADIA_HOSTING_WINDOW_CLASS".to_string(),
+                executable: None,
+                title: "Build shell".to_string(),
+            },
+        );
+
+        let path = std::env::temp_dir()
+            .join("jumpstart_identity_test")
+            .join("windows.yml");
+        store.save_to(&path).unwrap();
+        assert_eq!(IdentityStore::load_from(&path), store);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(IdentityStore::load_from(&path), IdentityStore::default());
+    }
+}
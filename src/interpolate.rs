@@ -0,0 +1,100 @@
+/// Expands `${VAR}` and `${VAR:-default}` references in `input`, docker-compose style.
+/// `$$` produces a literal `$`. References to undefined variables without a default
+/// are left as written and their names pushed onto `undefined`.
+pub fn interpolate(
+    input: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    undefined: &mut Vec<String>,
+) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(stripped) = after.strip_prefix('$') {
+            output.push('$');
+            rest = stripped;
+            continue;
+        }
+
+        let Some(body_and_rest) = after.strip_prefix('{') else {
+            output.push('$');
+            rest = after;
+            continue;
+        };
+        let Some(end) = body_and_rest.find('}') else {
+            // Unterminated reference; keep it literally
+            output.push_str(&rest[pos..]);
+            rest = "";
+            break;
+        };
+
+        let body = &body_and_rest[..end];
+        let (name, default) = match body.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (body, None),
+        };
+
+        // As in docker-compose, `:-` also replaces variables set to an empty string
+        match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => output.push_str(default),
+            (Some(value), _) => output.push_str(&value),
+            (None, Some(default)) => output.push_str(default),
+            (None, None) => {
+                undefined.push(name.to_string());
+                output.push_str(&rest[pos..pos + end + 3]);
+            }
+        }
+        rest = &body_and_rest[end + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Looks variables up in the process environment
+pub fn env_lookup(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "USER" => Some("shawon".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    fn expand(input: &str) -> (String, Vec<String>) {
+        let mut undefined = Vec::new();
+        let output = interpolate(input, &lookup, &mut undefined);
+        (output, undefined)
+    }
+
+    #[test]
+    fn test_interpolate() {
+        assert_eq!(
+            expand("C:\\Users\\${USER}\\app.exe").0,
+            "C:\\Users\\shawon\\app.exe"
+        );
+        assert_eq!(expand("${MISSING:-work}-${USER}").0, "work-shawon");
+        assert_eq!(expand("${EMPTY:-fallback}").0, "fallback");
+        assert_eq!(expand("[${EMPTY}]").0, "[]");
+        assert_eq!(expand("cost: $$5 or $5").0, "cost: $5 or $5");
+        assert_eq!(expand("no vars").0, "no vars");
+        assert_eq!(expand("${UNTERMINATED").0, "${UNTERMINATED");
+    }
+
+    #[test]
+    fn test_undefined_variables_are_left_literal() {
+        let (output, undefined) = expand("${NOPE}/${USER}/${ALSO_NOPE}");
+        assert_eq!(output, "${NOPE}/shawon/${ALSO_NOPE}");
+        assert_eq!(undefined, vec!["NOPE", "ALSO_NOPE"]);
+    }
+}
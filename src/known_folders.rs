@@ -0,0 +1,118 @@
+/// A Windows folder whose location depends on the machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnownFolder {
+    /// `Program Files`, where the machine's native apps are installed
+    ProgramFiles,
+    /// `Program Files (x86)` on 64-bit Windows, `Program Files` on 32-bit
+    ProgramFilesX86,
+}
+
+/// Placeholders for known folders, matched ignoring case
+const PLACEHOLDERS: [(&str, KnownFolder); 4] = [
+    ("{pf}", KnownFolder::ProgramFiles),
+    ("{pf86}", KnownFolder::ProgramFilesX86),
+    ("%programfiles%", KnownFolder::ProgramFiles),
+    ("%programfiles(x86)%", KnownFolder::ProgramFilesX86),
+];
+
+/// Replaces `{pf}`/`%ProgramFiles%` and `{pf86}`/`%ProgramFiles(x86)%` in
+/// `path` with where those folders are on this machine, as Windows reports
+/// them. Placeholders for folders it doesn't know are left as written.
+pub fn resolve_known_folders(path: &str) -> String {
+    replace_known_folders(path, known_folder_path)
+}
+
+fn replace_known_folders(path: &str, lookup: impl Fn(KnownFolder) -> Option<String>) -> String {
+    let mut output = String::with_capacity(path.len());
+    let mut rest = path;
+    'scan: while !rest.is_empty() {
+        for (placeholder, folder) in PLACEHOLDERS {
+            if rest
+                .get(..placeholder.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(placeholder))
+                && let Some(location) = lookup(folder)
+            {
+                output.push_str(&location);
+                rest = &rest[placeholder.len()..];
+                continue 'scan;
+            }
+        }
+        let next = rest.chars().next().map_or(1, char::len_utf8);
+        output.push_str(&rest[..next]);
+        rest = &rest[next..];
+    }
+    output
+}
+
+#[cfg(windows)]
+fn known_folder_path(folder: KnownFolder) -> Option<String> {
+    use std::ptr;
+    use widestring::U16CStr;
+    use winapi::shared::winerror::SUCCEEDED;
+    use winapi::um::combaseapi::CoTaskMemFree;
+    use winapi::um::knownfolders::{FOLDERID_ProgramFiles, FOLDERID_ProgramFilesX86};
+    use winapi::um::shlobj::SHGetKnownFolderPath;
+
+    let id = match folder {
+        KnownFolder::ProgramFiles => &FOLDERID_ProgramFiles,
+        KnownFolder::ProgramFilesX86 => &FOLDERID_ProgramFilesX86,
+    };
+    let mut path = ptr::null_mut();
+    let result = unsafe { SHGetKnownFolderPath(id, 0, ptr::null_mut(), &mut path) };
+    // The buffer is allocated even when the call fails
+    let location = (SUCCEEDED(result) && !path.is_null())
+        .then(|| unsafe { U16CStr::from_ptr_str(path) }.to_string_lossy());
+    unsafe { CoTaskMemFree(path as *mut _) };
+    location
+}
+
+#[cfg(not(windows))]
+fn known_folder_path(_folder: KnownFolder) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_known_folders() {
+        // As on 64-bit Windows
+        let lookup = |folder| {
+            Some(match folder {
+                KnownFolder::ProgramFiles => "C:\\Program Files".to_string(),
+                KnownFolder::ProgramFilesX86 => "C:\\Program Files (x86)".to_string(),
+            })
+        };
+        assert_eq!(
+            replace_known_folders("{pf86}\\Slack\\slack.exe", lookup),
+            "C:\\Program Files (x86)\\Slack\\slack.exe"
+        );
+        assert_eq!(
+            replace_known_folders("%ProgramFiles%\\Notepad++\\notepad++.exe", lookup),
+            "C:\\Program Files\\Notepad++\\notepad++.exe"
+        );
+        assert_eq!(
+            replace_known_folders("%PROGRAMFILES(X86)%\\Tool.exe", lookup),
+            "C:\\Program Files (x86)\\Tool.exe"
+        );
+        assert_eq!(
+            replace_known_folders("{PF}\\a.exe", lookup),
+            "C:\\Program Files\\a.exe"
+        );
+        assert_eq!(
+            replace_known_folders("ünï {pf}", lookup),
+            "ünï C:\\Program Files"
+        );
+        assert_eq!(
+            replace_known_folders("{pfx}\\a.exe", lookup),
+            "{pfx}\\a.exe"
+        );
+
+        // Where the folders aren't known, e.g. off Windows
+        assert_eq!(
+            replace_known_folders("{pf}\\a.exe", |_| None),
+            "{pf}\\a.exe"
+        );
+    }
+}
@@ -1,9 +1,39 @@
 mod app_launcher;
+mod autostart;
+mod bench;
+mod browser;
+mod capture;
+mod conditions;
 mod config;
+mod control;
+mod desktop;
+mod display;
+mod export;
+mod gpu;
+mod identity;
+mod interpolate;
+mod known_folders;
+mod matching;
+mod notify;
+mod placement_script;
+mod prefetch;
+mod priority;
+mod readiness;
+mod record;
+mod render;
+mod remote;
+mod replay;
+mod schedule;
+mod session;
+mod snapshot;
+mod watch;
 
 #[cfg(windows)]
 mod monitor;
 
+#[cfg(windows)]
+mod virtual_desktop;
+
 #[cfg(windows)]
 mod window;
 
@@ -11,79 +41,689 @@ mod window;
 mod mock;
 
 mod gui;
+mod tui;
+mod undo;
+
+use app_launcher::{
+    LaunchOptions, Pacing, RETRY_BASE_DELAY, ShellLauncher, launch_and_position_applications,
+    launch_with_retries,
+};
+use config::{LoadOptions, load_config};
+use control::ControlServer;
+use schedule::ScheduledLayouts;
+use tracing::{error, info, warn};
+use watch::WatchedLayout;
 
-use app_launcher::launch_and_position_applications;
-use config::load_config;
-use tracing::{error, info};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
-#[allow(clippy::single_component_path_imports)]
-use tracing_subscriber;
+use clap::{ArgGroup, Parser, Subcommand};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use clap::Parser;
+/// Exit code of a CLI run cancelled with Ctrl+C, as for a shell's SIGINT
+const EXIT_CANCELLED: i32 = 130;
 
 #[derive(Parser)]
 #[command(name = "jumpstart")]
 #[command(about = "Application launcher for positioning windows")]
+#[command(group(ArgGroup::new("mode").args(["gui", "cli", "tui"])))]
 struct Cli {
-    /// Path to the configuration file
+    /// Path or http(s):// URL of the configuration file
     #[arg(short = 'f', long, default_value = "config.yml")]
     config: String,
 
+    /// Directory of config files, one per profile, instead of a single --config file
+    #[arg(long, value_name = "DIR", conflicts_with = "config")]
+    config_dir: Option<PathBuf>,
+
+    /// Profile to use from --config-dir, i.e. a file name without its extension
+    #[arg(long, requires = "config_dir")]
+    profile: Option<String>,
+
+    /// Launch in GUI mode (the default)
+    #[arg(short, long)]
+    gui: bool,
+
     /// Launch in CLI mode instead of GUI mode
     #[arg(short, long)]
     cli: bool,
+
+    /// Launch an interactive terminal UI, e.g. over SSH where the GUI can't run
+    #[arg(short, long)]
+    tui: bool,
+
+    /// Show what would be launched and where, without launching or moving anything
+    #[arg(long, conflicts_with_all = ["gui", "tui"])]
+    dry_run: bool,
+
+    /// Position the configured apps' open windows without launching anything (same as `snap`)
+    #[arg(long, conflicts_with_all = ["gui", "tui", "dry_run"])]
+    reposition_only: bool,
+
+    /// Dry-run against these monitors instead of the connected ones, e.g. "1920x1080@0,0;2560x1440@1920,0"
+    #[arg(long, value_name = "SPEC", requires = "dry_run")]
+    simulate_monitors: Option<String>,
+
+    /// Write the open windows' titles, classes and process IDs to this file and exit
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["gui", "cli", "tui", "dry_run", "reposition_only"])]
+    dump_windows: Option<PathBuf>,
+
+    /// Dry-run window matching against a --dump-windows file instead of the open windows
+    #[arg(long, value_name = "FILE", requires = "dry_run")]
+    windows_from: Option<PathBuf>,
+
+    /// Seconds to wait before launching, e.g. to let the desktop settle after login
+    #[arg(long, value_name = "SECONDS", requires = "cli")]
+    delay: Option<u64>,
+
+    /// Re-run the whole launch up to N times, with exponential backoff, until every app is positioned
+    #[arg(long, value_name = "N", default_value_t = 0, requires = "cli")]
+    retry_run: u32,
+
+    /// Append every window move to this file as a JSON line, e.g. to check a layout in CI
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["gui", "tui", "dry_run"])]
+    record: Option<PathBuf>,
+
+    /// Append every launch and window move to this file with its time, for `jumpstart replay`
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["gui", "tui", "dry_run"])]
+    record_session: Option<PathBuf>,
+
+    /// Be gentle on slow machines: longer delays, no background window enumeration, no smooth_move or enforce
+    #[arg(long, conflicts_with_all = ["gui", "tui", "dry_run"])]
+    safe_mode: bool,
+
+    /// Keep running after the launch and put windows back when their app restarts (CLI mode only)
+    #[arg(long, requires = "cli")]
+    watch: bool,
+
+    /// While watching, accept commands such as `reapply` from `jumpstart control` over a local pipe or socket
+    #[arg(long, requires = "watch")]
+    control: bool,
+
+    /// Show a desktop notification summarizing the run (CLI mode only)
+    #[arg(long, requires = "cli")]
+    notify: bool,
+
+    /// Log every window seen while searching for an app's window, to work out match criteria
+    #[arg(long, global = true)]
+    verbose_matching: bool,
+
+    /// Log newline-delimited JSON records instead of human-readable lines
+    #[arg(long, env = "JUMPSTART_JSON_LOGS")]
+    json_logs: bool,
+
+    /// Also log to this file, starting a new one each day, e.g. for runs at login
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+
+    /// Treat warnings as errors: undefined ${VAR} references, apps sharing a slot,
+    /// executables that can't be run, and in CLI mode the first app that isn't positioned
+    #[arg(long, global = true, conflicts_with = "lenient")]
+    strict: bool,
+
+    /// Like --strict, but launch every app before failing the run
+    #[arg(long, global = true, conflicts_with_all = ["lenient", "strict"])]
+    strict_continue: bool,
+
+    /// Position windows even when the config's expected_monitors doesn't match the connected monitors
+    #[arg(long, global = true)]
+    ignore_monitor_mismatch: bool,
+
+    /// Ignore unknown fields in the config with a warning instead of failing
+    #[arg(long, global = true)]
+    lenient: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Snap the configured apps' open windows into the layout without launching anything
+    Snap,
+    /// Show how long launching the config takes, per app and in total
+    Bench {
+        /// Also launch the apps and measure the actual time each one takes
+        #[arg(long)]
+        real: bool,
+    },
+    /// Write a config for the windows that are open now, as a starting point
+    #[command(alias = "from-running")]
+    Capture {
+        /// File to write the config to instead of printing it
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Move windows back to where they were before the last launch
+    Undo,
+    /// Draw where the configured apps would go to a PNG, without launching anything
+    RenderLayout {
+        /// Path of the PNG to write
+        file: PathBuf,
+        /// Draw on these monitors instead of the connected ones, e.g. "1920x1080@0,0;2560x1440@1920,0"
+        #[arg(long, value_name = "SPEC")]
+        monitors: Option<String>,
+    },
+    /// Write a .bat script that launches the configured apps without jumpstart
+    ExportScript {
+        /// Path of the script to write
+        file: PathBuf,
+    },
+    /// Launch jumpstart in CLI mode with this config whenever you log in
+    InstallAutostart {
+        /// Seconds to wait after login before launching
+        #[arg(long, default_value_t = 0)]
+        delay: u64,
+    },
+    /// Stop launching jumpstart at login
+    UninstallAutostart,
+    /// Send a command to a jumpstart running with --watch --control: reapply, launch <profile> or status
+    Control {
+        #[arg(required = true, num_args = 1..)]
+        command: Vec<String>,
+    },
+    /// Repeat the launches and moves of a --record-session file with their timing, ignoring the config
+    Replay {
+        /// Session file written by --record-session
+        file: PathBuf,
+    },
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing subscriber with default info level
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
+    // Parse command line arguments
+    let mut cli = Cli::parse();
+
+    // Initialize tracing subscriber with default info level. The TUI owns the
+    // terminal, so console logging is discarded there; status is shown inline.
+    let console = if cli.tui {
+        None
+    } else if cli.json_logs {
+        Some(json_layer(std::io::stdout).boxed())
+    } else {
+        Some(tracing_subscriber::fmt::layer().boxed())
+    };
+    let file = match cli.log_file {
+        Some(ref path) => Some(log_file_layer(log_file_appender(path)?, cli.json_logs)),
+        None => None,
+    };
+    tracing_subscriber::registry()
+        .with(log_filter(cli.verbose_matching))
+        .with(console)
+        .with(file)
         .init();
 
-    // Parse command line arguments
-    let cli = Cli::parse();
+    let load_options = LoadOptions {
+        strict: cli.strict || cli.strict_continue,
+        lenient: cli.lenient,
+    };
+    if let Some(ref dir) = cli.config_dir {
+        let path = config::profile_config_path(dir, cli.profile.as_deref())?;
+        info!("Using profile config '{}'", path.display());
+        cli.config = path.to_string_lossy().into_owned();
+    }
+    let launch_options = LaunchOptions {
+        record: cli.record.take(),
+        record_session: cli.record_session.take(),
+        fail_fast: load_options.strict && !cli.strict_continue,
+        ignore_monitor_mismatch: cli.ignore_monitor_mismatch,
+        pacing: if cli.safe_mode {
+            Pacing::SAFE_MODE
+        } else {
+            Pacing::default()
+        },
+        ..Default::default()
+    };
 
-    // GUI is default, CLI requires explicit --cli flag
-    if cli.cli {
-        run_cli_mode(cli.config)?;
+    // GUI is default; CLI and TUI require an explicit flag
+    if let Some(command) = cli.command {
+        match command {
+            Command::Snap => run_snap(cli.config, &load_options, launch_options)?,
+            Command::Bench { real } => run_bench(cli.config, &load_options, real)?,
+            Command::Capture { output } => run_capture(output)?,
+            Command::Undo => run_undo()?,
+            Command::RenderLayout { file, monitors } => {
+                run_render_layout(cli.config, &load_options, file, monitors.as_deref())?
+            }
+            Command::ExportScript { file } => run_export_script(cli.config, &load_options, file)?,
+            Command::InstallAutostart { delay } => run_install_autostart(cli.config, delay)?,
+            Command::UninstallAutostart => {
+                autostart::uninstall()?;
+                info!("Removed jumpstart from the programs run at login");
+            }
+            Command::Control { command } => run_control(&command.join(" "))?,
+            Command::Replay { file } => run_replay(file)?,
+        }
+    } else if let Some(path) = cli.dump_windows {
+        run_dump_windows(path)?;
+    } else if cli.reposition_only {
+        run_snap(cli.config, &load_options, launch_options)?;
+    } else if cli.dry_run {
+        run_dry_run(
+            cli.config,
+            &load_options,
+            cli.simulate_monitors.as_deref(),
+            cli.windows_from,
+        )?;
+    } else if cli.cli {
+        if let Some(delay) = cli.delay {
+            info!("Waiting {} seconds before launching", delay);
+            std::thread::sleep(std::time::Duration::from_secs(delay));
+        }
+        run_cli_mode(
+            cli.config,
+            &load_options,
+            cli.retry_run,
+            cli.notify,
+            launch_options,
+            cli.watch.then_some(WatchArgs {
+                control: cli.control,
+            }),
+        )?;
+    } else if cli.tui {
+        tui::run_tui(cli.config, load_options)?;
     } else {
-        run_gui_mode(cli.config)?;
+        run_gui_mode(cli.config, load_options)?;
     }
 
     Ok(())
 }
 
-fn run_cli_mode(config_path: String) -> Result<(), Box<dyn std::error::Error>> {
+/// Info-level logging unless `RUST_LOG` says otherwise. `verbose_matching`
+/// adds the enumerated windows on top of whatever the filter allows.
+fn log_filter(verbose_matching: bool) -> tracing_subscriber::EnvFilter {
+    let mut filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    if verbose_matching {
+        let directive = format!("{}=debug", matching::VERBOSE_MATCHING_TARGET);
+        filter = filter.add_directive(directive.parse().expect("valid directive"));
+    }
+    filter
+}
+
+/// Newline-delimited JSON logs; structured fields become JSON keys
+fn json_layer<S, W>(writer: W) -> impl tracing_subscriber::Layer<S> + Send + Sync
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    W: for<'a> tracing_subscriber::fmt::MakeWriter<'a> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_writer(writer)
+}
+
+/// The `--log-file` records, in the console's format without its colors
+fn log_file_layer<S>(
+    appender: tracing_appender::rolling::RollingFileAppender,
+    json: bool,
+) -> Box<dyn tracing_subscriber::Layer<S> + Send + Sync>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    if json {
+        json_layer(appender).boxed()
+    } else {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(appender)
+            .boxed()
+    }
+}
+
+/// How many days of `--log-file` logs are kept
+const LOG_FILE_DAYS: usize = 14;
+
+/// Writes to `path` with the date appended, e.g. `jumpstart.log.2026-10-16`,
+/// starting a new file each day and deleting the oldest beyond `LOG_FILE_DAYS`
+fn log_file_appender(
+    path: &Path,
+) -> Result<tracing_appender::rolling::RollingFileAppender, String> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("Log file '{}' has no file name", path.display()))?;
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    tracing_appender::rolling::RollingFileAppender::builder()
+        .rotation(tracing_appender::rolling::Rotation::DAILY)
+        .filename_prefix(name.to_string_lossy())
+        .max_log_files(LOG_FILE_DAYS)
+        .build(dir)
+        .map_err(|e| format!("Failed to open log file '{}': {}", path.display(), e))
+}
+
+/// How `--watch` runs after the launch
+struct WatchArgs {
+    control: bool,
+}
+
+fn run_cli_mode(
+    config_path: String,
+    load_options: &LoadOptions,
+    retries: u32,
+    notify: bool,
+    options: LaunchOptions,
+    watch: Option<WatchArgs>,
+) -> Result<(), Box<dyn std::error::Error>> {
     info!("Starting application launcher in CLI mode...");
 
     // Load configuration
-    let config = load_config(&config_path)?;
+    let config = load_config(&config_path, load_options)?;
     info!(
         "Loaded configuration from '{}' with {} applications",
         config_path,
         config.applications.len()
     );
 
+    let notify = notify || config.notify_on_complete;
+    let (options, launching) = cancel_on_ctrl_c(start_recording(options)?)?;
+    let launcher = ShellLauncher::for_config(&config);
+
     // Launch and position applications
-    if let Err(e) = launch_and_position_applications(&config) {
-        error!("Failed to launch and position applications: {}", e);
-        return Err(e.into());
+    let report = match launch_with_retries(
+        retries,
+        RETRY_BASE_DELAY,
+        &options,
+        |options| launch_and_position_applications(&config, &launcher, options),
+        std::thread::sleep,
+    ) {
+        Ok(report) => report,
+        Err(e) => {
+            error!("Failed to launch and position applications: {}", e);
+            if notify {
+                show_notification("Jumpstart failed", &e);
+            }
+            return Err(e.into());
+        }
+    };
+    launching.store(false, Ordering::SeqCst);
+    if report.cancelled {
+        error!(
+            "Cancelled after {} of {} applications",
+            report.results.len(),
+            config.applications.len()
+        );
+        std::process::exit(EXIT_CANCELLED);
+    }
+
+    for result in report.results.iter().filter(|r| r.outcome.is_failure()) {
+        match result.found {
+            // Shows which window a failed move was aimed at
+            Some(ref found) => warn!(
+                "{}: {} (window '{}' found via {})",
+                result.name, result.outcome, found.title, found.strategy
+            ),
+            None => warn!("{}: {}", result.name, result.outcome),
+        }
+    }
+    if report.timed_out {
+        warn!(
+            "Stopped at total_timeout_secs before launching {} applications",
+            config.applications.len() - report.results.len()
+        );
+    }
+
+    info!(
+        "Application launcher completed: {} of {} applications positioned",
+        report.positioned_count(),
+        report.results.len()
+    );
+    if notify {
+        let (title, body) = notify::report_summary(&report);
+        show_notification(&title, &body);
+    }
+    if load_options.strict && report.timed_out {
+        return Err("The run stopped at total_timeout_secs".into());
+    }
+    if load_options.strict && !report.is_success() {
+        let failed = report.results.iter().filter(|r| r.outcome.is_failure());
+        return Err(format!("{} applications were not positioned", failed.count()).into());
+    }
+    if let Some(watch_args) = watch {
+        let schedule = ScheduledLayouts::new(&config, &config_path, load_options)?;
+        let control = watch_args
+            .control
+            .then(|| ControlServer::start(&control::default_endpoint(), load_options))
+            .transpose()?;
+        let layout = WatchedLayout {
+            config_path,
+            windows: report.windows,
+            ignore: config.ignore_list(),
+        };
+        watch::watch(layout, schedule, control);
+    } else if !config.schedule.is_empty() {
+        warn!("The config's schedule only applies with --watch");
+    }
+    Ok(())
+}
+
+/// A notification that can't be shown must not fail the run
+fn show_notification(title: &str, body: &str) {
+    if let Err(e) = notify::send(title, body) {
+        warn!("{}", e);
+    }
+}
+
+fn run_dry_run(
+    config_path: String,
+    load_options: &LoadOptions,
+    simulate_monitors: Option<&str>,
+    windows_from: Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let simulated = simulate_monitors
+        .map(display::parse_monitor_spec)
+        .transpose()?;
+    let windows = windows_from.as_deref().map(snapshot::load).transpose()?;
+    let config = load_config(&config_path, load_options)?;
+    info!(
+        "Dry run of '{}'; nothing will be launched or moved",
+        config_path
+    );
+
+    for line in app_launcher::dry_run(&config, simulated, windows.as_deref()) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Launch options that Ctrl+C cancels, so an aborted run puts the windows
+/// it moved back instead of leaving a half-arranged desktop. Once the
+/// returned flag is cleared, or on a second Ctrl+C, it exits right away.
+fn cancel_on_ctrl_c(options: LaunchOptions) -> Result<(LaunchOptions, Arc<AtomicBool>), String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let launching = Arc::new(AtomicBool::new(true));
+    let handler = {
+        let cancel = cancel.clone();
+        let launching = launching.clone();
+        move || {
+            if launching.load(Ordering::SeqCst) && !cancel.swap(true, Ordering::SeqCst) {
+                warn!("Cancelling after the current application; press Ctrl+C again to quit now");
+            } else {
+                std::process::exit(EXIT_CANCELLED);
+            }
+        }
+    };
+    ctrlc::set_handler(handler).map_err(|e| format!("Failed to handle Ctrl+C: {}", e))?;
+    let options = LaunchOptions {
+        cancel: Some(cancel),
+        ..options
+    };
+    Ok((options, launching))
+}
+
+/// Launch options that append window moves to `record` and launches and
+/// moves to `record_session`, each emptied first
+fn start_recording(options: LaunchOptions) -> Result<LaunchOptions, String> {
+    if let Some(ref path) = options.record {
+        record::start(path)?;
+        info!("Recording window moves to '{}'", path.display());
+    }
+    if let Some(ref path) = options.record_session {
+        replay::start(path)?;
+        info!("Recording the session to '{}'", path.display());
+    }
+    Ok(options)
+}
+
+fn run_snap(
+    config_path: String,
+    load_options: &LoadOptions,
+    options: LaunchOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config(&config_path, load_options)?;
+    let options = LaunchOptions {
+        snap_only: true,
+        ..start_recording(options)?
+    };
+    let report = launch_and_position_applications(&config, &ShellLauncher::default(), &options)?;
+
+    for result in report.results.iter().filter(|r| r.outcome.is_failure()) {
+        warn!("{}: {}", result.name, result.outcome);
+    }
+    info!(
+        "Snapped {} of {} applications into place",
+        report.positioned_count(),
+        report.results.len()
+    );
+    Ok(())
+}
+
+fn run_bench(
+    config_path: String,
+    load_options: &LoadOptions,
+    real: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config(&config_path, load_options)?;
+    let mut rows = bench::plan(&config);
+    let total = if real {
+        info!("Launching '{}' to measure it", config_path);
+        Some(bench::measure(&config, &mut rows)?)
+    } else {
+        None
+    };
+
+    for line in bench::format_table(&rows, total) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn run_capture(output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let windows = capture::capture_windows();
+    let yaml = serde_yaml::to_string(&capture::config_from_windows(&windows))?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, yaml)?;
+            info!("Captured {} windows to '{}'", windows.len(), path.display());
+        }
+        None => print!("{}", yaml),
+    }
+    Ok(())
+}
+
+fn run_dump_windows(path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    let windows = snapshot::current_windows()?;
+    snapshot::save(&path, &windows)?;
+    info!("Wrote {} windows to '{}'", windows.len(), path.display());
+    Ok(())
+}
+
+fn run_control(command: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let reply = control::send(&control::default_endpoint(), command)?;
+    if reply.starts_with("error") {
+        return Err(reply.into());
+    }
+    println!("{}", reply);
+    Ok(())
+}
+
+fn run_replay(file: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    let moved = replay::replay_session(&file)?;
+    info!("Replayed '{}', moving {} windows", file.display(), moved);
+    Ok(())
+}
+
+fn run_undo() -> Result<(), Box<dyn std::error::Error>> {
+    let restored = undo::undo_last_launch()?;
+    info!("Restored {} windows to their previous positions", restored);
+    Ok(())
+}
+
+fn run_render_layout(
+    config_path: String,
+    load_options: &LoadOptions,
+    file: PathBuf,
+    monitors: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let monitors = match monitors {
+        Some(spec) => display::parse_monitor_spec(spec)?,
+        None => app_launcher::monitors(),
+    };
+    if monitors.is_empty() {
+        return Err("No monitors found; pass --monitors to draw on simulated ones".into());
     }
+    let config = load_config(&config_path, load_options)?;
+    render::render_layout(&config, &monitors, &file)?;
+    info!(
+        "Drew the layout on {} monitors to '{}'",
+        monitors.len(),
+        file.display()
+    );
+    Ok(())
+}
 
-    info!("Application launcher completed successfully");
+fn run_export_script(
+    config_path: String,
+    load_options: &LoadOptions,
+    file: PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config(&config_path, load_options)?;
+    std::fs::write(&file, export::batch_script(&config, &config_path))?;
+    info!(
+        "Exported launch script for {} applications to '{}'",
+        config.applications.iter().filter(|app| app.enabled).count(),
+        file.display()
+    );
     Ok(())
 }
 
-fn run_gui_mode(config_path: String) -> Result<(), Box<dyn std::error::Error>> {
+fn run_install_autostart(
+    config_path: String,
+    delay: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Login runs in a different working directory, so register absolute paths
+    let config = if remote::is_url(&config_path) {
+        PathBuf::from(config_path)
+    } else {
+        let config = std::path::absolute(&config_path)?;
+        if !config.is_file() {
+            return Err(format!("Config file '{}' not found", config.display()).into());
+        }
+        config
+    };
+    let exe = std::env::current_exe()?;
+
+    let command = autostart::autostart_command(&exe, &config, delay);
+    autostart::install(&command)?;
+    info!("Registered to run at login: {}", command);
+    Ok(())
+}
+
+fn run_gui_mode(
+    config_path: String,
+    load_options: LoadOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
     info!("Starting application launcher in GUI mode...");
 
     // Initialize the GUI with the specified config path
-    let app = gui::JumpstartGui::with_initial_config(config_path);
+    let app = gui::JumpstartGui::with_initial_config(config_path, load_options);
 
-    // Set up the GUI options
+    // Set up the GUI options; the size is only used until eframe has saved
+    // the window's own size and position on exit
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([820.0, 500.0])
@@ -98,9 +738,10 @@ fn run_gui_mode(config_path: String) -> Result<(), Box<dyn std::error::Error>> {
         Box::new(|cc| {
             // Customize egui style here if needed
             cc.egui_ctx.set_visuals(egui::Visuals::dark());
-            Ok(Box::new(app))
+            Ok(Box::new(app.with_saved_state(cc.storage)))
         }),
-    ).map_err(|e| {
+    )
+    .map_err(|e| {
         error!("GUI error: {}", e);
         Box::<dyn std::error::Error>::from(e)
     })?;
@@ -108,3 +749,235 @@ fn run_gui_mode(config_path: String) -> Result<(), Box<dyn std::error::Error>> {
     info!("GUI application closed");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects log output in memory
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for Buffer {
+        type Writer = Buffer;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_json_logs_are_ndjson() {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(log_filter(false))
+            .with(json_layer(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            info!(app = "Editor", "Launching {}...", "Editor");
+            warn!(app = "Chat", suggestions = ?["Chat | General"], "Could not find window");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let records: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["level"], "INFO");
+        assert_eq!(records[0]["app"], "Editor");
+        assert_eq!(records[0]["message"], "Launching Editor...");
+        assert_eq!(records[1]["app"], "Chat");
+    }
+
+    #[test]
+    fn test_log_file() {
+        let dir = std::env::temp_dir().join("jumpstart_log_file_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let appender = log_file_appender(&dir.join("jumpstart.log")).unwrap();
+        let subscriber = tracing_subscriber::registry()
+            .with(log_filter(false))
+            .with(log_file_layer(appender, false));
+        tracing::subscriber::with_default(subscriber, || {
+            info!(app = "Editor", "Launching {}...", "Editor");
+        });
+
+        // One file for today, named after the path, with the structured fields
+        let files: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 1);
+        let name = files[0].file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("jumpstart.log."), "{}", name);
+        let content = std::fs::read_to_string(&files[0]).unwrap();
+        assert!(content.contains("Launching Editor..."), "{}", content);
+        assert!(content.contains("app=\"Editor\""), "{}", content);
+        assert!(!content.contains('\u{1b}'), "no color codes: {}", content);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_modes_are_mutually_exclusive() {
+        assert!(Cli::try_parse_from(["jumpstart", "--cli", "--tui"]).is_err());
+        assert!(Cli::try_parse_from(["jumpstart", "--gui", "--cli"]).is_err());
+        assert!(Cli::try_parse_from(["jumpstart", "--gui", "--tui"]).is_err());
+
+        let cli = Cli::try_parse_from(["jumpstart", "--tui", "-f", "desk.yml"]).unwrap();
+        assert!(cli.tui && !cli.cli && !cli.gui);
+        assert_eq!(cli.config, "desk.yml");
+    }
+
+    #[test]
+    fn test_undo_subcommand() {
+        let cli = Cli::try_parse_from(["jumpstart", "undo"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Undo)));
+        let cli = Cli::try_parse_from(["jumpstart"]).unwrap();
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn test_snap_subcommand() {
+        let cli = Cli::try_parse_from(["jumpstart", "-f", "desk.yml", "snap"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Snap)));
+    }
+
+    #[test]
+    fn test_reposition_only_flag() {
+        let cli = Cli::try_parse_from(["jumpstart", "--reposition-only"]).unwrap();
+        assert!(cli.reposition_only);
+        assert!(Cli::try_parse_from(["jumpstart", "--reposition-only", "--tui"]).is_err());
+    }
+
+    #[test]
+    fn test_export_script_subcommand() {
+        let cli = Cli::try_parse_from(["jumpstart", "-f", "desk.yml", "export-script", "desk.bat"])
+            .unwrap();
+        assert!(
+            matches!(cli.command, Some(Command::ExportScript { file }) if file.as_os_str() == "desk.bat")
+        );
+    }
+
+    #[test]
+    fn test_render_layout_subcommand() {
+        let cli = Cli::try_parse_from([
+            "jumpstart",
+            "render-layout",
+            "desk.png",
+            "--monitors",
+            "1920x1080@0,0",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::RenderLayout { file, monitors: Some(spec) })
+                if file.as_os_str() == "desk.png" && spec == "1920x1080@0,0"
+        ));
+    }
+
+    #[test]
+    fn test_install_autostart_delay() {
+        let cli = Cli::try_parse_from(["jumpstart", "install-autostart", "--delay", "20"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::InstallAutostart { delay: 20 })
+        ));
+        assert!(Cli::try_parse_from(["jumpstart", "--delay", "5"]).is_err());
+        assert!(Cli::try_parse_from(["jumpstart", "--cli", "--delay", "5"]).is_ok());
+    }
+
+    #[test]
+    fn test_retry_run_flag() {
+        let cli = Cli::try_parse_from(["jumpstart", "--cli", "--retry-run", "3"]).unwrap();
+        assert_eq!(cli.retry_run, 3);
+        assert!(Cli::try_parse_from(["jumpstart", "--retry-run", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_notify_flag() {
+        assert!(
+            Cli::try_parse_from(["jumpstart", "--cli", "--notify"])
+                .unwrap()
+                .notify
+        );
+        assert!(Cli::try_parse_from(["jumpstart", "--notify"]).is_err());
+    }
+
+    #[test]
+    fn test_record_flag() {
+        let cli = Cli::try_parse_from(["jumpstart", "--cli", "--record", "moves.jsonl"]).unwrap();
+        assert_eq!(cli.record, Some(PathBuf::from("moves.jsonl")));
+        assert!(Cli::try_parse_from(["jumpstart", "--tui", "--record", "moves.jsonl"]).is_err());
+    }
+
+    #[test]
+    fn test_record_session_and_replay() {
+        let cli =
+            Cli::try_parse_from(["jumpstart", "--cli", "--record-session", "demo.jsonl"]).unwrap();
+        assert_eq!(cli.record_session, Some(PathBuf::from("demo.jsonl")));
+        let cli = Cli::try_parse_from(["jumpstart", "replay", "demo.jsonl"]).unwrap();
+        assert!(
+            matches!(cli.command, Some(Command::Replay { file }) if file.as_os_str() == "demo.jsonl")
+        );
+    }
+
+    #[test]
+    fn test_simulate_monitors_flag() {
+        let cli = Cli::try_parse_from([
+            "jumpstart",
+            "--dry-run",
+            "--simulate-monitors",
+            "1920x1080@0,0;2560x1440@1920,0",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.simulate_monitors.as_deref(),
+            Some("1920x1080@0,0;2560x1440@1920,0")
+        );
+        assert!(
+            Cli::try_parse_from(["jumpstart", "--simulate-monitors", "1920x1080@0,0"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_control_flag_and_subcommand() {
+        assert!(Cli::try_parse_from(["jumpstart", "--cli", "--control"]).is_err());
+        assert!(Cli::try_parse_from(["jumpstart", "--cli", "--watch", "--control"]).is_ok());
+        let cli = Cli::try_parse_from(["jumpstart", "control", "launch", "evening"]).unwrap();
+        assert!(
+            matches!(cli.command, Some(Command::Control { ref command }) if command == &["launch", "evening"])
+        );
+    }
+
+    #[test]
+    fn test_windows_from_flag() {
+        let cli = Cli::try_parse_from(["jumpstart", "--dry-run", "--windows-from", "desk.jsonl"])
+            .unwrap();
+        assert_eq!(cli.windows_from, Some(PathBuf::from("desk.jsonl")));
+        assert!(Cli::try_parse_from(["jumpstart", "--windows-from", "desk.jsonl"]).is_err());
+        assert!(
+            Cli::try_parse_from(["jumpstart", "--cli", "--dump-windows", "desk.jsonl"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_verbose_matching_flag() {
+        assert!(
+            Cli::try_parse_from(["jumpstart", "snap", "--verbose-matching"])
+                .unwrap()
+                .verbose_matching
+        );
+    }
+}
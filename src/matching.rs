@@ -0,0 +1,803 @@
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+/// A top-level window seen during enumeration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowCandidate {
+    /// Raw window handle
+    pub hwnd: usize,
+    pub title: String,
+    /// Window class name, e.g. `Chrome_WidgetWin_1`
+    #[serde(default)]
+    pub class: String,
+    /// Hidden by the window manager despite being visible, e.g. a suspended
+    /// Store app's window
+    #[serde(default)]
+    pub cloaked: bool,
+    pub process_id: u32,
+}
+
+/// Tracing target of the per-window lines `--verbose-matching` enables, so
+/// they can also be turned on through `RUST_LOG`
+pub const VERBOSE_MATCHING_TARGET: &str = "jumpstart::verbose_matching";
+
+/// One enumerated window for `--verbose-matching`. Titles of windows that
+/// aren't on screen, being hidden or cloaked, are left out, since they may
+/// hold text the user never sees.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn describe_candidate(candidate: &WindowCandidate, visible: bool) -> String {
+    let title = if visible && !candidate.cloaked {
+        format!("'{}'", candidate.title)
+    } else {
+        "(title hidden)".to_string()
+    };
+    format!(
+        "{} class '{}' process {} visible {} cloaked {}",
+        title, candidate.class, candidate.process_id, visible, candidate.cloaked
+    )
+}
+
+/// Puts text in a form where equivalent spellings compare equal: NFKC
+/// normalization, so composed and decomposed accents (or fullwidth and
+/// ASCII letters) match, and case folding approximated by upper- then
+/// lowercasing, which also folds e.g. "ß" and "SS"
+pub fn fold(text: &str) -> String {
+    text.to_uppercase().to_lowercase().nfkc().collect()
+}
+
+/// Case-insensitive partial match used to locate an application's window
+pub fn title_matches(title: &str, search: &str) -> bool {
+    fold(title).contains(&fold(search))
+}
+
+/// What an app's window must look like. Every part that is set must hold:
+/// `title` is contained in the window's title and `class` is its whole class
+/// name, both ignoring case; every criterion in `all` holds, and at least
+/// one in `any` does.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MatchCriteria {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub all: Vec<MatchCriteria>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub any: Vec<MatchCriteria>,
+}
+
+impl MatchCriteria {
+    /// Windows whose title contains `title`
+    pub fn title(title: &str) -> Self {
+        MatchCriteria {
+            title: Some(title.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// A title the matching window should contain, to suggest similar
+    /// titles from when nothing matches
+    pub fn title_hint(&self) -> Option<&str> {
+        self.title.as_deref().or_else(|| {
+            self.all
+                .iter()
+                .chain(&self.any)
+                .find_map(MatchCriteria::title_hint)
+        })
+    }
+
+    /// Checks that every criterion tests something, and that `all` and
+    /// `any` are nested at most one level deep
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_depth(0)
+    }
+
+    fn validate_depth(&self, depth: usize) -> Result<(), String> {
+        if self.title.is_none()
+            && self.class.is_none()
+            && self.all.is_empty()
+            && self.any.is_empty()
+        {
+            return Err("a match criterion needs a title, class, all or any".to_string());
+        }
+        // The top level's `all` and `any` may hold one more level of them
+        if depth == 2 && !(self.all.is_empty() && self.any.is_empty()) {
+            return Err("all and any can only be nested one level deep".to_string());
+        }
+        self.all
+            .iter()
+            .chain(&self.any)
+            .try_for_each(|criteria| criteria.validate_depth(depth + 1))
+    }
+}
+
+impl MatchCriteria {
+    /// How many parts must hold, counting `any` as one
+    fn part_count(&self) -> usize {
+        usize::from(self.title.is_some())
+            + usize::from(self.class.is_some())
+            + self.all.len()
+            + usize::from(!self.any.is_empty())
+    }
+
+    /// As shown inside another criterion, in parentheses when it has parts
+    fn nested(&self) -> String {
+        if self.part_count() > 1 || self.any.len() > 1 {
+            format!("({})", self)
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+/// e.g. `Gmail and class Chrome_WidgetWin_1`; a plain title search shows as
+/// just the title
+impl std::fmt::Display for MatchCriteria {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts: Vec<String> = Vec::new();
+        parts.extend(self.title.clone());
+        parts.extend(self.class.as_ref().map(|class| format!("class {}", class)));
+        parts.extend(self.all.iter().map(MatchCriteria::nested));
+        if !self.any.is_empty() {
+            let any: Vec<String> = self.any.iter().map(MatchCriteria::nested).collect();
+            let any = any.join(" or ");
+            parts.push(if self.part_count() > 1 && self.any.len() > 1 {
+                format!("({})", any)
+            } else {
+                any
+            });
+        }
+        write!(f, "{}", parts.join(" and "))
+    }
+}
+
+/// Whether the window meets every part of `criteria`
+pub fn window_matches(candidate: &WindowCandidate, criteria: &MatchCriteria) -> bool {
+    criteria
+        .title
+        .as_ref()
+        .is_none_or(|title| title_matches(&candidate.title, title))
+        && criteria
+            .class
+            .as_ref()
+            .is_none_or(|class| class.eq_ignore_ascii_case(&candidate.class))
+        && criteria
+            .all
+            .iter()
+            .all(|criteria| window_matches(candidate, criteria))
+        && (criteria.any.is_empty()
+            || criteria
+                .any
+                .iter()
+                .any(|criteria| window_matches(candidate, criteria)))
+}
+
+/// Which part of an app's criteria picked its window
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum MatchStrategy {
+    /// A title containing the text
+    Title(String),
+    /// The whole class name
+    Class(String),
+    /// Several parts together, e.g. a title and a class
+    Criteria(String),
+    /// Owned by the launched process or one it started, whatever the title
+    ProcessTree(u32),
+}
+
+impl MatchStrategy {
+    /// The narrowest part of `criteria` that `candidate` matched: a lone
+    /// title or class, or the alternative in a lone `any` that held
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn of(candidate: &WindowCandidate, criteria: &MatchCriteria) -> Self {
+        let single = match (&criteria.all[..], &criteria.any[..]) {
+            ([], []) => None,
+            ([only], []) => Some(only),
+            ([], any) => any
+                .iter()
+                .find(|criteria| window_matches(candidate, criteria)),
+            _ => return MatchStrategy::Criteria(criteria.to_string()),
+        };
+        match (&criteria.title, &criteria.class, single) {
+            (Some(title), None, None) => MatchStrategy::Title(title.clone()),
+            (None, Some(class), None) => MatchStrategy::Class(class.clone()),
+            (None, None, Some(single)) => MatchStrategy::of(candidate, single),
+            _ => MatchStrategy::Criteria(criteria.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for MatchStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchStrategy::Title(title) => write!(f, "title '{}'", title),
+            MatchStrategy::Class(class) => write!(f, "class '{}'", class),
+            MatchStrategy::Criteria(criteria) => write!(f, "match '{}'", criteria),
+            MatchStrategy::ProcessTree(root) => write!(f, "process tree of {}", root),
+        }
+    }
+}
+
+/// A window found for an app, with its title and how it was matched
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoundWindow {
+    pub hwnd: usize,
+    pub title: String,
+    pub strategy: MatchStrategy,
+}
+
+impl FoundWindow {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn new(candidate: &WindowCandidate, criteria: &MatchCriteria) -> Self {
+        FoundWindow {
+            hwnd: candidate.hwnd,
+            title: candidate.title.clone(),
+            strategy: MatchStrategy::of(candidate, criteria),
+        }
+    }
+}
+
+/// Window classes of the Windows shell: the desktop and the taskbars
+pub const SHELL_CLASSES: &[&str] = &[
+    "Progman",
+    "WorkerW",
+    "Shell_TrayWnd",
+    "Shell_SecondaryTrayWnd",
+];
+
+/// Titles of the Windows shell's windows
+pub const SHELL_TITLES: &[&str] = &["Program Manager"];
+
+/// Windows that are never matched to an app, whatever their title contains.
+/// The default is the Windows shell's windows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoreList {
+    /// Whole titles, compared ignoring case
+    pub titles: Vec<String>,
+    /// Window class names, compared ignoring case
+    pub classes: Vec<String>,
+}
+
+impl Default for IgnoreList {
+    fn default() -> Self {
+        IgnoreList {
+            titles: SHELL_TITLES.iter().map(|title| title.to_string()).collect(),
+            classes: SHELL_CLASSES
+                .iter()
+                .map(|class| class.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl IgnoreList {
+    /// The defaults plus `titles` and `classes`
+    pub fn extended(titles: &[String], classes: &[String]) -> Self {
+        let mut list = IgnoreList::default();
+        list.titles.extend_from_slice(titles);
+        list.classes.extend_from_slice(classes);
+        list
+    }
+
+    pub fn ignores(&self, candidate: &WindowCandidate) -> bool {
+        let title = fold(&candidate.title);
+        self.titles.iter().any(|ignored| fold(ignored) == title)
+            || self
+                .classes
+                .iter()
+                .any(|ignored| ignored.eq_ignore_ascii_case(&candidate.class))
+    }
+}
+
+/// The first candidate that matches `search`
+pub fn find_candidate<'a>(
+    candidates: &'a [WindowCandidate],
+    search: &MatchCriteria,
+    ignore: &IgnoreList,
+) -> Option<&'a WindowCandidate> {
+    find_ready_candidate(candidates, search, None, ignore)
+}
+
+/// The first candidate that matches `search` and, when given, whose title
+/// also contains `ready`, so an app's loading window is passed over until its
+/// title changes. Candidates on the `ignore` list are passed over too.
+pub fn find_ready_candidate<'a>(
+    candidates: &'a [WindowCandidate],
+    search: &MatchCriteria,
+    ready: Option<&str>,
+    ignore: &IgnoreList,
+) -> Option<&'a WindowCandidate> {
+    ready_candidates(candidates, search, ready, ignore).next()
+}
+
+/// Every candidate `find_ready_candidate` could pick, in order
+pub fn ready_candidates<'a>(
+    candidates: &'a [WindowCandidate],
+    search: &MatchCriteria,
+    ready: Option<&str>,
+    ignore: &IgnoreList,
+) -> impl Iterator<Item = &'a WindowCandidate> {
+    candidates.iter().filter(move |candidate| {
+        window_matches(candidate, search)
+            && ready.is_none_or(|ready| title_matches(&candidate.title, ready))
+            && !ignore.ignores(candidate)
+    })
+}
+
+/// The matching candidate with the largest `area`, e.g. an app's main window
+/// rather than a small popup with the same title. Ties go to the first match.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn find_largest_candidate<'a>(
+    candidates: &'a [WindowCandidate],
+    search: &MatchCriteria,
+    ignore: &IgnoreList,
+    area: impl Fn(&WindowCandidate) -> i64,
+) -> Option<&'a WindowCandidate> {
+    candidates
+        .iter()
+        .filter(|candidate| window_matches(candidate, search) && !ignore.ignores(candidate))
+        .map(|candidate| (area(candidate), candidate))
+        .reduce(|best, next| if next.0 > best.0 { next } else { best })
+        .map(|(_, candidate)| candidate)
+}
+
+/// `root` and its descendants among `processes`, given as (process ID,
+/// parent ID) pairs, nearest first: the root, then its children, then theirs
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn process_tree(root: u32, processes: &[(u32, u32)]) -> Vec<u32> {
+    let mut tree = vec![root];
+    let mut next = 0;
+    while let Some(&parent) = tree.get(next) {
+        for &(process, process_parent) in processes {
+            // A reused parent ID can make a cycle, so each process joins once
+            if process_parent == parent && !tree.contains(&process) {
+                tree.push(process);
+            }
+        }
+        next += 1;
+    }
+    tree
+}
+
+/// The first candidate owned by a process in `tree`, preferring processes
+/// earlier in it, so a launcher's own window wins over its helper's
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn find_in_process_tree<'a>(
+    candidates: &'a [WindowCandidate],
+    tree: &[u32],
+    ignore: &IgnoreList,
+) -> Option<&'a WindowCandidate> {
+    tree.iter().find_map(|&process| {
+        candidates
+            .iter()
+            .find(|candidate| candidate.process_id == process && !ignore.ignores(candidate))
+    })
+}
+
+fn words(text: &str) -> Vec<String> {
+    // Fold first, so decomposed accents are composed before splitting on them
+    fold(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// How closely a window title resembles a search string: two points per
+/// shared word, one per word that is a prefix of the other (e.g. "team" and
+/// "teams"). Zero means nothing in common.
+pub fn similarity(title: &str, search: &str) -> usize {
+    let title_words = words(title);
+    words(search)
+        .iter()
+        .map(|wanted| {
+            if title_words.iter().any(|word| word == wanted) {
+                2
+            } else if title_words
+                .iter()
+                .any(|word| word.starts_with(wanted.as_str()) || wanted.starts_with(word.as_str()))
+            {
+                1
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+/// The titles most similar to `search`, best first, for "could not find window" hints
+pub fn suggest_titles(search: &str, candidates: &[WindowCandidate], limit: usize) -> Vec<String> {
+    let mut scored: Vec<(usize, &str)> = candidates
+        .iter()
+        .map(|candidate| {
+            (
+                similarity(&candidate.title, search),
+                candidate.title.as_str(),
+            )
+        })
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    // Best score first; shorter titles are usually the more specific match
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.len().cmp(&b.1.len())));
+
+    let mut suggestions: Vec<String> = Vec::new();
+    for (_, title) in scored {
+        if suggestions.len() == limit {
+            break;
+        }
+        if !suggestions.iter().any(|existing| existing == title) {
+            suggestions.push(title.to_string());
+        }
+    }
+    suggestions
+}
+
+/// Renders suggestions for inclusion in a log or error message
+pub fn format_suggestions(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        "no similar windows are open".to_string()
+    } else {
+        let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{}'", s)).collect();
+        format!("similar windows: {}", quoted.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(title: &str) -> WindowCandidate {
+        WindowCandidate {
+            hwnd: 0,
+            title: title.to_string(),
+            class: String::new(),
+            cloaked: false,
+            process_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_find_candidate() {
+        let candidates = vec![candidate("Inbox - Outlook"), candidate("General | Slack")];
+        assert_eq!(
+            find_candidate(
+                &candidates,
+                &MatchCriteria::title("slack"),
+                &IgnoreList::default()
+            )
+            .map(|c| c.title.as_str()),
+            Some("General | Slack")
+        );
+        assert!(
+            find_candidate(
+                &candidates,
+                &MatchCriteria::title("teams"),
+                &IgnoreList::default()
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_find_in_process_tree() {
+        // The launcher (10) started an updater (11), which started the app (12)
+        let processes = [(10, 1), (11, 10), (12, 11), (13, 1), (14, 12), (1, 0)];
+        assert_eq!(process_tree(10, &processes), vec![10, 11, 12, 14]);
+        assert_eq!(process_tree(99, &processes), vec![99]);
+        assert_eq!(process_tree(10, &[(10, 11), (11, 10)]), vec![10, 11]);
+
+        let owned = |title: &str, process_id| WindowCandidate {
+            process_id,
+            ..candidate(title)
+        };
+        let candidates = vec![
+            owned("Other App", 13),
+            owned("Program Manager", 11),
+            owned("Chat | Workspace", 12),
+            owned("Chat Notifications", 14),
+        ];
+        let tree = process_tree(10, &processes);
+        assert_eq!(
+            find_in_process_tree(&candidates, &tree, &IgnoreList::default())
+                .map(|c| c.title.as_str()),
+            Some("Chat | Workspace")
+        );
+        assert!(
+            find_in_process_tree(
+                &candidates,
+                &[13],
+                &IgnoreList::extended(&["other app".to_string()], &[])
+            )
+            .is_none()
+        );
+        assert_eq!(
+            MatchStrategy::ProcessTree(10).to_string(),
+            "process tree of 10"
+        );
+    }
+
+    #[test]
+    fn test_describe_candidate() {
+        let chat = WindowCandidate {
+            class: "Chrome_WidgetWin_1".to_string(),
+            process_id: 4242,
+            ..candidate("General | Slack")
+        };
+        assert_eq!(
+            describe_candidate(&chat, true),
+            "'General | Slack' class 'Chrome_WidgetWin_1' process 4242 visible true cloaked false"
+        );
+        // Only titles that are on screen are logged
+        assert_eq!(
+            describe_candidate(&chat, false),
+            "(title hidden) class 'Chrome_WidgetWin_1' process 4242 visible false cloaked false"
+        );
+        let suspended = WindowCandidate {
+            cloaked: true,
+            ..chat
+        };
+        assert!(describe_candidate(&suspended, true).starts_with("(title hidden)"));
+    }
+
+    #[test]
+    fn test_match_strategy() {
+        let slack = WindowCandidate {
+            class: "Chrome_WidgetWin_1".to_string(),
+            ..candidate("Slack - General")
+        };
+        let class = |class: &str| MatchCriteria {
+            class: Some(class.to_string()),
+            ..Default::default()
+        };
+        let strategy = |criteria: &MatchCriteria| MatchStrategy::of(&slack, criteria).to_string();
+
+        assert_eq!(strategy(&MatchCriteria::title("slack")), "title 'slack'");
+        assert_eq!(
+            strategy(&class("Chrome_WidgetWin_1")),
+            "class 'Chrome_WidgetWin_1'"
+        );
+        let any = MatchCriteria {
+            any: vec![MatchCriteria::title("discord"), class("Chrome_WidgetWin_1")],
+            ..Default::default()
+        };
+        assert_eq!(strategy(&any), "class 'Chrome_WidgetWin_1'");
+        let both = MatchCriteria {
+            class: Some("Chrome_WidgetWin_1".to_string()),
+            ..MatchCriteria::title("slack")
+        };
+        assert_eq!(
+            strategy(&both),
+            "match 'slack and class Chrome_WidgetWin_1'"
+        );
+
+        let found = FoundWindow::new(&slack, &any);
+        assert_eq!(found.title, "Slack - General");
+    }
+
+    #[test]
+    fn test_window_matches() {
+        let gmail = WindowCandidate {
+            class: "Chrome_WidgetWin_1".to_string(),
+            ..candidate("Inbox - Gmail - Google Chrome")
+        };
+        let chrome = WindowCandidate {
+            class: "Chrome_WidgetWin_1".to_string(),
+            ..candidate("New Tab - Google Chrome")
+        };
+        let notes = WindowCandidate {
+            class: "Notepad".to_string(),
+            ..candidate("Gmail password.txt - Notepad")
+        };
+        let class = |class: &str| MatchCriteria {
+            class: Some(class.to_string()),
+            ..Default::default()
+        };
+        let matching = |criteria: &MatchCriteria| {
+            [&gmail, &chrome, &notes]
+                .into_iter()
+                .filter(|window| window_matches(window, criteria))
+                .map(|window| window.title.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        let all = MatchCriteria {
+            all: vec![MatchCriteria::title("gmail"), class("chrome_widgetwin_1")],
+            ..Default::default()
+        };
+        assert_eq!(matching(&all), ["Inbox - Gmail - Google Chrome"]);
+        assert_eq!(all.to_string(), "gmail and class chrome_widgetwin_1");
+
+        let any = MatchCriteria {
+            any: vec![MatchCriteria::title("new tab"), class("Notepad")],
+            ..Default::default()
+        };
+        assert_eq!(
+            matching(&any),
+            ["New Tab - Google Chrome", "Gmail password.txt - Notepad"]
+        );
+        assert_eq!(any.to_string(), "new tab or class Notepad");
+
+        // One level of nesting, next to a part of its own
+        let nested = MatchCriteria {
+            title: Some("gmail".to_string()),
+            any: vec![
+                class("Notepad"),
+                MatchCriteria {
+                    all: vec![class("Chrome_WidgetWin_1"), MatchCriteria::title("inbox")],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            matching(&nested),
+            [
+                "Inbox - Gmail - Google Chrome",
+                "Gmail password.txt - Notepad"
+            ]
+        );
+        assert_eq!(
+            nested.to_string(),
+            "gmail and (class Notepad or (class Chrome_WidgetWin_1 and inbox))"
+        );
+        assert_eq!(nested.title_hint(), Some("gmail"));
+        assert!(nested.validate().is_ok());
+
+        let too_deep = MatchCriteria {
+            all: vec![nested],
+            ..Default::default()
+        };
+        assert!(too_deep.validate().unwrap_err().contains("one level deep"));
+        assert!(MatchCriteria::default().validate().is_err());
+    }
+
+    #[test]
+    fn test_ignore_list() {
+        let candidates = vec![
+            WindowCandidate {
+                class: "Progman".to_string(),
+                ..candidate("Program Manager")
+            },
+            WindowCandidate {
+                class: "Shell_TrayWnd".to_string(),
+                ..candidate("Taskbar")
+            },
+            WindowCandidate {
+                class: "CabinetWClass".to_string(),
+                ..candidate("Downloads - File Explorer")
+            },
+        ];
+        let find = |search, ignore: &IgnoreList| {
+            find_candidate(&candidates, &MatchCriteria::title(search), ignore)
+                .map(|c| c.title.as_str())
+        };
+
+        // The shell's windows are skipped by default, found when nothing is ignored
+        let nothing = IgnoreList {
+            titles: Vec::new(),
+            classes: Vec::new(),
+        };
+        assert_eq!(find("program manager", &nothing), Some("Program Manager"));
+        assert_eq!(find("program manager", &IgnoreList::default()), None);
+        assert_eq!(find("taskbar", &IgnoreList::default()), None);
+        assert_eq!(
+            find("explorer", &IgnoreList::default()),
+            Some("Downloads - File Explorer")
+        );
+
+        // Extra titles must match the whole title; classes ignore case
+        let by_title = IgnoreList::extended(&["file explorer".to_string()], &[]);
+        assert_eq!(
+            find("explorer", &by_title),
+            Some("Downloads - File Explorer")
+        );
+        let by_class = IgnoreList::extended(&[], &["cabinetwclass".to_string()]);
+        assert_eq!(find("explorer", &by_class), None);
+    }
+
+    #[test]
+    fn test_find_ready_candidate() {
+        let mut candidates = vec![candidate("Slack - Loading...")];
+        let ignore = IgnoreList::default();
+        let slack = MatchCriteria::title("slack");
+        assert!(find_ready_candidate(&candidates, &slack, Some("| general"), &ignore).is_none());
+        assert!(find_ready_candidate(&candidates, &slack, None, &ignore).is_some());
+
+        // The same window once the app has finished loading
+        candidates[0].title = "Slack | General | Acme".to_string();
+        assert_eq!(
+            find_ready_candidate(&candidates, &slack, Some("| general"), &ignore)
+                .map(|c| c.title.as_str()),
+            Some("Slack | General | Acme")
+        );
+    }
+
+    #[test]
+    fn test_find_largest_candidate() {
+        let candidates = vec![
+            WindowCandidate {
+                hwnd: 1,
+                ..candidate("Slack | Huddle")
+            },
+            WindowCandidate {
+                hwnd: 2,
+                ..candidate("General | Slack")
+            },
+            WindowCandidate {
+                hwnd: 3,
+                ..candidate("Slack | Thread")
+            },
+            WindowCandidate {
+                hwnd: 4,
+                ..candidate("Inbox - Outlook")
+            },
+        ];
+        let areas = [0, 300 * 200, 1600 * 900, 1600 * 900, 2560 * 1440];
+        let largest = |search| {
+            find_largest_candidate(
+                &candidates,
+                &MatchCriteria::title(search),
+                &IgnoreList::default(),
+                |c| areas[c.hwnd],
+            )
+            .map(|c| c.hwnd)
+        };
+
+        // The main window rather than the huddle popup; the first of equal sizes
+        assert_eq!(largest("slack"), Some(2));
+        assert_eq!(largest("outlook"), Some(4));
+        assert_eq!(largest("teams"), None);
+    }
+
+    #[test]
+    fn test_title_matches_unicode() {
+        // Composed "é" (U+00E9) against "e" + combining acute accent (U+0301)
+        let composed = "Caf\u{e9} Menu - Notes";
+        let decomposed = "cafe\u{301} menu";
+        assert!(title_matches(composed, decomposed));
+        assert!(title_matches(decomposed, "CAF\u{c9}"));
+        assert_eq!(similarity(composed, decomposed), 4);
+
+        assert!(title_matches("🎵 Lo-fi Beats – Spotify", "spotify"));
+        assert!(title_matches("🎵 Lo-fi Beats – Spotify", "🎵 lo-fi"));
+        assert!(title_matches("Straße.txt - Editor", "STRASSE"));
+        // Fullwidth letters, common in CJK titles, match their ASCII forms
+        assert!(title_matches("会議 | Ｔｅａｍｓ", "teams"));
+        assert!(!title_matches("会議 | Teams", "会話"));
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("Microsoft Teams", "teams"), 2);
+        assert_eq!(similarity("Microsoft Teams (work)", "team"), 1);
+        assert_eq!(similarity("Inbox - Outlook", "slack"), 0);
+    }
+
+    #[test]
+    fn test_suggest_titles() {
+        let candidates = vec![
+            candidate("Visual Studio Code - main.rs"),
+            candidate("Calendar | Microsoft Teams"),
+            candidate("Chat | Microsoft Teams"),
+            candidate("Chat | Microsoft Teams"),
+            candidate("Microsoft Store"),
+            candidate("Slack"),
+        ];
+        assert_eq!(
+            suggest_titles("Microsoft Teams classic", &candidates, 3),
+            vec![
+                "Chat | Microsoft Teams",
+                "Calendar | Microsoft Teams",
+                "Microsoft Store"
+            ]
+        );
+        assert!(suggest_titles("Notion", &candidates, 3).is_empty());
+
+        assert_eq!(
+            format_suggestions(&["Slack".to_string(), "Slack | general".to_string()]),
+            "similar windows: 'Slack', 'Slack | general'"
+        );
+        assert_eq!(format_suggestions(&[]), "no similar windows are open");
+    }
+}
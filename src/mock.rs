@@ -1,28 +1,20 @@
+use crate::app_launcher::{LaunchHandle, Launcher};
+use crate::config::Application;
+use crate::desktop::Desktop;
+use crate::display::{Monitor, Rect, ShowState, test_monitor};
+use crate::identity::WindowIdentity;
+use crate::matching::{FoundWindow, IgnoreList, MatchCriteria, WindowCandidate};
+use crate::prefetch::Prefetch;
+use crate::undo::UndoSnapshot;
 #[cfg(test)]
 use mockall::{mock, predicate::*};
+use std::cell::RefCell;
 use std::collections::HashMap;
-
-// Mock structures for testing
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-pub struct MockMonitorInfo {
-    pub handle: usize,
-    pub rect: MockRect,
-    pub work_area: MockRect,
-    pub device_name: String,
-}
-
-#[derive(Debug, Clone)]
-pub struct MockRect {
-    pub left: i32,
-    pub top: i32,
-    pub right: i32,
-    pub bottom: i32,
-}
+use std::time::Duration;
 
 // Trait for Windows API operations
 pub trait WindowsApiTrait {
-    fn get_monitors(&self) -> Vec<MockMonitorInfo>;
+    fn get_monitors(&self) -> Vec<Monitor>;
     fn find_window_by_title(&self, partial_title: &str) -> Option<usize>;
     fn position_window(
         &self,
@@ -41,7 +33,7 @@ mock! {
     pub WindowsApi {}
 
     impl WindowsApiTrait for WindowsApi {
-        fn get_monitors(&self) -> Vec<MockMonitorInfo>;
+        fn get_monitors(&self) -> Vec<Monitor>;
         fn find_window_by_title(&self, partial_title: &str) -> Option<usize>;
         fn position_window(&self, hwnd: usize, x: i32, y: i32, width: i32, height: i32) -> Result<(), String>;
         fn launch_application(&self, executable: &str) -> Result<(), String>;
@@ -49,49 +41,254 @@ mock! {
 }
 
 #[cfg(test)]
-pub fn create_mock_monitors() -> Vec<MockMonitorInfo> {
+pub fn create_mock_monitors() -> Vec<Monitor> {
     vec![
-        MockMonitorInfo {
-            handle: 1,
-            rect: MockRect {
-                left: 0,
-                top: 0,
-                right: 1920,
-                bottom: 1080,
-            },
-            work_area: MockRect {
-                left: 0,
-                top: 0,
-                right: 1920,
-                bottom: 1040,
-            },
-            device_name: "Monitor1".to_string(),
+        test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080)),
+        test_monitor(1, "Monitor2", Rect::new(1920, 0, 1920, 1080)),
+    ]
+}
+
+/// A laptop with an external monitor to its right, 200 pixels apart; the
+/// external one is the primary monitor
+#[cfg(test)]
+pub fn mock_monitors_with_gap() -> Vec<Monitor> {
+    vec![
+        Monitor {
+            primary: false,
+            ..test_monitor(0, "Laptop", Rect::new(0, 0, 1920, 1080))
         },
-        MockMonitorInfo {
-            handle: 2,
-            rect: MockRect {
-                left: 1920,
-                top: 0,
-                right: 3840,
-                bottom: 1080,
-            },
-            work_area: MockRect {
-                left: 1920,
-                top: 0,
-                right: 3840,
-                bottom: 1040,
-            },
-            device_name: "Monitor2".to_string(),
+        Monitor {
+            primary: true,
+            ..test_monitor(1, "External", Rect::new(2120, 0, 2560, 1440))
         },
     ]
 }
 
+/// A primary laptop with a larger monitor stacked above it
 #[cfg(test)]
-pub fn create_mock_window_map() -> HashMap<String, String> {
-    let mut window_map = HashMap::new();
-    window_map.insert("Teams".to_string(), "teams".to_string());
-    window_map.insert("Outlook".to_string(), "outlook".to_string());
-    window_map.insert("Slack".to_string(), "slack".to_string());
-    window_map.insert("Notion".to_string(), "notion".to_string());
-    window_map
+pub fn mock_monitors_vertical() -> Vec<Monitor> {
+    vec![
+        test_monitor(0, "Laptop", Rect::new(0, 0, 1920, 1080)),
+        test_monitor(1, "Above", Rect::new(-320, -1440, 2560, 1440)),
+    ]
+}
+
+/// Launches apps and finds and moves their windows through a
+/// `WindowsApiTrait`, so a launch run can be checked against its mock.
+/// Windows are found by the title their app searches for, and remember
+/// where they were last moved.
+pub struct MockDesktop<'a> {
+    api: &'a dyn WindowsApiTrait,
+    rects: RefCell<HashMap<usize, Rect>>,
+    maximized: RefCell<HashMap<usize, bool>>,
+    maximizes_itself: bool,
+    /// Whether each move and each restore from maximized activated the window
+    moves: RefCell<Vec<bool>>,
+    restores: RefCell<Vec<bool>>,
+}
+
+impl<'a> MockDesktop<'a> {
+    pub fn new(api: &'a dyn WindowsApiTrait) -> Self {
+        MockDesktop {
+            api,
+            rects: RefCell::new(HashMap::new()),
+            maximized: RefCell::new(HashMap::new()),
+            maximizes_itself: false,
+            moves: RefCell::new(Vec::new()),
+            restores: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Windows maximize themselves after every move, like an app that
+    /// insists on its own size
+    pub fn maximizing_itself(self) -> Self {
+        MockDesktop {
+            maximizes_itself: true,
+            ..self
+        }
+    }
+
+    /// A window that is already at `rect` before the run
+    pub fn with_window(self, hwnd: usize, rect: Rect) -> Self {
+        self.rects.borrow_mut().insert(hwnd, rect);
+        self
+    }
+
+    /// Whether each move activated its window, in order
+    pub fn moves(&self) -> Vec<bool> {
+        self.moves.borrow().clone()
+    }
+
+    /// Whether each restore from maximized activated its window, in order
+    pub fn restores(&self) -> Vec<bool> {
+        self.restores.borrow().clone()
+    }
+
+    fn restore_from_maximized(&self, hwnd: usize, activate: bool) {
+        if self.maximized.borrow_mut().insert(hwnd, false) == Some(true) {
+            self.restores.borrow_mut().push(activate);
+        }
+    }
+
+    fn find_by_title(&self, search: &MatchCriteria) -> Option<FoundWindow> {
+        let title = search.title_hint()?;
+        let hwnd = self.api.find_window_by_title(title)?;
+        let window = WindowCandidate {
+            hwnd,
+            title: title.to_string(),
+            class: String::new(),
+            cloaked: false,
+            process_id: 0,
+        };
+        Some(FoundWindow::new(&window, search))
+    }
+}
+
+impl Launcher for MockDesktop<'_> {
+    fn launch(&self, app: &Application) -> Result<LaunchHandle, String> {
+        self.api.launch_application(&app.executable)?;
+        Ok(LaunchHandle::default())
+    }
+}
+
+impl Desktop for MockDesktop<'_> {
+    fn monitors(&self) -> Vec<Monitor> {
+        self.api.get_monitors()
+    }
+
+    fn enumerate_windows(&self, _include_cloaked: bool) -> Vec<WindowCandidate> {
+        Vec::new()
+    }
+
+    fn prefetch_windows(
+        &self,
+        _interval: Duration,
+        _include_cloaked: bool,
+    ) -> Option<Prefetch<Vec<WindowCandidate>>> {
+        None
+    }
+
+    fn find_in_windows(
+        &self,
+        _windows: &[WindowCandidate],
+        search: &MatchCriteria,
+        _ready_title: Option<&str>,
+        _ignore: &IgnoreList,
+        _remembered: Option<&WindowIdentity>,
+    ) -> Option<FoundWindow> {
+        self.find_by_title(search)
+    }
+
+    fn find_largest_in_windows(
+        &self,
+        _windows: &[WindowCandidate],
+        search: &MatchCriteria,
+        _ignore: &IgnoreList,
+    ) -> Option<FoundWindow> {
+        self.find_by_title(search)
+    }
+
+    fn find_by_process_tree(
+        &self,
+        _process_id: u32,
+        _windows: &[WindowCandidate],
+        _ignore: &IgnoreList,
+    ) -> Option<FoundWindow> {
+        None
+    }
+
+    fn window_rect(&self, hwnd: usize) -> Option<Rect> {
+        self.rects.borrow().get(&hwnd).copied()
+    }
+
+    fn normal_size(&self, hwnd: usize) -> Option<(i32, i32)> {
+        self.window_rect(hwnd).map(|rect| (rect.width, rect.height))
+    }
+
+    fn monitor_of_window<'m>(&self, _hwnd: usize, _monitors: &'m [Monitor]) -> Option<&'m Monitor> {
+        None
+    }
+
+    fn is_on_other_desktop(&self, _hwnd: usize) -> bool {
+        false
+    }
+
+    fn set_title(&self, _hwnd: usize, _title: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn restore_without_activating(&self, hwnd: usize) {
+        self.restore_from_maximized(hwnd, false);
+    }
+
+    fn position_window(&self, hwnd: usize, rect: Rect, activate: bool) -> Result<(), String> {
+        self.api
+            .position_window(hwnd, rect.x, rect.y, rect.width, rect.height)?;
+        self.rects.borrow_mut().insert(hwnd, rect);
+        self.moves.borrow_mut().push(activate);
+        if self.maximizes_itself {
+            self.maximized.borrow_mut().insert(hwnd, true);
+        }
+        Ok(())
+    }
+
+    fn position_window_smooth(
+        &self,
+        hwnd: usize,
+        rect: Rect,
+        _steps: u32,
+        _total_ms: u64,
+        activate: bool,
+    ) -> Result<(), String> {
+        self.position_window(hwnd, rect, activate)
+    }
+
+    fn align_visible_frame(&self, _hwnd: usize, rect: Rect) -> Result<Rect, String> {
+        Ok(rect)
+    }
+
+    fn show_state(&self, hwnd: usize) -> ShowState {
+        match self.maximized.borrow().get(&hwnd) {
+            Some(true) => ShowState::Maximized,
+            _ => ShowState::Normal,
+        }
+    }
+
+    fn set_show_state(&self, hwnd: usize, state: ShowState) {
+        // Like SW_RESTORE, restoring this way activates the window
+        if state == ShowState::Normal {
+            self.restore_from_maximized(hwnd, true);
+        }
+    }
+
+    fn set_zorder(&self, _windows_in_order: &[usize]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn foreground_window(&self) -> Option<usize> {
+        None
+    }
+
+    fn set_foreground_window(&self, _hwnd: usize) -> bool {
+        true
+    }
+
+    fn window_identity(&self, _hwnd: usize, title: &str) -> WindowIdentity {
+        WindowIdentity {
+            class: String::new(),
+            executable: None,
+            title: title.to_string(),
+        }
+    }
+
+    fn restore(&self, snapshot: &UndoSnapshot) -> usize {
+        snapshot
+            .windows
+            .iter()
+            .filter(|window| {
+                let rect = Rect::new(window.x, window.y, window.width, window.height);
+                self.position_window(window.hwnd, rect, false).is_ok()
+            })
+            .count()
+    }
 }
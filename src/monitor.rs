@@ -1,41 +1,45 @@
+use crate::display::{Monitor, Rect, monitor_at_point};
+use std::collections::HashMap;
 use std::mem;
 use std::ptr;
+use tracing::debug;
 use widestring::U16CString;
-use winapi::shared::minwindef::{BOOL, DWORD, LPARAM, TRUE};
-use winapi::shared::windef::{HDC, HMONITOR, LPRECT};
-use winapi::um::winuser::{EnumDisplayMonitors, GetMonitorInfoW, MONITORINFOEXW};
-
-#[derive(Clone)]
-pub struct MonitorInfo {
-    pub handle: HMONITOR,
-    pub rect: winapi::shared::windef::RECT,
-    pub work_area: winapi::shared::windef::RECT,
-    pub device_name: String,
+use winapi::shared::basetsd::UINT32;
+use winapi::shared::minwindef::{BOOL, DWORD, LPARAM, TRUE, UINT};
+use winapi::shared::windef::{HDC, HMONITOR, HWND, LPRECT, RECT};
+use winapi::shared::winerror::{ERROR_SUCCESS, S_OK};
+use winapi::um::shellscalingapi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+use winapi::um::wingdi::{
+    DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME, DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME,
+    DISPLAYCONFIG_DEVICE_INFO_HEADER, DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO,
+    DISPLAYCONFIG_SOURCE_DEVICE_NAME, DISPLAYCONFIG_TARGET_DEVICE_NAME, DISPLAYCONFIG_TOPOLOGY_ID,
+    QDC_ONLY_ACTIVE_PATHS,
+};
+use winapi::um::winnt::LONG;
+use winapi::um::winuser::{
+    EnumDisplayMonitors, GetMonitorInfoW, MONITOR_DEFAULTTONULL, MONITORINFOEXW,
+    MONITORINFOF_PRIMARY, MonitorFromWindow,
+};
+
+impl From<RECT> for Rect {
+    fn from(rect: RECT) -> Self {
+        Rect::new(
+            rect.left,
+            rect.top,
+            rect.right - rect.left,
+            rect.bottom - rect.top,
+        )
+    }
 }
 
-impl std::fmt::Debug for MonitorInfo {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("MonitorInfo")
-            .field("handle", &self.handle)
-            .field(
-                "rect",
-                &format!(
-                    "RECT {{ left: {}, top: {}, right: {}, bottom: {} }}",
-                    self.rect.left, self.rect.top, self.rect.right, self.rect.bottom
-                ),
-            )
-            .field(
-                "work_area",
-                &format!(
-                    "RECT {{ left: {}, top: {}, right: {}, bottom: {} }}",
-                    self.work_area.left,
-                    self.work_area.top,
-                    self.work_area.right,
-                    self.work_area.bottom
-                ),
-            )
-            .field("device_name", &self.device_name)
-            .finish()
+/// Scale factor of the monitor relative to 96 DPI; 1.0 if it can't be queried
+fn monitor_scale(hmonitor: HMONITOR) -> f32 {
+    let (mut dpi_x, mut dpi_y): (UINT, UINT) = (0, 0);
+    let result = unsafe { GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) };
+    if result == S_OK && dpi_x > 0 {
+        dpi_x as f32 / 96.0
+    } else {
+        1.0
     }
 }
 
@@ -45,7 +49,7 @@ unsafe extern "system" fn monitor_enum_proc(
     _rect: LPRECT,
     data: LPARAM,
 ) -> BOOL {
-    let monitors = data as *mut Vec<MonitorInfo>;
+    let monitors = data as *mut Vec<Monitor>;
 
     let mut monitor_info: MONITORINFOEXW = unsafe { mem::zeroed() };
     monitor_info.cbSize = mem::size_of::<MONITORINFOEXW>() as DWORD;
@@ -62,14 +66,17 @@ unsafe extern "system" fn monitor_enum_proc(
                 .to_string()
         };
 
-        let monitor = MonitorInfo {
-            handle: hmonitor,
-            rect: monitor_info.rcMonitor,
-            work_area: monitor_info.rcWork,
-            device_name,
-        };
-
         unsafe {
+            let monitor = Monitor {
+                index: (*monitors).len(),
+                name: device_name,
+                friendly_name: String::new(),
+                bounds: monitor_info.rcMonitor.into(),
+                work_area: monitor_info.rcWork.into(),
+                primary: monitor_info.dwFlags & MONITORINFOF_PRIMARY != 0,
+                scale: monitor_scale(hmonitor),
+                handle: hmonitor as usize,
+            };
             (*monitors).push(monitor);
         }
     }
@@ -77,40 +84,125 @@ unsafe extern "system" fn monitor_enum_proc(
     TRUE
 }
 
-pub fn get_monitors() -> Vec<MonitorInfo> {
-    let mut monitors: Vec<MonitorInfo> = Vec::new();
+pub fn get_monitors() -> Vec<Monitor> {
+    let mut monitors: Vec<Monitor> = Vec::new();
     unsafe {
         EnumDisplayMonitors(
             ptr::null_mut(),
             ptr::null(),
             Some(monitor_enum_proc),
-            &mut monitors as *mut Vec<MonitorInfo> as LPARAM,
+            &mut monitors as *mut Vec<Monitor> as LPARAM,
         );
     }
+
+    let friendly_names = friendly_monitor_names();
+    for monitor in &mut monitors {
+        if let Some(name) = friendly_names.get(&monitor.name) {
+            monitor.friendly_name = name.clone();
+        }
+    }
     monitors
 }
 
-pub fn get_monitor_by_number(monitors: &[MonitorInfo], number: u32) -> Option<&MonitorInfo> {
-    if number == 0 || number > monitors.len() as u32 {
-        return None;
-    }
-    monitors.get((number - 1) as usize)
+// Not declared by winapi 0.3
+#[link(name = "user32")]
+unsafe extern "system" {
+    fn GetDisplayConfigBufferSizes(
+        flags: UINT32,
+        num_path_array_elements: *mut UINT32,
+        num_mode_info_array_elements: *mut UINT32,
+    ) -> LONG;
+    fn QueryDisplayConfig(
+        flags: UINT32,
+        num_path_array_elements: *mut UINT32,
+        path_array: *mut DISPLAYCONFIG_PATH_INFO,
+        num_mode_info_array_elements: *mut UINT32,
+        mode_info_array: *mut DISPLAYCONFIG_MODE_INFO,
+        current_topology_id: *mut DISPLAYCONFIG_TOPOLOGY_ID,
+    ) -> LONG;
+    fn DisplayConfigGetDeviceInfo(request_packet: *mut DISPLAYCONFIG_DEVICE_INFO_HEADER) -> LONG;
 }
 
-pub fn calculate_window_position(
-    monitor: &MonitorInfo,
-    side: &crate::config::Side,
-) -> (i32, i32, i32, i32) {
-    let work_area = &monitor.work_area;
-    let width = work_area.right - work_area.left;
-    let height = work_area.bottom - work_area.top;
-
-    match side {
-        crate::config::Side::Left => (work_area.left, work_area.top, width / 2, height),
-        crate::config::Side::Right => {
-            (work_area.left + width / 2, work_area.top, width / 2, height)
+fn wide_to_string(wide: &[u16]) -> String {
+    let len = wide.iter().position(|&c| c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..len])
+}
+
+/// Maps GDI device names (`\\.\DISPLAY1`) to the monitors' EDID friendly names
+fn friendly_monitor_names() -> HashMap<String, String> {
+    let mut names = HashMap::new();
+
+    let mut path_count: UINT32 = 0;
+    let mut mode_count: UINT32 = 0;
+    if unsafe {
+        GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count)
+    } != ERROR_SUCCESS as LONG
+    {
+        debug!("GetDisplayConfigBufferSizes failed; monitor names unavailable");
+        return names;
+    }
+
+    let mut paths: Vec<DISPLAYCONFIG_PATH_INFO> =
+        vec![unsafe { mem::zeroed() }; path_count as usize];
+    let mut modes: Vec<DISPLAYCONFIG_MODE_INFO> =
+        vec![unsafe { mem::zeroed() }; mode_count as usize];
+    let status = unsafe {
+        QueryDisplayConfig(
+            QDC_ONLY_ACTIVE_PATHS,
+            &mut path_count,
+            paths.as_mut_ptr(),
+            &mut mode_count,
+            modes.as_mut_ptr(),
+            ptr::null_mut(),
+        )
+    };
+    if status != ERROR_SUCCESS as LONG {
+        debug!("QueryDisplayConfig failed with {}", status);
+        return names;
+    }
+    paths.truncate(path_count as usize);
+
+    for path in &paths {
+        let mut source: DISPLAYCONFIG_SOURCE_DEVICE_NAME = unsafe { mem::zeroed() };
+        source.header._type = DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME;
+        source.header.size = mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as UINT32;
+        source.header.adapterId = path.sourceInfo.adapterId;
+        source.header.id = path.sourceInfo.id;
+
+        let mut target: DISPLAYCONFIG_TARGET_DEVICE_NAME = unsafe { mem::zeroed() };
+        target.header._type = DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME;
+        target.header.size = mem::size_of::<DISPLAYCONFIG_TARGET_DEVICE_NAME>() as UINT32;
+        target.header.adapterId = path.targetInfo.adapterId;
+        target.header.id = path.targetInfo.id;
+
+        let ok = unsafe {
+            DisplayConfigGetDeviceInfo(&mut source.header) == ERROR_SUCCESS as LONG
+                && DisplayConfigGetDeviceInfo(&mut target.header) == ERROR_SUCCESS as LONG
+        };
+        if !ok {
+            continue;
+        }
+
+        let friendly_name = wide_to_string(&target.monitorFriendlyDeviceName);
+        if !friendly_name.is_empty() {
+            names.insert(wide_to_string(&source.viewGdiDeviceName), friendly_name);
         }
     }
+    names
+}
+
+/// Returns the monitor a window is currently on. Uses `MonitorFromWindow` and
+/// falls back to the monitor containing the window's center point.
+pub fn monitor_of_window(hwnd: HWND, monitors: &[Monitor]) -> Option<&Monitor> {
+    let handle = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONULL) };
+    if !handle.is_null()
+        && let Some(monitor) = monitors.iter().find(|m| m.handle == handle as usize)
+    {
+        return Some(monitor);
+    }
+
+    let (x, y) = crate::window::get_window_rect(hwnd)?.center();
+    monitor_at_point(monitors, x, y)
 }
 
 #[cfg(test)]
@@ -118,89 +210,13 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_get_monitor_by_number() {
-        let monitors = vec![
-            MonitorInfo {
-                handle: ptr::null_mut(),
-                rect: winapi::shared::windef::RECT {
-                    left: 0,
-                    top: 0,
-                    right: 1920,
-                    bottom: 1080,
-                },
-                work_area: winapi::shared::windef::RECT {
-                    left: 0,
-                    top: 0,
-                    right: 1920,
-                    bottom: 1040,
-                },
-                device_name: "Monitor1".to_string(),
-            },
-            MonitorInfo {
-                handle: ptr::null_mut(),
-                rect: winapi::shared::windef::RECT {
-                    left: 1920,
-                    top: 0,
-                    right: 3840,
-                    bottom: 1080,
-                },
-                work_area: winapi::shared::windef::RECT {
-                    left: 1920,
-                    top: 0,
-                    right: 3840,
-                    bottom: 1040,
-                },
-                device_name: "Monitor2".to_string(),
-            },
-        ];
-
-        // Test valid monitor numbers
-        assert_eq!(
-            get_monitor_by_number(&monitors, 1).unwrap().device_name,
-            "Monitor1"
-        );
-        assert_eq!(
-            get_monitor_by_number(&monitors, 2).unwrap().device_name,
-            "Monitor2"
-        );
-
-        // Test invalid monitor numbers
-        assert!(get_monitor_by_number(&monitors, 0).is_none());
-        assert!(get_monitor_by_number(&monitors, 3).is_none());
-    }
-
-    #[test]
-    fn test_calculate_window_position() {
-        let monitor = MonitorInfo {
-            handle: ptr::null_mut(),
-            rect: winapi::shared::windef::RECT {
-                left: 0,
-                top: 0,
-                right: 1920,
-                bottom: 1080,
-            },
-            work_area: winapi::shared::windef::RECT {
-                left: 0,
-                top: 0,
-                right: 1920,
-                bottom: 1040,
-            },
-            device_name: "Test Monitor".to_string(),
+    fn test_rect_from_win32() {
+        let rect = RECT {
+            left: -1920,
+            top: 0,
+            right: 0,
+            bottom: 1040,
         };
-
-        // Test left side positioning
-        let (x, y, width, height) = calculate_window_position(&monitor, &crate::config::Side::Left);
-        assert_eq!(x, 0);
-        assert_eq!(y, 0);
-        assert_eq!(width, 960);
-        assert_eq!(height, 1040);
-
-        // Test right side positioning
-        let (x, y, width, height) =
-            calculate_window_position(&monitor, &crate::config::Side::Right);
-        assert_eq!(x, 960);
-        assert_eq!(y, 0);
-        assert_eq!(width, 960);
-        assert_eq!(height, 1040);
+        assert_eq!(Rect::from(rect), Rect::new(-1920, 0, 1920, 1040));
     }
 }
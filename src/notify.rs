@@ -0,0 +1,78 @@
+use crate::app_launcher::{AppOutcome, LaunchReport};
+#[cfg(not(feature = "notifications"))]
+use tracing::warn;
+
+/// Title and body of the notification summarizing a finished run
+pub fn report_summary(report: &LaunchReport) -> (String, String) {
+    let launched = report
+        .results
+        .iter()
+        .filter(|result| !matches!(result.outcome, AppOutcome::Skipped(_)))
+        .count();
+    let title = format!(
+        "Jumpstart: {}/{} apps positioned",
+        report.positioned_count(),
+        launched
+    );
+
+    let failures: Vec<String> = report
+        .results
+        .iter()
+        .filter(|result| result.outcome.is_failure())
+        .map(|result| format!("{}: {}", result.name, result.outcome))
+        .collect();
+    let body = if failures.is_empty() {
+        "All apps are in place.".to_string()
+    } else {
+        failures.join("\n")
+    };
+    (title, body)
+}
+
+/// Shows a desktop notification, for runs where nobody watches the console
+#[cfg(feature = "notifications")]
+pub fn send(title: &str, body: &str) -> Result<(), String> {
+    notify_rust::Notification::new()
+        .appname("Jumpstart")
+        .summary(title)
+        .body(body)
+        .show()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to show notification: {}", e))
+}
+
+#[cfg(not(feature = "notifications"))]
+pub fn send(title: &str, _body: &str) -> Result<(), String> {
+    warn!(
+        "Not showing notification '{}': jumpstart was built without the notifications feature",
+        title
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_summary() {
+        let mut report = LaunchReport::default();
+        report.record("Editor", AppOutcome::Positioned);
+        report.record("Music", AppOutcome::Skipped("disabled".to_string()));
+        report.record("Chat", AppOutcome::WindowNotFound);
+
+        let (title, body) = report_summary(&report);
+        assert_eq!(title, "Jumpstart: 1/2 apps positioned");
+        assert_eq!(body, "Chat: window not found");
+
+        let mut report = LaunchReport::default();
+        report.record("Editor", AppOutcome::Positioned);
+        assert_eq!(
+            report_summary(&report),
+            (
+                "Jumpstart: 1/1 apps positioned".to_string(),
+                "All apps are in place.".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use crate::config::Application;
+use crate::display::{Monitor, Rect};
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a placement script may take to answer
+pub const PLACEMENT_SCRIPT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Most of a script's stdout that is read; a rectangle needs far less
+const MAX_OUTPUT_BYTES: u64 = 64 * 1024;
+
+/// A monitor as a placement script sees it
+#[derive(Debug, Serialize)]
+struct ScriptMonitor<'a> {
+    /// Display number, counting from 1 as `display` does in the config
+    display: usize,
+    name: &'a str,
+    friendly_name: &'a str,
+    primary: bool,
+    scale: f32,
+    bounds: Rect,
+    work_area: Rect,
+}
+
+/// What a placement script reads from stdin, as one JSON object
+#[derive(Debug, Serialize)]
+pub struct PlacementRequest<'a> {
+    /// The app's settings from the config
+    app: &'a Application,
+    /// Display number of the monitor the app is assigned to
+    display: usize,
+    monitors: Vec<ScriptMonitor<'a>>,
+    /// Where the window would go without the script
+    planned: Rect,
+}
+
+impl<'a> PlacementRequest<'a> {
+    pub fn new(
+        app: &'a Application,
+        monitor: &Monitor,
+        monitors: &'a [Monitor],
+        planned: Rect,
+    ) -> Self {
+        PlacementRequest {
+            app,
+            display: monitor.index + 1,
+            monitors: monitors
+                .iter()
+                .map(|monitor| ScriptMonitor {
+                    display: monitor.index + 1,
+                    name: &monitor.name,
+                    friendly_name: &monitor.friendly_name,
+                    primary: monitor.primary,
+                    scale: monitor.scale,
+                    bounds: monitor.bounds,
+                    work_area: monitor.work_area,
+                })
+                .collect(),
+            planned,
+        }
+    }
+}
+
+/// Runs `script` through the shell, writing `request` to its stdin, and
+/// returns the rectangle it prints. Fails if it takes longer than `timeout`,
+/// exits with an error or prints anything but a rectangle on `monitors`.
+pub fn run(
+    script: &str,
+    request: &PlacementRequest,
+    monitors: &[Monitor],
+    timeout: Duration,
+) -> Result<Rect, String> {
+    let input = serde_json::to_vec(request).map_err(|e| e.to_string())?;
+    let mut child = shell(script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run placement script '{}': {}", script, e))?;
+
+    // Write and read on threads so a script that ignores its input or
+    // never finishes can't block the launch
+    if let Some(mut stdin) = child.stdin.take() {
+        thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+    }
+    let (sender, receiver) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = stdout.take(MAX_OUTPUT_BYTES).read_to_end(&mut output);
+            let _ = sender.send(output);
+        });
+    }
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if start.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!(
+                "Placement script '{}' didn't answer within {} ms",
+                script,
+                timeout.as_millis()
+            ));
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    if !status.success() {
+        return Err(format!(
+            "Placement script '{}' failed with {}",
+            script, status
+        ));
+    }
+    let output = receiver
+        .recv_timeout(Duration::from_millis(200))
+        .unwrap_or_default();
+    parse_rect(&String::from_utf8_lossy(&output), monitors)
+        .map_err(|e| format!("Placement script '{}' {}", script, e))
+}
+
+/// The script's answer, e.g. `{"x": 0, "y": 0, "width": 960, "height": 1040}`,
+/// checked to have a size and to be at least partly on one of `monitors`
+pub fn parse_rect(output: &str, monitors: &[Monitor]) -> Result<Rect, String> {
+    let rect: Rect =
+        serde_json::from_str(output.trim()).map_err(|e| format!("printed no rectangle ({})", e))?;
+    if rect.width <= 0 || rect.height <= 0 {
+        return Err(format!(
+            "returned a {}x{} rectangle; width and height must be positive",
+            rect.width, rect.height
+        ));
+    }
+    if !monitors
+        .iter()
+        .any(|monitor| monitor.bounds.intersects(&rect))
+    {
+        return Err(format!(
+            "returned ({}, {}) {}x{}, which is on no monitor",
+            rect.x, rect.y, rect.width, rect.height
+        ));
+    }
+    Ok(rect)
+}
+
+#[cfg(windows)]
+fn shell(script: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", script]);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell(script: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", script]);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::test_monitor;
+
+    #[test]
+    fn test_parse_rect() {
+        let monitors = vec![test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080))];
+        assert_eq!(
+            parse_rect(
+                r#"{"x": 0, "y": 0, "width": 960, "height": 1040}"#,
+                &monitors
+            ),
+            Ok(Rect::new(0, 0, 960, 1040))
+        );
+        assert!(
+            parse_rect("left half", &monitors)
+                .unwrap_err()
+                .contains("printed no rectangle")
+        );
+        let empty = r#"{"x": 0, "y": 0, "width": 0, "height": 1040}"#;
+        assert!(
+            parse_rect(empty, &monitors)
+                .unwrap_err()
+                .contains("positive")
+        );
+        let off_screen = r#"{"x": 5000, "y": 0, "width": 960, "height": 1040}"#;
+        assert!(
+            parse_rect(off_screen, &monitors)
+                .unwrap_err()
+                .contains("on no monitor")
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_placement_script() {
+        let monitors = vec![test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080))];
+        let app = Application {
+            name: "Editor".to_string(),
+            ..Default::default()
+        };
+        let request = PlacementRequest::new(&app, &monitors[0], &monitors, Rect::default());
+
+        // The script sees the request and answers with a rectangle
+        let script =
+            r#"grep -q '"name":"Editor"' && echo '{"x":10,"y":20,"width":300,"height":400}'"#;
+        assert_eq!(
+            run(script, &request, &monitors, PLACEMENT_SCRIPT_TIMEOUT),
+            Ok(Rect::new(10, 20, 300, 400))
+        );
+
+        assert!(
+            run("exit 2", &request, &monitors, PLACEMENT_SCRIPT_TIMEOUT)
+                .unwrap_err()
+                .contains("failed")
+        );
+        let slow = run("sleep 5", &request, &monitors, Duration::from_millis(100));
+        assert!(slow.unwrap_err().contains("didn't answer within 100 ms"));
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Runs a probe repeatedly on a background thread so a fresh result is ready
+/// when the caller needs it, instead of paying for the probe on the critical path.
+/// The thread stops when the `Prefetch` is dropped.
+pub struct Prefetch<T> {
+    rx: Receiver<T>,
+    stop: Arc<AtomicBool>,
+}
+
+impl<T: Send + 'static> Prefetch<T> {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn spawn<F>(interval: Duration, probe: F) -> Self
+    where
+        F: Fn() -> T + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+
+        thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                if tx.send(probe()).is_err() {
+                    break;
+                }
+                thread::sleep(interval);
+            }
+        });
+
+        Self { rx, stop }
+    }
+
+    /// Waits up to `wait` for a result produced since the last call and returns
+    /// the newest one, or `None` if the probe hasn't produced anything new.
+    pub fn next_within(&self, wait: Duration) -> Option<T> {
+        let mut latest = match self.rx.recv_timeout(wait) {
+            Ok(value) => value,
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return None,
+        };
+        while let Ok(newer) = self.rx.try_recv() {
+            latest = newer;
+        }
+        Some(latest)
+    }
+}
+
+impl<T> Drop for Prefetch<T> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_prefetch_returns_newest_result() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let probe_counter = Arc::clone(&counter);
+        let prefetch = Prefetch::spawn(Duration::from_millis(1), move || {
+            probe_counter.fetch_add(1, Ordering::SeqCst) + 1
+        });
+
+        let first = prefetch.next_within(Duration::from_secs(1)).unwrap();
+        thread::sleep(Duration::from_millis(20));
+        let second = prefetch.next_within(Duration::from_secs(1)).unwrap();
+        // Stale results in between are skipped
+        assert!(second > first + 1);
+
+        drop(prefetch);
+        thread::sleep(Duration::from_millis(20));
+        let stopped_at = counter.load(Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(counter.load(Ordering::SeqCst), stopped_at);
+    }
+
+    #[test]
+    fn test_next_within_times_out() {
+        let prefetch = Prefetch::spawn(Duration::from_millis(1), || {
+            thread::sleep(Duration::from_millis(200));
+        });
+        assert!(prefetch.next_within(Duration::from_millis(5)).is_none());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::config::ProcessPriority;
+
+/// The Windows priority class for `priority`
+#[cfg(windows)]
+pub fn priority_class(priority: ProcessPriority) -> winapi::shared::minwindef::DWORD {
+    use winapi::um::winbase::{
+        ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS,
+        IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+    };
+
+    match priority {
+        ProcessPriority::Idle => IDLE_PRIORITY_CLASS,
+        ProcessPriority::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+        ProcessPriority::Normal => NORMAL_PRIORITY_CLASS,
+        ProcessPriority::AboveNormal => ABOVE_NORMAL_PRIORITY_CLASS,
+        ProcessPriority::High => HIGH_PRIORITY_CLASS,
+    }
+}
+
+/// The niceness for `priority`, from 19 (only runs when nothing else wants
+/// the CPU) to -10; raising a process above 0 takes root
+#[cfg(not(windows))]
+pub fn nice_value(priority: ProcessPriority) -> i32 {
+    match priority {
+        ProcessPriority::Idle => 19,
+        ProcessPriority::BelowNormal => 10,
+        ProcessPriority::Normal => 0,
+        ProcessPriority::AboveNormal => -5,
+        ProcessPriority::High => -10,
+    }
+}
+
+/// Gives the process `priority`. Processes it starts later inherit it, but
+/// ones it has already started keep their own.
+#[cfg(windows)]
+pub fn set_priority(process_id: u32, priority: ProcessPriority) -> Result<(), String> {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{OpenProcess, SetPriorityClass};
+    use winapi::um::winnt::PROCESS_SET_INFORMATION;
+
+    let process = unsafe { OpenProcess(PROCESS_SET_INFORMATION, 0, process_id) };
+    if process.is_null() {
+        return Err(format!(
+            "Failed to open process {}: {}",
+            process_id,
+            std::io::Error::last_os_error()
+        ));
+    }
+    let result = if unsafe { SetPriorityClass(process, priority_class(priority)) } != 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to set the priority of process {}: {}",
+            process_id,
+            std::io::Error::last_os_error()
+        ))
+    };
+    unsafe {
+        CloseHandle(process);
+    }
+    result
+}
+
+/// Gives the process `priority` with `renice`. Processes it starts later
+/// inherit it, but ones it has already started keep their own.
+#[cfg(not(windows))]
+pub fn set_priority(process_id: u32, priority: ProcessPriority) -> Result<(), String> {
+    let output = std::process::Command::new("renice")
+        .args(["-n", &nice_value(priority).to_string()])
+        .args(["-p", &process_id.to_string()])
+        .output()
+        .map_err(|e| format!("Failed to run renice: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to set the priority of process {}: {}",
+            process_id,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_mapping() {
+        let parse = |name: &str| serde_yaml::from_str::<ProcessPriority>(name).unwrap();
+
+        #[cfg(windows)]
+        {
+            use winapi::um::winbase::{
+                ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS,
+                IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+            };
+            assert_eq!(priority_class(parse("idle")), IDLE_PRIORITY_CLASS);
+            assert_eq!(
+                priority_class(parse("below_normal")),
+                BELOW_NORMAL_PRIORITY_CLASS
+            );
+            assert_eq!(priority_class(parse("normal")), NORMAL_PRIORITY_CLASS);
+            assert_eq!(
+                priority_class(parse("above_normal")),
+                ABOVE_NORMAL_PRIORITY_CLASS
+            );
+            assert_eq!(priority_class(parse("high")), HIGH_PRIORITY_CLASS);
+        }
+        #[cfg(not(windows))]
+        {
+            assert_eq!(nice_value(parse("idle")), 19);
+            assert_eq!(nice_value(parse("below_normal")), 10);
+            assert_eq!(nice_value(parse("normal")), 0);
+            assert_eq!(nice_value(parse("above_normal")), -5);
+            assert_eq!(nice_value(parse("high")), -10);
+        }
+
+        assert!(serde_yaml::from_str::<ProcessPriority>("realtime").is_err());
+    }
+}
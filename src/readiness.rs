@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Pause between connection attempts while waiting for a service
+const RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Upper bound for a single connection attempt, so one unresponsive address
+/// doesn't use up the whole wait
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// What to do with an application whose dependency never became ready
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OnTimeout {
+    /// Don't launch it and report it as failed
+    #[default]
+    Fail,
+    /// Don't launch it and report it as skipped
+    Skip,
+    /// Launch it anyway
+    Launch,
+}
+
+/// A service that has to be reachable before the application is launched
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WaitFor {
+    /// `host:port` that must accept TCP connections
+    pub tcp: String,
+    #[serde(default = "default_wait_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub on_timeout: OnTimeout,
+}
+
+fn default_wait_timeout_ms() -> u64 {
+    30_000
+}
+
+impl WaitFor {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.tcp.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(()),
+            _ => Err(format!("'{}' is not a host:port address", self.tcp)),
+        }
+    }
+
+    /// How long to wait for the service before giving up
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
+
+/// Polls `addr` until it accepts a TCP connection or `timeout` passes
+pub fn wait_for_tcp(addr: &str, timeout: Duration) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+    let mut last_error = String::from("no attempt made");
+
+    loop {
+        // Resolve on every attempt; the name may only become resolvable once a VPN is up
+        match addr.to_socket_addrs() {
+            Ok(addrs) => {
+                for socket_addr in addrs {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    let attempt = remaining.min(ATTEMPT_TIMEOUT).max(Duration::from_millis(1));
+                    match TcpStream::connect_timeout(&socket_addr, attempt) {
+                        Ok(_) => return Ok(()),
+                        Err(e) => last_error = e.to_string(),
+                    }
+                }
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(format!(
+                "{} not reachable after {} ms: {}",
+                addr,
+                timeout.as_millis(),
+                last_error
+            ));
+        }
+        thread::sleep(RETRY_INTERVAL.min(deadline - now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_wait_for_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        assert!(wait_for_tcp(&addr, Duration::from_secs(1)).is_ok());
+
+        // Nothing listens on the port once the listener is gone
+        drop(listener);
+        let err = wait_for_tcp(&addr, Duration::from_millis(50)).unwrap_err();
+        assert!(err.contains("not reachable"));
+    }
+
+    #[test]
+    fn test_validate_address() {
+        let wait_for = |tcp: &str| WaitFor {
+            tcp: tcp.to_string(),
+            timeout_ms: default_wait_timeout_ms(),
+            on_timeout: OnTimeout::default(),
+        };
+        assert!(wait_for("intranet.local:8080").validate().is_ok());
+        assert!(wait_for("[::1]:443").validate().is_ok());
+        assert!(wait_for("intranet.local").validate().is_err());
+        assert!(wait_for(":80").validate().is_err());
+        assert!(wait_for("host:http").validate().is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::display::Rect;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// One window move, as written to the `--record` file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionRecord {
+    pub app: String,
+    pub hwnd: usize,
+    #[serde(flatten)]
+    pub rect: Rect,
+}
+
+/// Empties the record file, so it only describes the current run
+pub fn start(path: &Path) -> Result<(), String> {
+    File::create(path)
+        .map(drop)
+        .map_err(|e| format!("Failed to create record file '{}': {}", path.display(), e))
+}
+
+/// Appends a record to the file as one JSON line
+pub fn append(path: &Path, record: &PositionRecord) -> Result<(), String> {
+    let line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|e| format!("Failed to write to record file '{}': {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_record_file() {
+        let path = std::env::temp_dir().join("jumpstart_record_test.jsonl");
+        fs::write(&path, "left over from an earlier run\n").unwrap();
+        start(&path).unwrap();
+
+        let editor = PositionRecord {
+            app: "Editor".to_string(),
+            hwnd: 1001,
+            rect: Rect::new(0, 0, 960, 1040),
+        };
+        let chat = PositionRecord {
+            app: "Chat".to_string(),
+            hwnd: 1002,
+            rect: Rect::new(2880, 0, 960, 1040),
+        };
+        append(&path, &editor).unwrap();
+        append(&path, &chat).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(
+            lines[0],
+            r#"{"app":"Editor","hwnd":1001,"x":0,"y":0,"width":960,"height":1040}"#
+        );
+        let records: Vec<PositionRecord> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records, vec![editor, chat]);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
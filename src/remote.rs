@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// How long to wait for a remote config before falling back to the cached copy
+#[cfg(feature = "remote_config")]
+const FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Whether the config path is an `http(s)://` URL rather than a file
+pub fn is_url(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Where the last successfully fetched copy of `url` is kept
+pub fn cache_path(url: &str) -> PathBuf {
+    let file_name: String = url
+        .split("://")
+        .last()
+        .unwrap_or(url)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    std::env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("jumpstart")
+        .join("remote")
+        .join(file_name)
+}
+
+/// Rejects responses that can't be a config, e.g. an HTML login page. YAML
+/// and JSON are both accepted, since the YAML parser reads JSON too. When the
+/// server doesn't say, the URL's extension decides.
+#[cfg(any(feature = "remote_config", test))]
+pub fn check_format(content_type: Option<&str>, url: &str) -> Result<(), String> {
+    let media_type = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty() && value != "application/octet-stream");
+
+    let supported = match media_type {
+        Some(ref media_type) => {
+            media_type.contains("yaml") || media_type.contains("json") || media_type == "text/plain"
+        }
+        None => {
+            let path = url.split(['?', '#']).next().unwrap_or(url);
+            match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+                Some(ext) => ["yml", "yaml", "json"].contains(&ext.to_lowercase().as_str()),
+                None => true,
+            }
+        }
+    };
+
+    if supported {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' is not a YAML or JSON config ({})",
+            url,
+            media_type.as_deref().unwrap_or("unknown format")
+        ))
+    }
+}
+
+/// Fetches the config with `fetch`, refreshing the cache at `cache`. If the
+/// fetch fails, the cached copy is used instead so offline launches still work.
+pub fn load_with_cache(
+    url: &str,
+    cache: &Path,
+    fetch: impl FnOnce() -> Result<String, String>,
+) -> Result<String, String> {
+    match fetch() {
+        Ok(content) => {
+            if let Some(dir) = cache.parent()
+                && let Err(e) = fs::create_dir_all(dir).and_then(|()| fs::write(cache, &content))
+            {
+                warn!("Failed to cache config from '{}': {}", url, e);
+            }
+            Ok(content)
+        }
+        Err(e) => {
+            let content = fs::read_to_string(cache).map_err(|_| {
+                format!(
+                    "Failed to fetch config from '{}' and no cached copy exists: {}",
+                    url, e
+                )
+            })?;
+            warn!(
+                "Failed to fetch config from '{}': {}; using the copy cached at '{}'",
+                url,
+                e,
+                cache.display()
+            );
+            Ok(content)
+        }
+    }
+}
+
+/// Reads a config from an `http(s)://` URL, falling back to the cached copy
+pub fn read_remote_config(url: &str) -> Result<String, String> {
+    info!("Fetching config from '{}'", url);
+    load_with_cache(url, &cache_path(url), || fetch(url))
+}
+
+#[cfg(feature = "remote_config")]
+fn fetch(url: &str) -> Result<String, String> {
+    let response = ureq::AgentBuilder::new()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .get(url)
+        .call()
+        .map_err(|e| e.to_string())?;
+    let content_type = response.header("Content-Type").map(str::to_string);
+    check_format(content_type.as_deref(), url)?;
+    response
+        .into_string()
+        .map_err(|e| format!("Failed to read response: {}", e))
+}
+
+#[cfg(not(feature = "remote_config"))]
+fn fetch(_url: &str) -> Result<String, String> {
+    Err("jumpstart was built without the remote_config feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_url_and_cache_path() {
+        assert!(is_url("https://intranet.local/layout.yml"));
+        assert!(is_url("HTTP://intranet.local/layout.yml"));
+        assert!(!is_url("config.yml"));
+        assert!(!is_url("C:\\configs\\https.yml"));
+
+        let path = cache_path("https://intranet.local:8080/desk/layout.yml?v=2");
+        assert_eq!(
+            path.file_name().unwrap(),
+            "intranet.local_8080_desk_layout.yml_v_2"
+        );
+    }
+
+    #[test]
+    fn test_check_format() {
+        let url = "https://intranet.local/layout";
+        assert!(check_format(Some("application/yaml"), url).is_ok());
+        assert!(check_format(Some("application/json; charset=utf-8"), url).is_ok());
+        assert!(check_format(Some("text/plain"), url).is_ok());
+        assert!(check_format(Some("text/html"), url).is_err());
+
+        // Without a useful Content-Type the extension decides
+        assert!(check_format(None, "https://intranet.local/layout.YML?v=2").is_ok());
+        assert!(check_format(Some("application/octet-stream"), "https://x/l.json").is_ok());
+        assert!(check_format(None, "https://intranet.local/index.html").is_err());
+        assert!(check_format(None, url).is_ok());
+    }
+
+    #[test]
+    fn test_load_with_cache() {
+        let dir = std::env::temp_dir().join("jumpstart_remote_cache_test");
+        let _ = fs::remove_dir_all(&dir);
+        let cache = dir.join("layout.yml");
+        let url = "https://intranet.local/layout.yml";
+
+        // Nothing fetched and nothing cached yet
+        assert!(load_with_cache(url, &cache, || Err("offline".to_string())).is_err());
+
+        let fetched = load_with_cache(url, &cache, || Ok("applications: []".to_string()));
+        assert_eq!(fetched.unwrap(), "applications: []");
+
+        // Offline launches use the last fetched copy
+        let cached = load_with_cache(url, &cache, || Err("timed out".to_string()));
+        assert_eq!(cached.unwrap(), "applications: []");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,324 @@
+use crate::config::Config;
+use crate::display::{Monitor, Rect, plan_layout};
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use std::path::Path;
+use tiny_skia::{Paint, PathBuilder, Pixmap, PremultipliedColorU8, Stroke, Transform};
+
+/// Largest size the monitors are drawn at; the picture is scaled to fit
+const MAX_WIDTH: f32 = 1200.0;
+const MAX_HEIGHT: f32 = 700.0;
+/// Narrowest picture, so the legend has room next to a single small monitor
+const MIN_WIDTH: f32 = 640.0;
+const MARGIN: f32 = 20.0;
+const LINE_HEIGHT: f32 = 22.0;
+const TEXT_SIZE: f32 = 15.0;
+
+const BACKGROUND: [u8; 3] = [250, 250, 250];
+const MONITOR_FILL: [u8; 3] = [225, 225, 228];
+const MONITOR_EDGE: [u8; 3] = [110, 110, 120];
+const TEXT: [u8; 3] = [30, 30, 30];
+const MUTED_TEXT: [u8; 3] = [120, 120, 120];
+
+/// Colors the apps take in turn
+const PALETTE: [[u8; 3]; 8] = [
+    [66, 133, 244],
+    [219, 68, 55],
+    [15, 157, 88],
+    [244, 160, 0],
+    [171, 71, 188],
+    [0, 172, 193],
+    [255, 112, 67],
+    [124, 179, 66],
+];
+
+/// Draws where the enabled apps in `config` would go on `monitors` to a PNG
+/// at `path`: the monitors as gray rectangles, each app as a numbered colored
+/// rectangle, and a legend of the apps below them. Nothing is launched or
+/// moved, so it works without a desktop, e.g. in CI or for docs.
+pub fn render_layout(config: &Config, monitors: &[Monitor], path: &Path) -> Result<(), String> {
+    render(config, monitors)?
+        .save_png(path)
+        .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+}
+
+fn render(config: &Config, monitors: &[Monitor]) -> Result<Pixmap, String> {
+    let desktop = bounding_box(monitors).ok_or("There are no monitors to draw the layout on")?;
+    let font = FontRef::try_from_slice(epaint_default_fonts::UBUNTU_LIGHT)
+        .map_err(|e| format!("Failed to load the font: {}", e))?;
+
+    let planned: Vec<_> = plan_layout(config, monitors)
+        .into_iter()
+        .zip(&config.applications)
+        .filter(|(_, app)| app.enabled)
+        .map(|(planned, _)| planned)
+        .collect();
+
+    let scale = (MAX_WIDTH / desktop.width as f32).min(MAX_HEIGHT / desktop.height as f32);
+    let drawn_width = desktop.width as f32 * scale;
+    let drawn_height = desktop.height as f32 * scale;
+    let legend_top = drawn_height + 2.0 * MARGIN;
+    let width = (drawn_width + 2.0 * MARGIN).max(MIN_WIDTH);
+    let height = legend_top + LINE_HEIGHT * planned.len().max(1) as f32 + MARGIN;
+    let mut pixmap = Pixmap::new(width.ceil() as u32, height.ceil() as u32)
+        .ok_or("The layout is too large to draw")?;
+    pixmap.fill(color(BACKGROUND, 255));
+
+    // Virtual-desktop pixels to picture pixels
+    let to_picture = |rect: Rect| {
+        tiny_skia::Rect::from_xywh(
+            MARGIN + (rect.x - desktop.x) as f32 * scale,
+            MARGIN + (rect.y - desktop.y) as f32 * scale,
+            (rect.width as f32 * scale).max(1.0),
+            (rect.height as f32 * scale).max(1.0),
+        )
+    };
+
+    for monitor in monitors {
+        let Some(area) = to_picture(monitor.bounds) else {
+            continue;
+        };
+        fill_rect(&mut pixmap, area, MONITOR_FILL, 255);
+        stroke_rect(&mut pixmap, area, MONITOR_EDGE, 2.0);
+    }
+
+    for (number, planned) in planned.iter().enumerate() {
+        let rgb = PALETTE[number % PALETTE.len()];
+        if let Some(area) = planned.rect.and_then(to_picture) {
+            fill_rect(&mut pixmap, area, rgb, 110);
+            stroke_rect(&mut pixmap, area, rgb, 2.0);
+            draw_text(
+                &mut pixmap,
+                &font,
+                &format!("{}. {}", number + 1, planned.app_name),
+                area.left() + 6.0,
+                area.top() + 4.0,
+                area.right() - 4.0,
+                TEXT,
+            );
+        }
+
+        let top = legend_top + LINE_HEIGHT * number as f32;
+        if let Some(swatch) = tiny_skia::Rect::from_xywh(MARGIN, top + 3.0, 14.0, 14.0) {
+            fill_rect(&mut pixmap, swatch, rgb, 255);
+        }
+        let line = match planned.rect {
+            Some(rect) => format!(
+                "{}. {}: display {}, {}x{} at ({}, {}), {}",
+                number + 1,
+                planned.app_name,
+                planned.display,
+                rect.width,
+                rect.height,
+                rect.x,
+                rect.y,
+                planned.reason
+            ),
+            None => format!(
+                "{}. {}: not placed, {}",
+                number + 1,
+                planned.app_name,
+                planned.reason
+            ),
+        };
+        draw_text(
+            &mut pixmap,
+            &font,
+            &line,
+            MARGIN + 22.0,
+            top,
+            width - MARGIN,
+            TEXT,
+        );
+    }
+    // Monitor labels go over the apps so windows can't hide them
+    for monitor in monitors {
+        let Some(area) = to_picture(monitor.bounds) else {
+            continue;
+        };
+        let label = format!("Display {}: {}", monitor.index + 1, monitor.label());
+        draw_text(
+            &mut pixmap,
+            &font,
+            &label,
+            area.left() + 6.0,
+            area.bottom() - LINE_HEIGHT,
+            area.right() - 6.0,
+            MUTED_TEXT,
+        );
+    }
+    if planned.is_empty() {
+        draw_text(
+            &mut pixmap,
+            &font,
+            "No enabled applications",
+            MARGIN,
+            legend_top,
+            width - MARGIN,
+            MUTED_TEXT,
+        );
+    }
+    Ok(pixmap)
+}
+
+/// The smallest rectangle around all of `monitors`
+fn bounding_box(monitors: &[Monitor]) -> Option<Rect> {
+    let left = monitors.iter().map(|monitor| monitor.bounds.x).min()?;
+    let top = monitors.iter().map(|monitor| monitor.bounds.y).min()?;
+    let right = monitors
+        .iter()
+        .map(|monitor| monitor.bounds.right())
+        .max()?;
+    let bottom = monitors
+        .iter()
+        .map(|monitor| monitor.bounds.bottom())
+        .max()?;
+    Some(Rect::new(left, top, right - left, bottom - top))
+}
+
+fn color(rgb: [u8; 3], alpha: u8) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba8(rgb[0], rgb[1], rgb[2], alpha)
+}
+
+fn paint(rgb: [u8; 3], alpha: u8) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(color(rgb, alpha));
+    paint
+}
+
+fn fill_rect(pixmap: &mut Pixmap, area: tiny_skia::Rect, rgb: [u8; 3], alpha: u8) {
+    pixmap.fill_rect(area, &paint(rgb, alpha), Transform::identity(), None);
+}
+
+fn stroke_rect(pixmap: &mut Pixmap, area: tiny_skia::Rect, rgb: [u8; 3], width: f32) {
+    let path = PathBuilder::from_rect(area);
+    let stroke = Stroke {
+        width,
+        ..Stroke::default()
+    };
+    pixmap.stroke_path(
+        &path,
+        &paint(rgb, 255),
+        &stroke,
+        Transform::identity(),
+        None,
+    );
+}
+
+/// Writes `text` with its top-left corner at (`x`, `y`), cut off at `max_x`
+fn draw_text(
+    pixmap: &mut Pixmap,
+    font: &FontRef,
+    text: &str,
+    x: f32,
+    y: f32,
+    max_x: f32,
+    rgb: [u8; 3],
+) {
+    let scaled = font.as_scaled(PxScale::from(TEXT_SIZE));
+    let baseline = y + scaled.ascent();
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let limit = (max_x as i32).min(width);
+    let pixels = pixmap.pixels_mut();
+
+    let mut caret = x;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, id);
+        }
+        previous = Some(id);
+        let glyph = id.with_scale_and_position(scaled.scale(), point(caret, baseline));
+        caret += scaled.h_advance(id);
+        if caret > max_x {
+            break;
+        }
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= limit || py >= height {
+                return;
+            }
+            let pixel = &mut pixels[(py * width + px) as usize];
+            *pixel = blend(*pixel, rgb, coverage.clamp(0.0, 1.0));
+        });
+    }
+}
+
+/// `rgb` drawn over `pixel` with the given coverage
+fn blend(pixel: PremultipliedColorU8, rgb: [u8; 3], coverage: f32) -> PremultipliedColorU8 {
+    let mix = |over: u8, under: u8| {
+        (over as f32 * coverage + under as f32 * (1.0 - coverage)).round() as u8
+    };
+    let alpha = mix(255, pixel.alpha());
+    PremultipliedColorU8::from_rgba(
+        mix(rgb[0], pixel.red()).min(alpha),
+        mix(rgb[1], pixel.green()).min(alpha),
+        mix(rgb[2], pixel.blue()).min(alpha),
+        alpha,
+    )
+    .unwrap_or(pixel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Application, Side};
+    use crate::display::test_monitor;
+
+    #[test]
+    fn test_render_layout() {
+        let monitors = vec![
+            test_monitor(0, "Monitor1", Rect::new(0, 0, 1920, 1080)),
+            test_monitor(1, "Monitor2", Rect::new(1920, 0, 2560, 1440)),
+        ];
+        let config = Config {
+            applications: vec![
+                Application {
+                    name: "Editor".to_string(),
+                    side: Some(Side::Left),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Browser".to_string(),
+                    display: Some(2.into()),
+                    side: Some(Side::Right),
+                    ..Default::default()
+                },
+                Application {
+                    name: "Chat".to_string(),
+                    display: Some(3.into()),
+                    side: Some(Side::Left),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        // Scaled down to fit, with a legend line per app below the monitors
+        let pixmap = render(&config, &monitors).unwrap();
+        assert_eq!(pixmap.width(), 1240);
+        assert_eq!(
+            pixmap.height(),
+            (1440.0 * 1200.0 / 4480.0 + 3.0 * MARGIN + 3.0 * LINE_HEIGHT).ceil() as u32
+        );
+        // The editor is drawn in the first color over the first monitor
+        let editor = pixmap.pixel(200, 150).unwrap().demultiply();
+        assert!(editor.blue() > editor.red());
+
+        let path = std::env::temp_dir().join("jumpstart_render_test.png");
+        render_layout(&config, &monitors, &path).unwrap();
+        let saved = Pixmap::load_png(&path).unwrap();
+        assert_eq!(
+            (saved.width(), saved.height()),
+            (pixmap.width(), pixmap.height())
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(render(&config, &[]).unwrap_err().contains("no monitors"));
+    }
+}
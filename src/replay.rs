@@ -0,0 +1,212 @@
+use crate::config::Application;
+use crate::display::Rect;
+use crate::matching::MatchCriteria;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Something a launch run did, as written to the `--record-session` file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum SessionAction {
+    /// Started `app`, with its settings as they were at the time
+    Launch { app: Box<Application> },
+    /// Moved the window matching `search` to `rect`
+    Move {
+        app: String,
+        search: MatchCriteria,
+        rect: Rect,
+    },
+}
+
+/// An action and when it happened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionStep {
+    /// Milliseconds since the Unix epoch
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub action: SessionAction,
+}
+
+/// Empties the session file, so it only describes the current run
+pub fn start(path: &Path) -> Result<(), String> {
+    File::create(path)
+        .map(drop)
+        .map_err(|e| format!("Failed to create session file '{}': {}", path.display(), e))
+}
+
+/// Appends `action` to the session file as one JSON line, stamped with the
+/// current time
+pub fn record_session(path: &Path, action: SessionAction) -> Result<(), String> {
+    let at_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis() as u64);
+    let line = serde_json::to_string(&SessionStep { at_ms, action }).map_err(|e| e.to_string())?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|e| {
+            format!(
+                "Failed to write to session file '{}': {}",
+                path.display(),
+                e
+            )
+        })
+}
+
+/// Reads the steps of a session file, in the order they happened
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn load(path: &Path) -> Result<Vec<SessionStep>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read session file '{}': {}", path.display(), e))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                format!(
+                    "Invalid step on line {} of '{}': {}",
+                    index + 1,
+                    path.display(),
+                    e
+                )
+            })
+        })
+        .collect()
+}
+
+/// How long to wait before each step, so a replay keeps the recorded pacing
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn delays(steps: &[SessionStep]) -> Vec<Duration> {
+    let mut previous = steps.first().map_or(0, |step| step.at_ms);
+    steps
+        .iter()
+        .map(|step| {
+            let delay = Duration::from_millis(step.at_ms.saturating_sub(previous));
+            previous = previous.max(step.at_ms);
+            delay
+        })
+        .collect()
+}
+
+/// Performs the steps of a session file with their recorded timing,
+/// ignoring the config. Returns the number of windows moved.
+#[cfg(windows)]
+pub fn replay_session(path: &Path) -> Result<usize, String> {
+    use crate::app_launcher::{
+        DEFAULT_WINDOW_WAIT, Launcher, ShellLauncher, WINDOW_POLL_INTERVAL, poll_until,
+    };
+    use crate::matching::IgnoreList;
+    use crate::window::{find_window, position_window};
+    use tracing::{info, warn};
+
+    let steps = load(path)?;
+    info!("Replaying {} steps from '{}'", steps.len(), path.display());
+    let mut moved = 0;
+    for (step, delay) in steps.iter().zip(delays(&steps)) {
+        std::thread::sleep(delay);
+        match &step.action {
+            SessionAction::Launch { app } => match ShellLauncher::default().launch(app) {
+                Ok(_) => info!("Launched {}", app.name),
+                Err(e) => warn!("Failed to launch {}: {}", app.name, e),
+            },
+            SessionAction::Move { app, search, rect } => {
+                let window = poll_until(DEFAULT_WINDOW_WAIT, WINDOW_POLL_INTERVAL, || {
+                    find_window(search, &IgnoreList::default())
+                });
+                let Some(hwnd) = window else {
+                    warn!(
+                        "Could not find window for {} (searched for: {})",
+                        app, search
+                    );
+                    continue;
+                };
+                match position_window(hwnd, rect.x, rect.y, rect.width, rect.height, true) {
+                    Ok(()) => {
+                        info!("Moved {} to ({}, {})", app, rect.x, rect.y);
+                        moved += 1;
+                    }
+                    Err(e) => warn!("Failed to move {}: {}", app, e),
+                }
+            }
+        }
+    }
+    Ok(moved)
+}
+
+#[cfg(not(windows))]
+pub fn replay_session(_path: &Path) -> Result<usize, String> {
+    Err("Replaying a session is only supported on Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_file() {
+        let path = std::env::temp_dir().join("jumpstart_session_test.jsonl");
+        fs::write(&path, "left over from an earlier run\n").unwrap();
+        start(&path).unwrap();
+
+        let editor = Application {
+            name: "Editor".to_string(),
+            executable: "code.exe".to_string(),
+            ..Default::default()
+        };
+        record_session(
+            &path,
+            SessionAction::Launch {
+                app: Box::new(editor),
+            },
+        )
+        .unwrap();
+        record_session(
+            &path,
+            SessionAction::Move {
+                app: "Editor".to_string(),
+                search: MatchCriteria::title("Visual Studio Code"),
+                rect: Rect::new(0, 0, 960, 1040),
+            },
+        )
+        .unwrap();
+
+        let steps = load(&path).unwrap();
+        assert!(matches!(
+            &steps[0].action,
+            SessionAction::Launch { app } if app.executable == "code.exe"
+        ));
+        assert!(matches!(
+            &steps[1].action,
+            SessionAction::Move { app, rect, .. } if app == "Editor" && rect.width == 960
+        ));
+        assert!(steps[1].at_ms >= steps[0].at_ms);
+
+        fs::write(&path, "{\"at_ms\":1,\"action\":\"teleport\"}\n").unwrap();
+        assert!(load(&path).unwrap_err().contains("line 1"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_delays_keep_the_recorded_gaps() {
+        let step = |at_ms| SessionStep {
+            at_ms,
+            action: SessionAction::Move {
+                app: "Editor".to_string(),
+                search: MatchCriteria::title("Editor"),
+                rect: Rect::default(),
+            },
+        };
+        let steps = [step(1_000), step(1_250), step(3_250), step(3_000)];
+        assert_eq!(
+            delays(&steps),
+            [0, 250, 2_000, 0].map(Duration::from_millis).to_vec()
+        );
+        assert!(delays(&[]).is_empty());
+    }
+}
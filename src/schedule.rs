@@ -0,0 +1,288 @@
+use crate::config::{self, Config, LoadOptions};
+use crate::remote;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[cfg(windows)]
+use crate::watch::{self, WatchedLayout};
+#[cfg(windows)]
+use chrono::Timelike;
+#[cfg(windows)]
+use tracing::{info, warn};
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// A daily time range such as `09:00-12:00`, in minutes since midnight. The
+/// end is exclusive, and a range that ends before it starts runs past
+/// midnight, e.g. `22:00-06:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl TimeRange {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid time range '{}': expected HH:MM-HH:MM", text);
+        let (start, end) = text.split_once('-').ok_or_else(invalid)?;
+        let start = parse_time(start).filter(|&minute| minute < MINUTES_PER_DAY);
+        let (Some(start), Some(end)) = (start, parse_time(end)) else {
+            return Err(invalid());
+        };
+        if start == end {
+            return Err(format!("Time range '{}' is empty", text));
+        }
+        Ok(TimeRange { start, end })
+    }
+
+    pub fn contains(&self, minute: u16) -> bool {
+        if self.start < self.end {
+            minute >= self.start && minute < self.end
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+/// Minutes since midnight for `HH:MM`; `24:00` is allowed as the end of a day
+fn parse_time(text: &str) -> Option<u16> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let (hours, minutes) = (hours.parse::<u16>().ok()?, minutes.parse::<u16>().ok()?);
+    if minutes >= 60 || hours > 24 || (hours == 24 && minutes > 0) {
+        return None;
+    }
+    Some(hours * 60 + minutes)
+}
+
+/// Parses a config's `schedule` of time ranges to profile names, rejecting
+/// ranges that overlap so only one profile is ever active
+pub fn parse_schedule(
+    schedule: &BTreeMap<String, String>,
+) -> Result<Vec<(TimeRange, String)>, String> {
+    let entries = schedule
+        .iter()
+        .map(|(range, profile)| Ok((TimeRange::parse(range)?, profile.clone())))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let labels: Vec<&String> = schedule.keys().collect();
+    for (i, (first, _)) in entries.iter().enumerate() {
+        for (j, (second, _)) in entries.iter().enumerate().skip(i + 1) {
+            if (0..MINUTES_PER_DAY).any(|minute| first.contains(minute) && second.contains(minute))
+            {
+                return Err(format!(
+                    "Schedule ranges '{}' and '{}' overlap",
+                    labels[i], labels[j]
+                ));
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// The profile scheduled at `minute`, if any
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn active_profile(entries: &[(TimeRange, String)], minute: u16) -> Option<&str> {
+    entries
+        .iter()
+        .find(|(range, _)| range.contains(minute))
+        .map(|(_, profile)| profile.as_str())
+}
+
+/// Remembers the active profile, so each one is applied once when its time
+/// range begins
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct Scheduler {
+    entries: Vec<(TimeRange, String)>,
+    active: Option<String>,
+}
+
+impl Scheduler {
+    pub fn new(entries: Vec<(TimeRange, String)>) -> Self {
+        Scheduler {
+            entries,
+            active: None,
+        }
+    }
+
+    /// The profile to switch to at `minute`, when a range for a different
+    /// profile has begun. Leaving all ranges switches to nothing.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn due(&mut self, minute: u16) -> Option<String> {
+        let current = active_profile(&self.entries, minute).map(str::to_string);
+        if current == self.active {
+            return None;
+        }
+        self.active = current.clone();
+        current
+    }
+}
+
+/// The current local time in minutes since midnight
+#[cfg(windows)]
+pub fn current_minute() -> u16 {
+    let now = chrono::Local::now();
+    (now.hour() * 60 + now.minute()) as u16
+}
+
+/// The directory of a config's sibling profiles, e.g. for `schedule` or the
+/// control endpoint's `launch <profile>`
+pub fn profile_dir(config_path: &str) -> Result<PathBuf, String> {
+    if remote::is_url(config_path) {
+        return Err("Profiles need a local config file next to them".to_string());
+    }
+    Ok(match Path::new(config_path).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    })
+}
+
+/// Switches between the profiles in a config's `schedule`, which are the
+/// other config files in its directory
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct ScheduledLayouts {
+    scheduler: Scheduler,
+    dir: PathBuf,
+    load_options: LoadOptions,
+}
+
+impl ScheduledLayouts {
+    /// `None` when the config has no schedule. Fails when a scheduled
+    /// profile doesn't exist, rather than when its time comes.
+    pub fn new(
+        config: &Config,
+        config_path: &str,
+        load_options: &LoadOptions,
+    ) -> Result<Option<Self>, String> {
+        if config.schedule.is_empty() {
+            return Ok(None);
+        }
+        let dir = profile_dir(config_path)?;
+
+        let entries = parse_schedule(&config.schedule)?;
+        for (_, profile) in &entries {
+            config::profile_config_path(&dir, Some(profile))?;
+        }
+        Ok(Some(ScheduledLayouts {
+            scheduler: Scheduler::new(entries),
+            dir,
+            load_options: *load_options,
+        }))
+    }
+
+    /// Launches and positions the profile whose time range has just begun,
+    /// returning it to watch instead of the previous layout
+    #[cfg(windows)]
+    pub fn tick(&mut self) -> Option<WatchedLayout> {
+        let profile = self.scheduler.due(current_minute())?;
+        info!("Switching to scheduled profile '{}'", profile);
+        match self.apply(&profile) {
+            Ok(layout) => Some(layout),
+            Err(e) => {
+                warn!("Failed to apply scheduled profile '{}': {}", profile, e);
+                None
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    fn apply(&self, profile: &str) -> Result<WatchedLayout, String> {
+        let path = config::profile_config_path(&self.dir, Some(profile))?;
+        watch::apply_layout(&path.to_string_lossy(), &self.load_options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_range() {
+        let morning = TimeRange::parse("09:00-12:00").unwrap();
+        assert_eq!(
+            morning,
+            TimeRange {
+                start: 540,
+                end: 720
+            }
+        );
+        assert!(morning.contains(540));
+        assert!(morning.contains(719));
+        assert!(!morning.contains(720));
+
+        // Past midnight
+        let night = TimeRange::parse("22:00-06:00").unwrap();
+        assert!(night.contains(23 * 60));
+        assert!(night.contains(0));
+        assert!(!night.contains(6 * 60));
+
+        let evening = TimeRange::parse("18:00-24:00").unwrap();
+        assert!(evening.contains(23 * 60 + 59));
+        assert!(!evening.contains(0));
+
+        for invalid in ["9-12", "09:00", "09:60-10:00", "24:00-01:00", "25:00-26:00"] {
+            assert!(TimeRange::parse(invalid).is_err(), "{}", invalid);
+        }
+        assert!(
+            TimeRange::parse("10:00-10:00")
+                .unwrap_err()
+                .contains("empty")
+        );
+        assert!(TimeRange::parse("00:00-24:00").unwrap().contains(0));
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        let schedule = |ranges: &[(&str, &str)]| {
+            ranges
+                .iter()
+                .map(|(range, profile)| (range.to_string(), profile.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let entries = parse_schedule(&schedule(&[
+            ("09:00-12:00", "morning"),
+            ("12:00-18:00", "afternoon"),
+            ("22:00-06:00", "night"),
+        ]))
+        .unwrap();
+        assert_eq!(active_profile(&entries, 9 * 60), Some("morning"));
+        assert_eq!(active_profile(&entries, 12 * 60), Some("afternoon"));
+        assert_eq!(active_profile(&entries, 3 * 60), Some("night"));
+        assert_eq!(active_profile(&entries, 20 * 60), None);
+
+        let error = parse_schedule(&schedule(&[
+            ("09:00-12:00", "morning"),
+            ("23:00-10:00", "night"),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "Schedule ranges '09:00-12:00' and '23:00-10:00' overlap"
+        );
+    }
+
+    #[test]
+    fn test_scheduler() {
+        let entries = vec![
+            (
+                TimeRange::parse("09:00-12:00").unwrap(),
+                "morning".to_string(),
+            ),
+            (
+                TimeRange::parse("12:00-18:00").unwrap(),
+                "afternoon".to_string(),
+            ),
+        ];
+        let mut scheduler = Scheduler::new(entries);
+
+        // Outside all ranges nothing happens
+        assert_eq!(scheduler.due(8 * 60), None);
+        assert_eq!(scheduler.due(9 * 60), Some("morning".to_string()));
+        assert_eq!(scheduler.due(10 * 60), None);
+        assert_eq!(scheduler.due(12 * 60), Some("afternoon".to_string()));
+        assert_eq!(scheduler.due(19 * 60), None);
+        // The next morning applies the layout again
+        assert_eq!(scheduler.due(9 * 60), Some("morning".to_string()));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::app_launcher::LaunchCommand;
+
+/// Quotes an argument so `CommandLineToArgvW` splits it back out unchanged
+#[cfg(any(windows, test))]
+pub fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        // Backslashes only need escaping when they precede a quote
+        let escaped = if c == '"' {
+            backslashes * 2 + 1
+        } else {
+            backslashes
+        };
+        quoted.extend(std::iter::repeat_n('\\', escaped));
+        quoted.push(c);
+        backslashes = 0;
+    }
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// The full command line for `CreateProcessAsUserW`
+#[cfg(any(all(windows, feature = "user_session"), test))]
+pub fn command_line(command: &LaunchCommand) -> String {
+    // The program is always quoted so a path with spaces can't be misread
+    let mut line = format!("\"{}\"", command.program);
+    for arg in &command.args {
+        line.push(' ');
+        line.push_str(&quote_arg(arg));
+    }
+    line
+}
+
+/// Starts `command` on the desktop of the user logged on to the console,
+/// for jumpstart running as a service, e.g. on a kiosk that logs in
+/// automatically. Only works when running as LocalSystem. Unlike a normal
+/// launch there is no shell, so `program` must be an executable.
+#[cfg(all(windows, feature = "user_session"))]
+pub fn launch_in_user_session(command: &LaunchCommand) -> Result<u32, String> {
+    use std::io;
+    use std::ptr::{null, null_mut};
+    use widestring::U16CString;
+    use winapi::shared::minwindef::{DWORD, FALSE, LPVOID};
+    use winapi::shared::winerror::ERROR_PRIVILEGE_NOT_HELD;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{CreateProcessAsUserW, PROCESS_INFORMATION, STARTUPINFOW};
+    use winapi::um::userenv::{CreateEnvironmentBlock, DestroyEnvironmentBlock};
+    use winapi::um::winbase::{
+        CREATE_UNICODE_ENVIRONMENT, STARTF_USESHOWWINDOW, WTSGetActiveConsoleSessionId,
+    };
+    use winapi::um::winuser::SW_SHOWMINNOACTIVE;
+    use winapi::um::wtsapi32::WTSQueryUserToken;
+
+    let session = unsafe { WTSGetActiveConsoleSessionId() };
+    if session == 0xFFFF_FFFF {
+        return Err("No user is logged on to the console".to_string());
+    }
+
+    let mut token = null_mut();
+    if unsafe { WTSQueryUserToken(session, &mut token) } == 0 {
+        let error = io::Error::last_os_error();
+        return Err(
+            if error.raw_os_error() == Some(ERROR_PRIVILEGE_NOT_HELD as i32) {
+                "Launching in the user session requires running as LocalSystem, e.g. as a service"
+                    .to_string()
+            } else {
+                format!("Failed to get the token of session {}: {}", session, error)
+            },
+        );
+    }
+
+    let mut command_line = U16CString::from_str(command_line(command))
+        .map_err(|e| e.to_string())?
+        .into_vec_with_nul();
+    let working_dir = command
+        .working_dir
+        .as_deref()
+        .map(U16CString::from_str)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let mut desktop = U16CString::from_str("winsta0\\default")
+        .map_err(|e| e.to_string())?
+        .into_vec_with_nul();
+
+    // The user's own environment; without it the app inherits the service's
+    let mut environment: LPVOID = null_mut();
+    if unsafe { CreateEnvironmentBlock(&mut environment, token, FALSE) } == 0 {
+        environment = null_mut();
+    }
+
+    let mut startup: STARTUPINFOW = unsafe { std::mem::zeroed() };
+    startup.cb = std::mem::size_of::<STARTUPINFOW>() as DWORD;
+    startup.lpDesktop = desktop.as_mut_ptr();
+    if command.minimized {
+        startup.dwFlags = STARTF_USESHOWWINDOW;
+        startup.wShowWindow = SW_SHOWMINNOACTIVE as u16;
+    }
+    let mut process: PROCESS_INFORMATION = unsafe { std::mem::zeroed() };
+
+    let created = unsafe {
+        CreateProcessAsUserW(
+            token,
+            null(),
+            command_line.as_mut_ptr(),
+            null_mut(),
+            null_mut(),
+            FALSE,
+            CREATE_UNICODE_ENVIRONMENT,
+            environment,
+            working_dir.as_ref().map_or(null(), |dir| dir.as_ptr()),
+            &mut startup,
+            &mut process,
+        )
+    };
+    let error = io::Error::last_os_error();
+
+    unsafe {
+        if !environment.is_null() {
+            DestroyEnvironmentBlock(environment);
+        }
+        CloseHandle(token);
+    }
+
+    if created == 0 {
+        return Err(format!(
+            "Failed to launch '{}' in session {}: {}",
+            command.program, session, error
+        ));
+    }
+    unsafe {
+        CloseHandle(process.hProcess);
+        CloseHandle(process.hThread);
+    }
+    Ok(process.dwProcessId)
+}
+
+#[cfg(all(windows, not(feature = "user_session")))]
+pub fn launch_in_user_session(_command: &LaunchCommand) -> Result<u32, String> {
+    Err("jumpstart was built without the user_session feature".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn launch_in_user_session(_command: &LaunchCommand) -> Result<u32, String> {
+    Err("Launching in the user session is only supported on Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line() {
+        assert_eq!(quote_arg("--new-window"), "--new-window");
+        assert_eq!(quote_arg(""), "\"\"");
+        assert_eq!(quote_arg("C:\\My Docs\\"), "\"C:\\My Docs\\\\\"");
+        assert_eq!(quote_arg("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote_arg("a\\\"b"), "\"a\\\\\\\"b\"");
+
+        let command = LaunchCommand {
+            program: "C:\\Program Files\\Signage\\player.exe".to_string(),
+            args: vec![
+                "--fullscreen".to_string(),
+                "D:\\Shows\\Lobby Loop".to_string(),
+            ],
+            working_dir: None,
+            console: Default::default(),
+            minimized: false,
+            method: Default::default(),
+            env: Vec::new(),
+        };
+        assert_eq!(
+            command_line(&command),
+            "\"C:\\Program Files\\Signage\\player.exe\" --fullscreen \"D:\\Shows\\Lobby Loop\""
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::matching::WindowCandidate;
+use std::fs;
+use std::path::Path;
+
+/// The titled top-level windows open now, cloaked ones included, for `--dump-windows`
+#[cfg(windows)]
+pub fn current_windows() -> Result<Vec<WindowCandidate>, String> {
+    Ok(crate::window::enumerate_windows_with(true))
+}
+
+#[cfg(not(windows))]
+pub fn current_windows() -> Result<Vec<WindowCandidate>, String> {
+    Err("Listing windows is only supported on Windows".to_string())
+}
+
+/// Writes enumerated windows to `path`, one JSON object per line, for
+/// replaying them later with `--windows-from`
+pub fn save(path: &Path, windows: &[WindowCandidate]) -> Result<(), String> {
+    let mut content = String::new();
+    for window in windows {
+        content.push_str(&serde_json::to_string(window).map_err(|e| e.to_string())?);
+        content.push('\n');
+    }
+    fs::write(path, content).map_err(|e| {
+        format!(
+            "Failed to write window snapshot '{}': {}",
+            path.display(),
+            e
+        )
+    })
+}
+
+/// Reads windows written by `save`, in their original enumeration order
+pub fn load(path: &Path) -> Result<Vec<WindowCandidate>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read window snapshot '{}': {}", path.display(), e))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                format!(
+                    "Invalid window on line {} of '{}': {}",
+                    index + 1,
+                    path.display(),
+                    e
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let path = std::env::temp_dir().join("jumpstart_snapshot_test.jsonl");
+        let windows = vec![
+            WindowCandidate {
+                hwnd: 0x1001,
+                title: "Inbox - Outlook".to_string(),
+                class: "rctrl_renwnd32".to_string(),
+                cloaked: false,
+                process_id: 4242,
+            },
+            WindowCandidate {
+                hwnd: 0x1002,
+                title: "General | Slack".to_string(),
+                class: "Chrome_WidgetWin_1".to_string(),
+                cloaked: true,
+                process_id: 5151,
+            },
+        ];
+        save(&path, &windows).unwrap();
+        assert_eq!(load(&path).unwrap(), windows);
+
+        // Hand-written snapshots may leave out the class
+        fs::write(
+            &path,
+            "{\"hwnd\":1,\"title\":\"Notes\",\"process_id\":7}\n\nnot json\n",
+        )
+        .unwrap();
+        let error = load(&path).unwrap_err();
+        assert!(error.contains("line 3"), "{}", error);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
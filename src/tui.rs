@@ -0,0 +1,232 @@
+use crate::app_launcher::{self, AppStatus, LaunchProgress};
+use crate::config::{Config, DisplaySelector, LoadOptions, load_config};
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+/// How often the event loop wakes up to drain launch progress when idle
+const TICK: Duration = Duration::from_millis(100);
+
+struct TuiState {
+    config_path: String,
+    config: Config,
+    app_status: Vec<AppStatus>,
+    list_state: ListState,
+    progress_rx: Option<Receiver<LaunchProgress>>,
+    status_message: String,
+    /// Display each app goes on, with `auto_distribute` applied
+    displays: Vec<DisplaySelector>,
+}
+
+impl TuiState {
+    fn new(config_path: String, config: Config) -> Self {
+        let mut list_state = ListState::default();
+        if !config.applications.is_empty() {
+            list_state.select(Some(0));
+        }
+        Self {
+            app_status: vec![AppStatus::Idle; config.applications.len()],
+            displays: config.assign_displays(app_launcher::monitors().len()),
+            config_path,
+            config,
+            list_state,
+            progress_rx: None,
+            status_message: "Press Enter to launch, Space to toggle an app, q to quit".to_string(),
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.progress_rx.is_some()
+    }
+
+    fn toggle_selected(&mut self) {
+        if self.is_running() {
+            self.status_message = "Cannot change apps while a launch is running".to_string();
+            return;
+        }
+        if let Some(app) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.config.applications.get_mut(i))
+        {
+            app.enabled = !app.enabled;
+            self.status_message = format!(
+                "{} {}",
+                app.name,
+                if app.enabled { "enabled" } else { "disabled" }
+            );
+        }
+    }
+
+    fn start_launch(&mut self) {
+        if self.is_running() {
+            self.status_message = "Applications are already being launched".to_string();
+            return;
+        }
+        self.app_status = vec![AppStatus::Idle; self.config.applications.len()];
+        let (tx, rx) = mpsc::channel();
+        self.progress_rx = Some(rx);
+        self.status_message = "Launching applications...".to_string();
+        app_launcher::spawn_launch(self.config.clone(), tx);
+    }
+
+    fn poll_progress(&mut self) {
+        let Some(ref rx) = self.progress_rx else {
+            return;
+        };
+
+        let mut completed = None;
+        while let Ok(event) = rx.try_recv() {
+            AppStatus::apply(&mut self.app_status, &event);
+            match event {
+                LaunchProgress::Started { name, .. } => {
+                    self.status_message = format!("Launching {}...", name);
+                }
+                LaunchProgress::Completed(result) => completed = Some(result),
+                LaunchProgress::Finished { .. } => {}
+            }
+        }
+
+        if let Some(result) = completed {
+            self.status_message = match result {
+                Ok(report) => format!(
+                    "Done: {} of {} applications positioned",
+                    report.positioned_count(),
+                    report.results.len()
+                ),
+                Err(e) => format!("Error launching applications: {}", e),
+            };
+            self.progress_rx = None;
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let len = self.config.applications.len();
+        if len == 0 {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).rem_euclid(len as isize) as usize;
+        self.list_state.select(Some(next));
+    }
+}
+
+/// Runs the terminal UI until the user quits
+pub fn run_tui(
+    config_path: String,
+    load_options: LoadOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config(&config_path, &load_options)?;
+    let mut state = TuiState::new(config_path, config);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut state);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    state: &mut TuiState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        state.poll_progress();
+        terminal.draw(|frame| draw(frame, state))?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => state.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => state.move_selection(1),
+            KeyCode::Char(' ') => state.toggle_selected(),
+            KeyCode::Enter | KeyCode::Char('l') => state.start_launch(),
+            _ => {}
+        }
+    }
+}
+
+fn status_span(status: &AppStatus) -> Span<'static> {
+    match status {
+        AppStatus::Idle => Span::raw(""),
+        AppStatus::Launching => Span::styled("launching...", Style::default().fg(Color::Yellow)),
+        AppStatus::Done(outcome) if outcome.is_failure() => {
+            Span::styled(outcome.to_string(), Style::default().fg(Color::Red))
+        }
+        AppStatus::Done(outcome) => {
+            Span::styled(outcome.to_string(), Style::default().fg(Color::Green))
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, state: &mut TuiState) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(1),
+        Constraint::Length(3),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(
+        Paragraph::new(format!("Config: {}", state.config_path)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Jumpstart Application Launcher"),
+        ),
+        header,
+    );
+
+    let items: Vec<ListItem> = state
+        .config
+        .applications
+        .iter()
+        .zip(&state.app_status)
+        .zip(&state.displays)
+        .map(|((app, status), display)| {
+            let checkbox = if app.enabled { "[x]" } else { "[ ]" };
+            let name_style = if app.enabled {
+                Style::default()
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{} ", checkbox)),
+                Span::styled(format!("{:<24}", app.name), name_style),
+                Span::raw(format!(
+                    " {} {:<6} ",
+                    display.short_label(),
+                    format!("{:?}", app.side())
+                )),
+                status_span(status),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Applications"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, body, &mut state.list_state);
+
+    frame.render_widget(
+        Paragraph::new(state.status_message.as_str()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("↑/↓ select · Space toggle · Enter launch · q quit"),
+        ),
+        footer,
+    );
+}
@@ -0,0 +1,144 @@
+use crate::display::Rect;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(windows)]
+use crate::display::clamp_to_visible;
+#[cfg(windows)]
+use crate::monitor::get_monitors;
+#[cfg(windows)]
+use crate::window::{is_window, position_window};
+#[cfg(windows)]
+use tracing::{info, warn};
+
+/// Where a window was before a launch moved it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WindowSnapshot {
+    pub name: String,
+    /// Raw window handle; only meaningful while the window stays open
+    pub hwnd: usize,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Window positions captured before the most recent launch
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UndoSnapshot {
+    pub windows: Vec<WindowSnapshot>,
+}
+
+impl UndoSnapshot {
+    pub fn record(&mut self, name: &str, hwnd: usize, rect: Rect) {
+        self.windows.push(WindowSnapshot {
+            name: name.to_string(),
+            hwnd,
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        });
+    }
+
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+        }
+        let content = serde_yaml::to_string(self)
+            .map_err(|e| format!("Failed to serialize undo snapshot: {}", e))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+    }
+
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("No undo snapshot at '{}': {}", path.display(), e))?;
+        serde_yaml::from_str(&content).map_err(|e| format!("Failed to parse undo snapshot: {}", e))
+    }
+}
+
+/// Location of the persisted snapshot, so undo survives a restart
+pub fn snapshot_path() -> PathBuf {
+    std::env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("jumpstart")
+        .join("undo.yml")
+}
+
+pub fn has_snapshot() -> bool {
+    snapshot_path().is_file()
+}
+
+/// Moves every window in the last snapshot back to where it was, then
+/// discards the snapshot. Returns the number of windows restored.
+#[cfg(windows)]
+pub fn undo_last_launch() -> Result<usize, String> {
+    let path = snapshot_path();
+    let restored = restore(&UndoSnapshot::load_from(&path)?);
+    fs::remove_file(&path).map_err(|e| format!("Failed to remove '{}': {}", path.display(), e))?;
+    Ok(restored)
+}
+
+/// Moves the snapshot's windows that are still open back to where they
+/// were, returning how many were restored
+#[cfg(windows)]
+pub fn restore(snapshot: &UndoSnapshot) -> usize {
+    let monitors = get_monitors();
+    let mut restored = 0;
+    for window in &snapshot.windows {
+        let hwnd = window.hwnd as winapi::shared::windef::HWND;
+        if !is_window(hwnd) {
+            warn!(
+                "{} was closed since the launch; nothing to restore",
+                window.name
+            );
+            continue;
+        }
+        // A monitor the window was on may have been unplugged since
+        let rect = clamp_to_visible(
+            Rect::new(window.x, window.y, window.width, window.height),
+            &monitors,
+        );
+        match position_window(hwnd, rect.x, rect.y, rect.width, rect.height, true) {
+            Ok(()) => {
+                info!("Restored {} to ({}, {})", window.name, rect.x, rect.y);
+                restored += 1;
+            }
+            Err(e) => warn!("Failed to restore {}: {}", window.name, e),
+        }
+    }
+    restored
+}
+
+#[cfg(not(windows))]
+pub fn undo_last_launch() -> Result<usize, String> {
+    Err("Undo is only supported on Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut snapshot = UndoSnapshot::default();
+        snapshot.record("Editor", 0x1234, Rect::new(10, 20, 800, 600));
+        snapshot.record("Chat", 0x5678, Rect::new(-1920, 0, 960, 1040));
+
+        let path = std::env::temp_dir()
+            .join("jumpstart_undo_test")
+            .join("undo.yml");
+        snapshot.save_to(&path).unwrap();
+        let loaded = UndoSnapshot::load_from(&path).unwrap();
+        assert_eq!(loaded, snapshot);
+        assert_eq!(loaded.windows[1].x, -1920);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert!(UndoSnapshot::load_from(&path).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use winapi::shared::windef::HWND;
+
+/// Tells windows parked on another virtual desktop apart from other cloaked
+/// windows. Windows cloaks them while their desktop isn't shown, so to the
+/// enumeration they look like a suspended Store app's background window.
+#[cfg(feature = "virtual_desktops")]
+pub struct DesktopManager {
+    manager: *mut winapi::um::shobjidl_core::IVirtualDesktopManager,
+    /// Whether `new` initialized COM on this thread and has to undo it
+    uninitialize: bool,
+}
+
+#[cfg(feature = "virtual_desktops")]
+impl DesktopManager {
+    /// Connects to the shell's virtual desktop manager, or `None` when
+    /// Windows doesn't have one
+    pub fn new() -> Option<Self> {
+        use std::ptr;
+        use winapi::Interface;
+        use winapi::shared::winerror::{RPC_E_CHANGED_MODE, SUCCEEDED};
+        use winapi::um::combaseapi::{
+            CLSCTX_ALL, CoCreateInstance, CoInitializeEx, CoUninitialize,
+        };
+        use winapi::um::objbase::COINIT_APARTMENTTHREADED;
+        use winapi::um::shobjidl_core::{CLSID_VirtualDesktopManager, IVirtualDesktopManager};
+
+        let result = unsafe { CoInitializeEx(ptr::null_mut(), COINIT_APARTMENTTHREADED) };
+        // A thread already set up for another threading model can still use COM
+        let uninitialize = SUCCEEDED(result);
+        if !uninitialize && result != RPC_E_CHANGED_MODE {
+            return None;
+        }
+
+        let mut manager: *mut IVirtualDesktopManager = ptr::null_mut();
+        let result = unsafe {
+            CoCreateInstance(
+                &CLSID_VirtualDesktopManager,
+                ptr::null_mut(),
+                CLSCTX_ALL,
+                &IVirtualDesktopManager::uuidof(),
+                &mut manager as *mut *mut IVirtualDesktopManager as *mut _,
+            )
+        };
+        if !SUCCEEDED(result) || manager.is_null() {
+            if uninitialize {
+                unsafe { CoUninitialize() };
+            }
+            return None;
+        }
+        Some(Self {
+            manager,
+            uninitialize,
+        })
+    }
+
+    /// Whether the window belongs to a virtual desktop other than the one shown
+    pub fn is_on_other_desktop(&self, hwnd: HWND) -> bool {
+        use winapi::shared::guiddef::GUID;
+        use winapi::shared::minwindef::{BOOL, FALSE, TRUE};
+        use winapi::shared::winerror::SUCCEEDED;
+
+        let manager = unsafe { &*self.manager };
+        let mut on_current: BOOL = TRUE;
+        if !SUCCEEDED(unsafe { manager.IsWindowOnCurrentVirtualDesktop(hwnd, &mut on_current) })
+            || on_current != FALSE
+        {
+            return false;
+        }
+        // Windows that belong to no desktop at all aren't on the current one either
+        let mut desktop: GUID = unsafe { std::mem::zeroed() };
+        SUCCEEDED(unsafe { manager.GetWindowDesktopId(hwnd, &mut desktop) })
+            && (desktop.Data1, desktop.Data2, desktop.Data3, desktop.Data4) != (0, 0, 0, [0; 8])
+    }
+}
+
+#[cfg(feature = "virtual_desktops")]
+impl Drop for DesktopManager {
+    fn drop(&mut self) {
+        unsafe {
+            (*self.manager).Release();
+            if self.uninitialize {
+                winapi::um::combaseapi::CoUninitialize();
+            }
+        }
+    }
+}
+
+/// Without the `virtual_desktops` feature, windows on other virtual desktops
+/// are treated like any other cloaked window
+#[cfg(not(feature = "virtual_desktops"))]
+pub struct DesktopManager;
+
+#[cfg(not(feature = "virtual_desktops"))]
+impl DesktopManager {
+    pub fn new() -> Option<Self> {
+        None
+    }
+
+    pub fn is_on_other_desktop(&self, _hwnd: HWND) -> bool {
+        false
+    }
+}
+
+/// Whether the window belongs to a virtual desktop other than the one shown,
+/// in which case activating it would switch desktops
+pub fn is_on_other_desktop(hwnd: HWND) -> bool {
+    DesktopManager::new().is_some_and(|desktops| desktops.is_on_other_desktop(hwnd))
+}
@@ -0,0 +1,197 @@
+use crate::app_launcher::{self, LaunchOptions, ShellLauncher};
+use crate::config::{LoadOptions, load_config};
+use crate::control::ControlServer;
+use crate::display::Rect;
+use crate::matching::{IgnoreList, MatchCriteria};
+use crate::schedule::ScheduledLayouts;
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use std::time::Duration;
+
+/// How often `--watch` checks whether a positioned app has restarted
+#[cfg(windows)]
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A window a launch put in place, remembered so it can be put back after
+/// its app restarts, e.g. to install an update
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedWindow {
+    pub app: String,
+    pub hwnd: usize,
+    /// What finds the app's window
+    pub search: MatchCriteria,
+    pub rect: Rect,
+}
+
+/// The config whose windows are being watched
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WatchedLayout {
+    pub config_path: String,
+    pub windows: Vec<TrackedWindow>,
+    /// The config's windows to pass over when looking for a restarted app
+    pub ignore: IgnoreList,
+}
+
+/// Launches and positions the config at `config_path` while watching,
+/// returning the layout to watch instead of the previous one
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn apply_layout(
+    config_path: &str,
+    load_options: &LoadOptions,
+) -> Result<WatchedLayout, String> {
+    let config = load_config(config_path, load_options)?;
+    // Apps shared with the previous layout are moved rather than started twice
+    let options = LaunchOptions {
+        reposition_existing: true,
+        ..Default::default()
+    };
+    let launcher = ShellLauncher::for_config(&config);
+    let report = app_launcher::launch_and_position_applications(&config, &launcher, &options)?;
+    Ok(WatchedLayout {
+        config_path: config_path.to_string(),
+        windows: report.windows,
+        ignore: config.ignore_list(),
+    })
+}
+
+/// One watch tick. When a tracked window has closed and a new one matching
+/// its search shows up, `reposition` moves the new window to the same place
+/// and it is tracked instead. Apps that haven't come back yet are tried again
+/// on the next tick. Returns the apps that were repositioned.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn reconcile(
+    tracked: &mut [TrackedWindow],
+    is_open: impl Fn(usize) -> bool,
+    find: impl Fn(&MatchCriteria) -> Option<usize>,
+    mut reposition: impl FnMut(usize, Rect) -> Result<(), String>,
+) -> Vec<String> {
+    let mut repositioned = Vec::new();
+    for index in 0..tracked.len() {
+        if is_open(tracked[index].hwnd) {
+            continue;
+        }
+        let Some(hwnd) = find(&tracked[index].search) else {
+            continue;
+        };
+        // Another tracked app's window isn't a restart of this one
+        if tracked.iter().any(|window| window.hwnd == hwnd) {
+            continue;
+        }
+
+        let window = &mut tracked[index];
+        match reposition(hwnd, window.rect) {
+            Ok(()) => {
+                info!(
+                    "{} restarted; moved its new window back into place",
+                    window.app
+                );
+                window.hwnd = hwnd;
+                repositioned.push(window.app.clone());
+            }
+            Err(e) => warn!("Failed to reposition restarted {}: {}", window.app, e),
+        }
+    }
+    repositioned
+}
+
+/// Keeps the launched windows in place across app restarts until the
+/// process is stopped. With a `schedule`, each profile's layout is applied
+/// when its time range begins and its windows are watched instead. With a
+/// `control` server, its commands are handled as they arrive.
+#[cfg(windows)]
+pub fn watch(
+    mut layout: WatchedLayout,
+    mut schedule: Option<ScheduledLayouts>,
+    control: Option<ControlServer>,
+) {
+    use crate::window::{find_window, is_window, position_window};
+    use std::time::Instant;
+    use winapi::shared::windef::HWND;
+
+    info!(
+        "Watching {} windows for restarted apps; press Ctrl+C to stop",
+        layout.windows.len()
+    );
+    let mut next_check = Instant::now() + WATCH_INTERVAL;
+    loop {
+        let wait = next_check.saturating_duration_since(Instant::now());
+        match control {
+            Some(ref control) => {
+                if let Some(request) = control.next_request(wait) {
+                    control.respond(request, &mut layout);
+                    continue;
+                }
+            }
+            None => std::thread::sleep(wait),
+        }
+        next_check = Instant::now() + WATCH_INTERVAL;
+
+        if let Some(next) = schedule.as_mut().and_then(ScheduledLayouts::tick) {
+            layout = next;
+        }
+        reconcile(
+            &mut layout.windows,
+            |hwnd| is_window(hwnd as HWND),
+            |search| find_window(search, &layout.ignore).map(|hwnd| hwnd as usize),
+            |hwnd, rect| {
+                position_window(hwnd as HWND, rect.x, rect.y, rect.width, rect.height, true)
+            },
+        );
+    }
+}
+
+#[cfg(not(windows))]
+pub fn watch(
+    _layout: WatchedLayout,
+    _schedule: Option<ScheduledLayouts>,
+    _control: Option<ControlServer>,
+) {
+    warn!("Watching windows is only supported on Windows.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_reconcile() {
+        let tracked = |app: &str, hwnd: usize, x: i32| TrackedWindow {
+            app: app.to_string(),
+            hwnd,
+            search: MatchCriteria::title(&app.to_lowercase()),
+            rect: Rect::new(x, 0, 960, 1040),
+        };
+        let mut windows = vec![tracked("Teams", 1, 0), tracked("Slack", 2, 960)];
+        let open = RefCell::new(HashSet::from([1, 2]));
+        let moves = RefCell::new(Vec::new());
+        let tick = |windows: &mut Vec<TrackedWindow>, found: Option<usize>| {
+            reconcile(
+                windows,
+                |hwnd| open.borrow().contains(&hwnd),
+                |search| found.filter(|_| *search == MatchCriteria::title("teams")),
+                |hwnd, rect| {
+                    moves.borrow_mut().push((hwnd, rect.x));
+                    Ok(())
+                },
+            )
+        };
+
+        assert!(tick(&mut windows, None).is_empty());
+
+        // Teams closes to update and isn't back yet
+        open.borrow_mut().remove(&1);
+        assert!(tick(&mut windows, None).is_empty());
+        // Slack's window matching the search is not a restarted Teams
+        assert!(tick(&mut windows, Some(2)).is_empty());
+
+        // The updated Teams opens a new window, which goes back to Teams' slot
+        open.borrow_mut().insert(7);
+        assert_eq!(tick(&mut windows, Some(7)), vec!["Teams"]);
+        assert_eq!(windows[0].hwnd, 7);
+        assert_eq!(*moves.borrow(), vec![(7, 0)]);
+        assert!(tick(&mut windows, Some(7)).is_empty());
+    }
+}
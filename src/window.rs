@@ -1,32 +1,46 @@
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Instant;
+use crate::display::{Rect, ShowState};
+use crate::identity::{WindowIdentity, most_like};
+use crate::matching::{
+    FoundWindow, IgnoreList, MatchCriteria, MatchStrategy, VERBOSE_MATCHING_TARGET,
+    WindowCandidate, describe_candidate, find_in_process_tree, find_largest_candidate,
+    process_tree, ready_candidates,
+};
+use crate::virtual_desktop::DesktopManager;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 use widestring::U16CString;
 use winapi::shared::minwindef::{BOOL, DWORD, LPARAM, TRUE};
-use winapi::shared::windef::HWND;
+use winapi::shared::windef::{HWND, RECT};
 use winapi::um::winuser::{
-    EnumWindows, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, HWND_TOP,
-    SWP_NOZORDER, SetWindowPos,
+    EnumWindows, GetClassNameW, GetForegroundWindow, GetWindowRect, GetWindowTextLengthW,
+    GetWindowTextW, GetWindowThreadProcessId, HWND_TOP, IsIconic, IsWindow, IsWindowVisible,
+    IsZoomed, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE, SW_SHOWNOACTIVATE, SWP_NOACTIVATE, SWP_NOMOVE,
+    SWP_NOSIZE, SWP_NOZORDER, SetForegroundWindow, SetWindowPos, SetWindowTextW, ShowWindow,
 };
 
-#[derive(Debug)]
-struct WindowInfo {
-    hwnd: HWND,
-    title: String,
-    _process_id: u32,
-}
+/// How long a single window enumeration may take before it is cut short
+const ENUM_TIMEOUT: Duration = Duration::from_secs(5);
 
-// Global timeout flag for window enumeration
-static ENUM_TIMEOUT: AtomicBool = AtomicBool::new(false);
+/// Per-call enumeration state, so enumerations on different threads
+/// (e.g. a background prefetch) don't share a timeout flag
+struct EnumContext {
+    windows: Vec<WindowCandidate>,
+    include_cloaked: bool,
+    deadline: Instant,
+    timed_out: bool,
+    /// Set when windows on other virtual desktops can be told apart
+    desktops: Option<DesktopManager>,
+}
 
 unsafe extern "system" fn enum_windows_proc(hwnd: HWND, data: LPARAM) -> BOOL {
+    let context = unsafe { &mut *(data as *mut EnumContext) };
+
     // Check if we've timed out
-    if ENUM_TIMEOUT.load(Ordering::Relaxed) {
+    if Instant::now() > context.deadline {
+        context.timed_out = true;
         return 0; // FALSE equivalent to stop enumeration
     }
 
-    let windows = data as *mut Vec<WindowInfo>;
-
     let mut process_id: DWORD = 0;
     unsafe {
         GetWindowThreadProcessId(hwnd, &mut process_id);
@@ -34,9 +48,10 @@ unsafe extern "system" fn enum_windows_proc(hwnd: HWND, data: LPARAM) -> BOOL {
 
     let text_len = unsafe { GetWindowTextLengthW(hwnd) };
 
-    // Limit the maximum title length to prevent issues with extremely long titles
-    const MAX_TITLE_LENGTH: usize = 1024;
-    if text_len > 0 && text_len <= MAX_TITLE_LENGTH as i32 {
+    // Extremely long titles are truncated; the start is what identifies the app
+    const MAX_TITLE_LENGTH: i32 = 1024;
+    if text_len > 0 {
+        let text_len = text_len.min(MAX_TITLE_LENGTH);
         let mut buffer = vec![0; text_len as usize + 1];
 
         unsafe {
@@ -49,71 +64,239 @@ unsafe extern "system" fn enum_windows_proc(hwnd: HWND, data: LPARAM) -> BOOL {
                 .to_string()
         };
 
-        if !title.is_empty() {
-            let window_info = WindowInfo {
-                hwnd,
+        // Positioning a cloaked window has no visible effect, except that
+        // windows on another virtual desktop show up there once it is shown
+        let cloaked = is_cloaked(hwnd)
+            && !context
+                .desktops
+                .as_ref()
+                .is_some_and(|desktops| desktops.is_on_other_desktop(hwnd));
+        if !title.is_empty() && (context.include_cloaked || !cloaked) {
+            context.windows.push(WindowCandidate {
+                hwnd: hwnd as usize,
                 title,
-                _process_id: process_id,
-            };
-            unsafe {
-                (*windows).push(window_info);
-            }
+                class: class_name(hwnd),
+                cloaked,
+                process_id,
+            });
         }
     }
 
     TRUE
 }
 
-pub fn find_window_by_title(partial_title: &str) -> Option<HWND> {
-    let mut windows: Vec<WindowInfo> = Vec::new();
+/// The window's class name; 256 characters is the documented maximum
+fn class_name(hwnd: HWND) -> String {
+    let mut buffer = [0u16; 256];
+    let len = unsafe { GetClassNameW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32) };
+    String::from_utf16_lossy(&buffer[..len.max(0) as usize])
+}
+
+/// Whether the window manager hides the window although it is visible, as
+/// it does with a suspended UWP app's windows
+fn is_cloaked(hwnd: HWND) -> bool {
+    use winapi::um::dwmapi::{DWMWA_CLOAKED, DwmGetWindowAttribute};
+
+    let mut cloaked: DWORD = 0;
+    let result = unsafe {
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut DWORD as *mut _,
+            std::mem::size_of::<DWORD>() as DWORD,
+        )
+    };
+    result == 0 && cloaked != 0
+}
 
-    // Reset the timeout flag
-    ENUM_TIMEOUT.store(false, Ordering::Relaxed);
+/// Lists the titled top-level windows, leaving out cloaked ones
+pub fn enumerate_windows() -> Vec<WindowCandidate> {
+    enumerate_windows_with(false)
+}
 
-    // Start timeout timer (5 seconds)
-    let start_time = Instant::now();
-    const ENUM_TIMEOUT_MS: u128 = 5000; // 5 seconds
+/// Lists the titled top-level windows, including cloaked ones when asked
+pub fn enumerate_windows_with(include_cloaked: bool) -> Vec<WindowCandidate> {
+    let mut context = EnumContext {
+        windows: Vec::new(),
+        include_cloaked,
+        deadline: Instant::now() + ENUM_TIMEOUT,
+        timed_out: false,
+        desktops: DesktopManager::new(),
+    };
 
     unsafe {
         EnumWindows(
             Some(enum_windows_proc),
-            &mut windows as *mut Vec<WindowInfo> as LPARAM,
+            &mut context as *mut EnumContext as LPARAM,
+        );
+    }
+
+    if context.timed_out {
+        warn!(
+            "Window enumeration timed out after {} ms",
+            ENUM_TIMEOUT.as_millis()
         );
     }
 
-    // Check if we timed out during enumeration
-    if start_time.elapsed().as_millis() > ENUM_TIMEOUT_MS {
-        ENUM_TIMEOUT.store(true, Ordering::Relaxed);
-        warn!("Window enumeration timed out after {} ms", ENUM_TIMEOUT_MS);
+    context.windows
+}
+
+pub fn find_window(search: &MatchCriteria, ignore: &IgnoreList) -> Option<HWND> {
+    find_in_windows(&enumerate_windows(), search, None, ignore, None)
+        .map(|found| found.hwnd as HWND)
+}
+
+/// Logs every window of an enumeration for `--verbose-matching`
+fn log_windows(windows: &[WindowCandidate]) {
+    if !tracing::enabled!(target: VERBOSE_MATCHING_TARGET, tracing::Level::DEBUG) {
+        return;
+    }
+    for window in windows {
+        let visible = is_window_visible(window.hwnd as HWND);
+        debug!(
+            target: VERBOSE_MATCHING_TARGET,
+            "  {:#x}: {}",
+            window.hwnd,
+            describe_candidate(window, visible)
+        );
     }
+}
 
+/// Finds the first window in an enumeration that matches `search` and whose
+/// title contains `ready_title` when given, skipping ignored windows. When
+/// several match, the one most like the `remembered` window wins.
+pub fn find_in_windows(
+    windows: &[WindowCandidate],
+    search: &MatchCriteria,
+    ready_title: Option<&str>,
+    ignore: &IgnoreList,
+    remembered: Option<&WindowIdentity>,
+) -> Option<FoundWindow> {
     debug!(
         "Enumerated {} windows, searching for '{}'",
         windows.len(),
-        partial_title
+        search
     );
+    log_windows(windows);
 
-    for window in windows {
-        if window
-            .title
-            .to_lowercase()
-            .contains(&partial_title.to_lowercase())
-        {
+    let matching: Vec<&WindowCandidate> =
+        ready_candidates(windows, search, ready_title, ignore).collect();
+    let window = match remembered {
+        Some(remembered) => most_like(&matching, remembered, |candidate| {
+            process_image_path(candidate.process_id)
+        }),
+        None => matching.first().copied(),
+    };
+    if matching.len() > 1 && window != matching.first().copied() {
+        info!(
+            "{} windows match '{}'; picking the one most like last time's",
+            matching.len(),
+            search
+        );
+    }
+    match window {
+        Some(window) => {
+            let found = FoundWindow::new(window, search);
             info!(
-                "Found matching window: '{}' for search '{}'",
-                window.title, partial_title
+                "Found matching window: '{}' via {}",
+                found.title, found.strategy
             );
-            return Some(window.hwnd);
+            Some(found)
+        }
+        None => {
+            debug!("No window found matching '{}'", search);
+            None
         }
     }
+}
 
-    debug!("No window found matching '{}'", partial_title);
-    None
+/// Finds the largest window in an enumeration that matches `search`, since
+/// an app's main window is usually bigger than its popups and tool windows
+pub fn find_largest_in_windows(
+    windows: &[WindowCandidate],
+    search: &MatchCriteria,
+    ignore: &IgnoreList,
+) -> Option<FoundWindow> {
+    log_windows(windows);
+    let area = |window: &WindowCandidate| {
+        get_window_rect(window.hwnd as HWND)
+            .map(|rect| i64::from(rect.width) * i64::from(rect.height))
+            .unwrap_or(0)
+    };
+    let window = find_largest_candidate(windows, search, ignore, area)?;
+    let found = FoundWindow::new(window, search);
+    info!(
+        "Found largest matching window: '{}' via {}",
+        found.title, found.strategy
+    );
+    Some(found)
 }
 
-pub fn position_window(hwnd: HWND, x: i32, y: i32, width: i32, height: i32) -> Result<(), String> {
+/// Every running process as a (process ID, parent ID) pair
+fn process_parents() -> Vec<(u32, u32)> {
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::tlhelp32::{
+        CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+        TH32CS_SNAPPROCESS,
+    };
+
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+    if snapshot == INVALID_HANDLE_VALUE {
+        warn!("Failed to list running processes");
+        return Vec::new();
+    }
+    let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+    entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as DWORD;
+    let mut processes = Vec::new();
+    let mut more = unsafe { Process32FirstW(snapshot, &mut entry) } != 0;
+    while more {
+        processes.push((entry.th32ProcessID, entry.th32ParentProcessID));
+        more = unsafe { Process32NextW(snapshot, &mut entry) } != 0;
+    }
+    unsafe {
+        CloseHandle(snapshot);
+    }
+    processes
+}
+
+/// Finds a window owned by `root_pid` or, failing that, by a process it
+/// started, e.g. the helper a launcher or updater hands off to. Titles are
+/// not checked, so this is a fallback for when matching found nothing.
+pub fn find_window_by_process_tree(
+    root_pid: u32,
+    windows: &[WindowCandidate],
+    ignore: &IgnoreList,
+) -> Option<FoundWindow> {
+    let tree = process_tree(root_pid, &process_parents());
+    debug!("Process tree of {}: {:?}", root_pid, tree);
+    let window = find_in_process_tree(windows, &tree, ignore)?;
+    info!(
+        "Found window '{}' of process {} started by {}",
+        window.title, window.process_id, root_pid
+    );
+    Some(FoundWindow {
+        hwnd: window.hwnd,
+        title: window.title.clone(),
+        strategy: MatchStrategy::ProcessTree(root_pid),
+    })
+}
+
+/// Moves and resizes the window, also activating it when `activate` is set
+pub fn position_window(
+    hwnd: HWND,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    activate: bool,
+) -> Result<(), String> {
+    let flags = if activate {
+        SWP_NOZORDER
+    } else {
+        SWP_NOZORDER | SWP_NOACTIVATE
+    };
     unsafe {
-        if SetWindowPos(hwnd, HWND_TOP, x, y, width, height, SWP_NOZORDER) != 0 {
+        if SetWindowPos(hwnd, HWND_TOP, x, y, width, height, flags) != 0 {
             Ok(())
         } else {
             Err("Failed to position window".to_string())
@@ -121,18 +304,272 @@ pub fn position_window(hwnd: HWND, x: i32, y: i32, width: i32, height: i32) -> R
     }
 }
 
+/// Stacks the windows top to bottom in the given order, the first one above
+/// all other windows, without moving, resizing or activating them
+pub fn set_window_zorder(windows_in_order: &[HWND]) -> Result<(), String> {
+    let mut insert_after = HWND_TOP;
+    for &hwnd in windows_in_order {
+        let flags = SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE;
+        if unsafe { SetWindowPos(hwnd, insert_after, 0, 0, 0, 0, flags) } == 0 {
+            return Err(format!(
+                "Failed to set the stacking order: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        insert_after = hwnd;
+    }
+    Ok(())
+}
+
+/// Intermediate `(x, y, width, height)` rectangles moving linearly from `from`
+/// to `to`; the last step is exactly `to`
+pub fn interpolate_steps(
+    from: (i32, i32, i32, i32),
+    to: (i32, i32, i32, i32),
+    steps: u32,
+) -> Vec<(i32, i32, i32, i32)> {
+    let steps = steps.max(1);
+    let lerp = |a: i32, b: i32, step: u32| {
+        a + ((b - a) as f64 * step as f64 / steps as f64).round() as i32
+    };
+    (1..=steps)
+        .map(|step| {
+            (
+                lerp(from.0, to.0, step),
+                lerp(from.1, to.1, step),
+                lerp(from.2, to.2, step),
+                lerp(from.3, to.3, step),
+            )
+        })
+        .collect()
+}
+
+/// Moves the window to `target` in `steps` `SetWindowPos` calls spread over
+/// `total_ms`. Some apps snap back after a single instant move but keep a
+/// position reached this way.
+pub fn position_window_smooth(
+    hwnd: HWND,
+    target: (i32, i32, i32, i32),
+    steps: u32,
+    total_ms: u64,
+    activate: bool,
+) -> Result<(), String> {
+    let Some(rect) = get_window_rect(hwnd) else {
+        let (x, y, width, height) = target;
+        return position_window(hwnd, x, y, width, height, activate);
+    };
+    let from = (rect.x, rect.y, rect.width, rect.height);
+
+    let path = interpolate_steps(from, target, steps);
+    let delay = Duration::from_millis(total_ms / path.len() as u64);
+    for (i, (x, y, width, height)) in path.iter().copied().enumerate() {
+        position_window(hwnd, x, y, width, height, activate)?;
+        if i + 1 < path.len() {
+            std::thread::sleep(delay);
+        }
+    }
+    Ok(())
+}
+
+/// Restores a minimized or maximized window to its normal size without
+/// activating it
+pub fn restore_without_activating(hwnd: HWND) {
+    unsafe {
+        if IsIconic(hwnd) != 0 || IsZoomed(hwnd) != 0 {
+            ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+        }
+    }
+}
+
+/// Whether the window is minimized, maximized or shown normally
+pub fn show_state(hwnd: HWND) -> ShowState {
+    unsafe {
+        if IsIconic(hwnd) != 0 {
+            ShowState::Minimized
+        } else if IsZoomed(hwnd) != 0 {
+            ShowState::Maximized
+        } else {
+            ShowState::Normal
+        }
+    }
+}
+
+/// Minimizes, maximizes or restores the window unless it is already shown
+/// that way. A maximized window has to be restored before it can be moved.
+pub fn set_show_state(hwnd: HWND, state: ShowState) {
+    if show_state(hwnd) == state {
+        return;
+    }
+    let command = match state {
+        ShowState::Normal => SW_RESTORE,
+        ShowState::Maximized => SW_MAXIMIZE,
+        ShowState::Minimized => SW_MINIMIZE,
+    };
+    unsafe {
+        ShowWindow(hwnd, command);
+    }
+}
+
+/// Renames the window. Apps that keep their title up to date, e.g. a shell
+/// showing the current directory, may change it back.
+pub fn set_window_title(hwnd: HWND, title: &str) -> Result<(), String> {
+    let title = U16CString::from_str(title).map_err(|e| e.to_string())?;
+    if unsafe { SetWindowTextW(hwnd, title.as_ptr()) } == 0 {
+        return Err(format!(
+            "Failed to set the window title: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+/// The window the user is working in, if any
+pub fn foreground_window() -> Option<HWND> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    (!hwnd.is_null()).then_some(hwnd)
+}
+
+/// Gives focus back to `hwnd`, e.g. after launched apps took it. Windows
+/// only allows this while our process may set the foreground window.
+pub fn set_foreground_window(hwnd: HWND) -> bool {
+    unsafe { IsWindow(hwnd) != 0 && SetForegroundWindow(hwnd) != 0 }
+}
+
+/// Returns the window's current outer rectangle in virtual-desktop coordinates
+pub fn get_window_rect(hwnd: HWND) -> Option<Rect> {
+    let mut rect = RECT {
+        left: 0,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+    if unsafe { GetWindowRect(hwnd, &mut rect) } != 0 {
+        Some(rect.into())
+    } else {
+        None
+    }
+}
+
+/// Size the window has when it is neither minimized nor maximized
+pub fn normal_size(hwnd: HWND) -> Option<(i32, i32)> {
+    use winapi::um::winuser::{GetWindowPlacement, WINDOWPLACEMENT};
+
+    let mut placement: WINDOWPLACEMENT = unsafe { std::mem::zeroed() };
+    placement.length = std::mem::size_of::<WINDOWPLACEMENT>() as u32;
+    if unsafe { GetWindowPlacement(hwnd, &mut placement) } == 0 {
+        return None;
+    }
+    let rect = placement.rcNormalPosition;
+    Some((rect.right - rect.left, rect.bottom - rect.top))
+}
+
+/// The window's visible frame, without the invisible resize borders that
+/// `get_window_rect` includes
+pub fn visible_frame(hwnd: HWND) -> Option<Rect> {
+    use winapi::um::dwmapi::{DWMWA_EXTENDED_FRAME_BOUNDS, DwmGetWindowAttribute};
+
+    let mut rect = RECT {
+        left: 0,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+    let result = unsafe {
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rect as *mut RECT as *mut _,
+            std::mem::size_of::<RECT>() as DWORD,
+        )
+    };
+    (result == 0).then(|| rect.into())
+}
+
+/// Moves the window again so its visible frame, rather than its invisible
+/// resize borders, fills `rect`, and returns where the window itself went.
+/// The borders are measured where the window already is, since their width
+/// depends on the monitor's DPI.
+pub fn align_visible_frame(hwnd: HWND, rect: Rect) -> Result<Rect, String> {
+    let (Some(window), Some(frame)) = (get_window_rect(hwnd), visible_frame(hwnd)) else {
+        return Err("Could not read the window's visible frame".to_string());
+    };
+    let outer = rect.outset_by_borders(window, frame);
+    if outer != window {
+        position_window(hwnd, outer.x, outer.y, outer.width, outer.height, false)?;
+    }
+    Ok(outer)
+}
+
+/// Whether the handle still refers to an existing window
+pub fn is_window(hwnd: HWND) -> bool {
+    unsafe { IsWindow(hwnd) != 0 }
+}
+
+pub fn is_window_visible(hwnd: HWND) -> bool {
+    unsafe { IsWindowVisible(hwnd) != 0 }
+}
+
+/// What the window can be recognized by in a later run; `title` is the
+/// title it was found with
+pub fn window_identity(hwnd: HWND, title: &str) -> WindowIdentity {
+    let mut process_id: DWORD = 0;
+    unsafe {
+        GetWindowThreadProcessId(hwnd, &mut process_id);
+    }
+    WindowIdentity {
+        class: class_name(hwnd),
+        executable: process_image_path(process_id),
+        title: title.to_string(),
+    }
+}
+
+/// Full path of the executable running as `process_id`
+pub fn process_image_path(process_id: u32) -> Option<String> {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::winbase::QueryFullProcessImageNameW;
+    use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
+
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id) };
+    if process.is_null() {
+        return None;
+    }
+    let mut buffer = vec![0u16; 32_768];
+    let mut length = buffer.len() as DWORD;
+    let found =
+        unsafe { QueryFullProcessImageNameW(process, 0, buffer.as_mut_ptr(), &mut length) } != 0;
+    unsafe {
+        CloseHandle(process);
+    }
+    found.then(|| String::from_utf16_lossy(&buffer[..length as usize]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::ptr;
 
+    #[test]
+    fn test_interpolate_steps() {
+        let steps = interpolate_steps((0, 0, 800, 600), (1000, -100, 960, 1040), 4);
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[0], (250, -25, 840, 710));
+        assert_eq!(steps[3], (1000, -100, 960, 1040));
+
+        // Zero steps still ends at the target
+        assert_eq!(
+            interpolate_steps((0, 0, 1, 1), (5, 5, 5, 5), 0),
+            vec![(5, 5, 5, 5)]
+        );
+    }
+
     #[test]
     fn test_find_window_by_title_case_insensitive() {
         // This test is difficult to implement without actual windows
         // We'll test the case insensitive logic with a mock scenario
 
         // Test that the function handles empty strings
-        let _result = find_window_by_title("");
+        let _result = find_window(&MatchCriteria::title(""), &IgnoreList::default());
         // We can't guarantee the result, but the function should not panic
         // It will return None if no window matches
     }
@@ -140,7 +577,7 @@ mod tests {
     #[test]
     fn test_position_window_invalid_handle() {
         // Test with an invalid handle
-        let result = position_window(ptr::null_mut(), 0, 0, 100, 100);
+        let result = position_window(ptr::null_mut(), 0, 0, 100, 100, true);
         // This should return an error
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Failed to position window");